@@ -1,3 +1,6 @@
 fn main() {
-    embuild::espidf::sysenv::output();
+    /* ESP-IDF environment is needed only when building firmware, host build runs tests */
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("espidf") {
+        embuild::espidf::sysenv::output();
+    }
 }
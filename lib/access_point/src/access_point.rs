@@ -1,7 +1,7 @@
 use std::net::Ipv4Addr;
 use esp_idf_svc::eventloop::{EspEventLoop, EspSystemEventLoop, System};
 use esp_idf_svc::hal::modem;
-use esp_idf_svc::sys::EspError;
+use esp_idf_svc::sys::{EspError, ESP_ERR_INVALID_ARG};
 use esp_idf_svc::wifi::{AccessPointConfiguration, AuthMethod, Configuration, EspWifi};
 
 pub struct AccessPoint<'a> {
@@ -51,4 +51,49 @@ impl<'a> AccessPoint<'a> {
     pub fn get_configuration(&self) -> &AccessPointConfiguration {
         &self.configuration
     }
+
+    pub fn get_ssid(&self) -> &str {
+        self.configuration.ssid.as_str()
+    }
+
+    /**
+     * Connected clients are disconnected when access point is running.
+     */
+    pub fn set_ssid(&mut self, ssid: &str) -> Result<(), EspError> {
+        let mut configuration: AccessPointConfiguration = self.configuration.clone();
+        configuration.ssid = ssid
+            .try_into()
+            .map_err(|_| EspError::from_infallible::<ESP_ERR_INVALID_ARG>())?;
+
+        self.apply_configuration(configuration)
+    }
+
+    /**
+     * Connected clients are disconnected when access point is running and have to reconnect with new password.
+     */
+    pub fn set_password(&mut self, password: &str) -> Result<(), EspError> {
+        let mut configuration: AccessPointConfiguration = self.configuration.clone();
+        configuration.password = password
+            .try_into()
+            .map_err(|_| EspError::from_infallible::<ESP_ERR_INVALID_ARG>())?;
+
+        self.apply_configuration(configuration)
+    }
+
+    fn apply_configuration(&mut self, configuration: AccessPointConfiguration) -> Result<(), EspError> {
+        let is_started: bool = self.wifi.is_started()?;
+
+        if is_started {
+            self.wifi.stop()?;
+        }
+
+        self.wifi.set_configuration(&Configuration::AccessPoint(configuration.clone()))?;
+        self.configuration = configuration;
+
+        if is_started {
+            self.wifi.start()?;
+        }
+
+        Ok(())
+    }
 }
@@ -1 +1,5 @@
-pub mod access_point;
\ No newline at end of file
+#[cfg(target_os = "espidf")]
+pub mod access_point;
+#[cfg(not(target_os = "espidf"))]
+#[path = "mock_access_point.rs"]
+pub mod access_point;
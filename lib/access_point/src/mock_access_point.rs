@@ -0,0 +1,87 @@
+use interface::EspError;
+use std::marker::PhantomData;
+use std::net::Ipv4Addr;
+
+/* ESP_ERR_INVALID_ARG */
+const ESP_ERR_INVALID_ARG: i32 = 0x102;
+
+/* limits of ESP-IDF access point configuration */
+const SSID_MAX_LENGTH: usize = 32;
+const PASSWORD_MAX_LENGTH: usize = 64;
+
+/* default address of ESP-IDF access point interface */
+const ACCESS_POINT_IPV4: Ipv4Addr = Ipv4Addr::new(192, 168, 71, 1);
+
+/**
+ * Access point of host build. Configuration is kept in memory and validated with limits of ESP-IDF.
+ */
+pub struct AccessPoint<'a> {
+    ssid: String,
+    password: String,
+    is_started: bool,
+    _modem: PhantomData<&'a ()>,
+}
+
+impl<'a> AccessPoint<'a> {
+    pub fn new(ssid: &str, password: &str) -> Result<Self, EspError> {
+        let mut this: Self = Self {
+            ssid: String::new(),
+            password: String::new(),
+            is_started: false,
+            _modem: PhantomData,
+        };
+
+        this.set_ssid(ssid)?;
+        this.set_password(password)?;
+
+        Ok(this)
+    }
+
+    pub fn is_started(&self) -> Result<bool, EspError> {
+        Ok(self.is_started)
+    }
+
+    pub fn start(&mut self) -> Result<(), EspError> {
+        self.is_started = true;
+
+        Ok(())
+    }
+
+    pub fn stop(&mut self) -> Result<(), EspError> {
+        self.is_started = false;
+
+        Ok(())
+    }
+
+    pub fn get_ipv4(&self) -> Result<Ipv4Addr, EspError> {
+        Ok(ACCESS_POINT_IPV4)
+    }
+
+    pub fn get_ssid(&self) -> &str {
+        self.ssid.as_str()
+    }
+
+    pub fn get_password(&self) -> &str {
+        self.password.as_str()
+    }
+
+    pub fn set_ssid(&mut self, ssid: &str) -> Result<(), EspError> {
+        if ssid.len() > SSID_MAX_LENGTH {
+            return Err(EspError::from_infallible::<ESP_ERR_INVALID_ARG>());
+        }
+
+        self.ssid = ssid.to_string();
+
+        Ok(())
+    }
+
+    pub fn set_password(&mut self, password: &str) -> Result<(), EspError> {
+        if password.len() > PASSWORD_MAX_LENGTH {
+            return Err(EspError::from_infallible::<ESP_ERR_INVALID_ARG>());
+        }
+
+        self.password = password.to_string();
+
+        Ok(())
+    }
+}
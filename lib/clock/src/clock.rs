@@ -1,67 +1,113 @@
 use crate::alarm::Alarm;
+#[cfg(not(target_os = "espidf"))]
+use crate::mock_rtc::MockRtc;
+#[cfg(not(target_os = "espidf"))]
+use crate::system_time::MockSystemTime;
 use crate::system_time::SystemTime;
 use chrono::{DateTime, NaiveDateTime, Timelike, Utc};
+#[cfg(target_os = "espidf")]
 use ds323x::interface::I2cInterface;
+#[cfg(target_os = "espidf")]
 use ds323x::{ic, DateTimeAccess, Ds323x};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::i2c::{I2cDriver};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::systime::EspSystemTime;
 use interface::clock::{ReadClock, WriteClock};
 use interface::ClockError;
+#[cfg(target_os = "espidf")]
 use shared_bus::I2cProxy;
 use std::collections::HashMap;
 use std::hash::Hash;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, RwLock, RwLockWriteGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(target_os = "espidf")]
+use std::sync::Mutex;
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
 
+#[cfg(target_os = "espidf")]
 type I2cSharedProxy<'a> = I2cProxy<'a, Mutex<I2cDriver<'a>>>;
+#[cfg(target_os = "espidf")]
 type Driver<'a> = Ds323x<I2cInterface<I2cSharedProxy<'a>>, ic::DS3231>;
+#[cfg(target_os = "espidf")]
+type Time = EspSystemTime;
+
+#[cfg(not(target_os = "espidf"))]
+type Driver<'a> = MockRtc;
+#[cfg(not(target_os = "espidf"))]
+type Time = MockSystemTime;
+
+/**
+ * Connection to external RTC: shared I2C bus on ESP32, in-memory RTC on host.
+ */
+#[cfg(target_os = "espidf")]
+pub type Rtc<'a> = I2cSharedProxy<'a>;
+#[cfg(not(target_os = "espidf"))]
+pub type Rtc<'a> = MockRtc;
 
 type Alarms<AlarmId> = HashMap<AlarmId, Alarm>;
 
 
 struct Api {
     rtc_driver: Driver<'static>,
-    system_time: EspSystemTime,
+    system_time: Time,
+}
+
+#[cfg(target_os = "espidf")]
+fn new_api(rtc: Rtc<'static>) -> Api {
+    Api {
+        rtc_driver: Ds323x::new_ds3231(rtc),
+        system_time: EspSystemTime,
+    }
+}
+
+#[cfg(not(target_os = "espidf"))]
+fn new_api(rtc: Rtc<'static>) -> Api {
+    Api {
+        rtc_driver: rtc,
+        system_time: MockSystemTime::default(),
+    }
 }
 
 pub struct Clock<AlarmId> {
     api: Arc<RwLock<Api>>,
     alarms: Arc<RwLock<Alarms<AlarmId>>>,
     shutdown: Arc<RwLock<AtomicBool>>,
+    /* Interval can be changed while alarm matching thread is running. */
+    alarm_match_check_interval_ms: Arc<AtomicU64>,
 }
 
 impl<AlarmId> Clock<AlarmId>
 where AlarmId: Eq + Hash + Send + Sync + Clone + 'static {
-    pub fn new<OnSynchronize, OnAlarm>(i2c_shared_proxy: I2cSharedProxy<'static>,
+    pub fn new<OnSynchronize, OnAlarm>(rtc: Rtc<'static>,
                                        on_synchronize: OnSynchronize,
                                        on_alarm: OnAlarm,
                                        alarm_match_check_interval_ms: u64,) -> Result<Self, ClockError>
     where OnSynchronize: Fn(Result<(), ClockError>) + Send + 'static,
           OnAlarm: Fn(&AlarmId, &Alarm, &DateTime<Utc>) + Send + 'static, {
 
-        let driver: Driver = Ds323x::new_ds3231(i2c_shared_proxy);
-
-        let mut api = Api {
-            rtc_driver: driver,
-            system_time: EspSystemTime,
-        };
+        let mut api: Api = new_api(rtc);
 
         Clock::<AlarmId>::synchronize_datetime(&mut api)?;
 
         let mut this: Self = Self {
             api: Arc::new(RwLock::new(api)),
             alarms: Arc::new(RwLock::new(HashMap::new())),
-            shutdown: Arc::new(RwLock::new(AtomicBool::new(false)))
+            shutdown: Arc::new(RwLock::new(AtomicBool::new(false))),
+            alarm_match_check_interval_ms: Arc::new(AtomicU64::new(alarm_match_check_interval_ms)),
         };
 
-        this.start_alarm_matching(on_synchronize, on_alarm, alarm_match_check_interval_ms);
+        this.start_alarm_matching(on_synchronize, on_alarm);
 
         Ok(this)
     }
 
+    pub fn set_alarm_match_check_interval(&self, alarm_match_check_interval_ms: u64) {
+        self.alarm_match_check_interval_ms.store(alarm_match_check_interval_ms, Ordering::SeqCst);
+    }
+
     pub fn is_alarm_id_unique(&self, id: &AlarmId) -> Result<bool, ClockError> {
         let contains: bool = self
             .alarms
@@ -93,7 +139,7 @@ where AlarmId: Eq + Hash + Send + Sync + Clone + 'static {
             .read()
             .map_err(|_| ClockError::MutexLockError)?
             .get(id)
-            .map(Clone::clone)
+            .cloned()
             .ok_or(ClockError::AlarmNotFound)
     }
 
@@ -150,52 +196,61 @@ where AlarmId: Eq + Hash + Send + Sync + Clone + 'static {
 
     fn start_alarm_matching<OnSynchronize, OnAlarm>(&mut self,
                                                     on_synchronize: OnSynchronize,
-                                                    on_alarm: OnAlarm,
-                                                    alarm_match_check_interval_ms: u64) -> JoinHandle<()>
+                                                    on_alarm: OnAlarm) -> JoinHandle<()>
     where OnSynchronize: Fn(Result<(), ClockError>) + Send + 'static,
           OnAlarm: Fn(&AlarmId, &Alarm, &DateTime<Utc>) + Send + 'static, {
 
         let api_lock: Arc<RwLock<Api>> = Arc::clone(&self.api);
         let alarms_lock: Arc<RwLock<Alarms<AlarmId>>> = Arc::clone(&self.alarms);
         let shutdown_lock: Arc<RwLock<AtomicBool>> = Arc::clone(&self.shutdown);
-
-        thread::spawn(move || loop {
-            /* lock(read) api to read current time */
-            let datetime: Option<DateTime<Utc>> = 
-                api_lock
-                    .read()
-                    .map_or(None, |api| {
-                        let seconds: u64 = api.system_time.get_time().as_secs();
-                        DateTime::from_timestamp(seconds as i64, 0)                
-                    });
-
-            let Some(datetime) = datetime else {
-                continue;
-            };
-
-            /* check matching alarms */
-            if let Ok(alarms) = alarms_lock.read() {
-                alarms
-                    .iter()
-                    .filter(|(_, alarm)| alarm.matches(&datetime))
-                    .for_each(|(id, alarm)| on_alarm(id, alarm, &datetime));
-            }
-
-            /* lock(write) api to synchronize time every hour */
-            if datetime.minute() == 0 && datetime.second() == 0 {
-                if let Ok(mut api) = api_lock.write() {
-                    let result: Result<(), ClockError> = Clock::<AlarmId>::synchronize_datetime(&mut *api);
-                    on_synchronize(result);
+        let alarm_match_check_interval_ms: Arc<AtomicU64> = Arc::clone(&self.alarm_match_check_interval_ms);
+
+        thread::spawn(move || {
+            /* alarm matching is checked more than once per second, but each second should trigger alarms only once */
+            let mut last_matched_datetime: Option<DateTime<Utc>> = None;
+
+            loop {
+                /* lock(read) api to read current time */
+                let datetime: Option<DateTime<Utc>> = 
+                    api_lock
+                        .read()
+                        .map_or(None, |api| {
+                            let seconds: u64 = api.system_time.get_time().as_secs();
+                            DateTime::from_timestamp(seconds as i64, 0)                
+                        });
+
+                let Some(datetime) = datetime else {
+                    continue;
+                };
+
+                /* check matching alarms */
+                if last_matched_datetime != Some(datetime) {
+                    last_matched_datetime = Some(datetime);
+
+                    if let Ok(alarms) = alarms_lock.read() {
+                        alarms
+                            .iter()
+                            .filter(|(_, alarm)| alarm.matches(&datetime))
+                            .for_each(|(id, alarm)| on_alarm(id, alarm, &datetime));
+                    }
                 }
-            };
 
-            if let Ok(shutdown) = shutdown_lock.read() {
-                if shutdown.load(Ordering::SeqCst) {
-                    break;
+                /* lock(write) api to synchronize time every hour */
+                if datetime.minute() == 0 && datetime.second() == 0 {
+                    if let Ok(mut api) = api_lock.write() {
+                        let result: Result<(), ClockError> = Clock::<AlarmId>::synchronize_datetime(&mut api);
+                        on_synchronize(result);
+                    }
+                };
+
+                if let Ok(shutdown) = shutdown_lock.read() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
                 }
-            }
 
-            thread::sleep(Duration::from_millis(alarm_match_check_interval_ms));
+                thread::sleep(Duration::from_millis(alarm_match_check_interval_ms.load(Ordering::SeqCst)));
+            }
         })
     }
 
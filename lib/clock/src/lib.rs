@@ -1,4 +1,5 @@
 pub mod clock;
 pub mod alarm;
-
-mod system_time;
+pub mod system_time;
+#[cfg(not(target_os = "espidf"))]
+pub mod mock_rtc;
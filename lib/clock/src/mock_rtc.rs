@@ -0,0 +1,37 @@
+use crate::system_time::{MockSystemTime, SystemTime};
+use chrono::{DateTime, NaiveDateTime};
+use interface::ClockError;
+use std::time::Duration;
+
+/**
+ * External RTC of host build. It counts time from host clock and keeps datetime which was set.
+ */
+#[derive(Default)]
+pub struct MockRtc {
+    time: MockSystemTime,
+}
+
+impl MockRtc {
+    pub fn new(datetime: NaiveDateTime) -> Self {
+        let mut this: Self = Self::default();
+        let _ = this.set_datetime(&datetime);
+
+        this
+    }
+
+    pub fn datetime(&mut self) -> Result<NaiveDateTime, ClockError> {
+        let seconds: u64 = self.time.get_time().as_secs();
+
+        DateTime::from_timestamp(seconds as i64, 0)
+            .map(|datetime| datetime.naive_utc())
+            .ok_or(ClockError::InvalidTimestamp(seconds))
+    }
+
+    pub fn set_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), ClockError> {
+        let timestamp: u64 = u64::try_from(datetime.and_utc().timestamp())
+            .map_err(|_| ClockError::SynchronizationError)?;
+        self.time.set_time(Duration::from_secs(timestamp));
+
+        Ok(())
+    }
+}
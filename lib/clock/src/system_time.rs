@@ -1,4 +1,6 @@
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::sys::{settimeofday, time_t, timeval, timezone};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::systime::EspSystemTime;
 use std::time::Duration;
 
@@ -7,6 +9,22 @@ pub trait SystemTime {
     fn set_time(&mut self, duration: Duration);
 }
 
+/**
+ * Current system time since UNIX epoch.
+ */
+#[cfg(target_os = "espidf")]
+pub fn now() -> Duration {
+    EspSystemTime.now()
+}
+
+#[cfg(not(target_os = "espidf"))]
+pub fn now() -> Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "espidf")]
 impl SystemTime for EspSystemTime {
     fn get_time(&self) -> Duration {
         self.now()
@@ -28,3 +46,23 @@ impl SystemTime for EspSystemTime {
         }
     }
 }
+
+/**
+ * System time of host build. Time of host can't be set, so offset from it is kept instead.
+ */
+#[cfg(not(target_os = "espidf"))]
+#[derive(Default)]
+pub struct MockSystemTime {
+    offset_seconds: i64,
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl SystemTime for MockSystemTime {
+    fn get_time(&self) -> Duration {
+        Duration::from_secs(now().as_secs().saturating_add_signed(self.offset_seconds))
+    }
+
+    fn set_time(&mut self, duration: Duration) {
+        self.offset_seconds = duration.as_secs() as i64 - now().as_secs() as i64;
+    }
+}
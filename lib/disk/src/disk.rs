@@ -1,9 +1,20 @@
-use embedded_sdmmc::{DirEntry, Error, Mode, SdCard, ShortFileName, TimeSource, Timestamp, VolumeIdx};
+#[cfg(not(target_os = "espidf"))]
+use crate::mock_block_device::MockBlockDevice;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use embedded_sdmmc::{DirEntry, Error, Mode, ShortFileName, TimeSource, Timestamp, VolumeIdx};
+#[cfg(target_os = "espidf")]
+use embedded_sdmmc::SdCard;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::delay::FreeRtos;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::gpio::OutputPin;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::peripheral::Peripheral;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::spi::config::Duplex;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::spi::{SpiConfig, SpiDeviceDriver, SpiDriver};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::sys::EspError;
 use interface::disk::path::directory_path::DirectoryPath;
 use interface::disk::path::file_path::FilePath;
@@ -14,7 +25,10 @@ const MAX_FILES: usize = 16;
 const MAX_VOLUMES: usize = 1;
 
 
+#[cfg(target_os = "espidf")]
 type BlockDevice<'spi> = SdCard<SpiDeviceDriver<'spi, SpiDriver<'spi>>, FreeRtos>;
+#[cfg(not(target_os = "espidf"))]
+type BlockDevice<'spi> = MockBlockDevice<'spi>;
 type VolumeManager<'spi> = embedded_sdmmc::VolumeManager<BlockDevice<'spi>, SdMmcClock, MAX_DIRS, MAX_FILES, MAX_VOLUMES>;
 type Volume<'spi, 'vol> = embedded_sdmmc::Volume<'vol, BlockDevice<'spi>, SdMmcClock, MAX_DIRS, MAX_FILES, MAX_VOLUMES>;
 type Directory<'spi, 'vol> = embedded_sdmmc::Directory<'vol, BlockDevice<'spi>, SdMmcClock, MAX_DIRS, MAX_FILES, MAX_VOLUMES>;
@@ -23,16 +37,25 @@ type File<'spi, 'vol> = embedded_sdmmc::File<'vol, BlockDevice<'spi>, SdMmcClock
 
 pub struct SdMmcClock;
 
+/**
+ * Files are stamped with system time in UTC, which is used for Last-Modified of web interface files.
+ * FAT can't store dates before 1980, so zero timestamp is used until clock is set.
+ */
 impl TimeSource for SdMmcClock {
     fn get_timestamp(&self) -> Timestamp {
-        Timestamp {
-            year_since_1970: 0,
-            zero_indexed_month: 0,
-            zero_indexed_day: 0,
-            hours: 0,
-            minutes: 0,
-            seconds: 0,
-        }
+        let now: DateTime<Utc> = Utc::now();
+
+        Timestamp::from_calendar(now.year() as u16, now.month() as u8, now.day() as u8, now.hour() as u8, now.minute() as u8, now.second() as u8)
+            .ok()
+            .filter(|_| now.year() >= 1980)
+            .unwrap_or(Timestamp {
+                year_since_1970: 0,
+                zero_indexed_month: 0,
+                zero_indexed_day: 0,
+                hours: 0,
+                minutes: 0,
+                seconds: 0,
+            })
     }
 }
 
@@ -42,6 +65,7 @@ pub struct Disk<'spi> {
 }
 
 impl<'spi> Disk<'spi> {
+    #[cfg(target_os = "espidf")]
     pub fn new<CS: Peripheral<P = impl OutputPin> + 'spi>(spi_driver: SpiDriver<'spi>, cs: CS) -> Result<Self, EspError> {
         let mut spi_config = SpiConfig::new();
         spi_config.duplex = Duplex::Full;
@@ -55,6 +79,16 @@ impl<'spi> Disk<'spi> {
         Ok(Self { volume_manager })
     }
 
+    /**
+     * Disk of host build. It is blank FAT16 volume kept in memory.
+     */
+    #[cfg(not(target_os = "espidf"))]
+    pub fn new_in_memory() -> Self {
+        let volume_manager = VolumeManager::new_with_limits(MockBlockDevice::new(), SdMmcClock, 5000);
+
+        Self { volume_manager }
+    }
+
     pub fn list_dir(&mut self, path: &DirectoryPath) -> DiskResult<Vec<String>> {
         let mut volume: Volume = self.volume_manager.open_volume(VolumeIdx(0))?;
         let mut directory: Directory = volume.open_root_dir()?;
@@ -178,6 +212,64 @@ impl<'spi> Disk<'spi> {
 
         Ok(())
     }
+
+    pub fn file_length(&mut self, path: &FilePath) -> DiskResult<u32> {
+        let mut volume: Volume = self.volume_manager.open_volume(VolumeIdx(0))?;
+        let mut directory: Directory = volume.open_root_dir()?;
+
+        for dir in &path.directories_path {
+            directory.change_dir(dir.as_str())?;
+        }
+
+        let file: File = directory.open_file_in_dir(path.filename.as_str(), Mode::ReadOnly)?;
+
+        Ok(file.length())
+    }
+
+    /**
+     * Directory entry of file, which has its length and modification time.
+     */
+    pub fn file_info(&mut self, path: &FilePath) -> DiskResult<DirEntry> {
+        let mut volume: Volume = self.volume_manager.open_volume(VolumeIdx(0))?;
+        let mut directory: Directory = volume.open_root_dir()?;
+
+        for dir in &path.directories_path {
+            directory.change_dir(dir.as_str())?;
+        }
+
+        directory.find_directory_entry(path.filename.as_str())
+    }
+
+    /**
+     * Reads `length` bytes from `offset` in chunks of `buffer_size` bytes. Reading stops when `on_read` fails.
+     */
+    pub fn read_from_file_range<OnRead: FnMut(&[u8]) -> Result<(), ()>>(&mut self, path: &FilePath, offset: u32, length: u32, buffer_size: usize, mut on_read: OnRead) -> DiskResult<()> {
+        let mut volume: Volume = self.volume_manager.open_volume(VolumeIdx(0))?;
+        let mut directory: Directory = volume.open_root_dir()?;
+
+        for dir in &path.directories_path {
+            directory.change_dir(dir.as_str())?;
+        }
+
+        let mut file: File = directory.open_file_in_dir(path.filename.as_str(), Mode::ReadOnly)?;
+        file.seek_from_start(offset)?;
+
+        let mut buffer: Vec<u8> = vec![0; buffer_size];
+        let mut remaining: usize = length as usize;
+
+        while remaining > 0 && !file.is_eof() {
+            let chunk_size: usize = remaining.min(buffer_size);
+            let bytes_read: usize = file.read(&mut buffer[..chunk_size])?;
+
+            if bytes_read == 0 || on_read(&buffer[..bytes_read]).is_err() {
+                break;
+            }
+
+            remaining -= bytes_read;
+        }
+
+        Ok(())
+    }
 }
 
 impl<'spi> ReadDisk for Disk<'spi> {
@@ -239,7 +331,22 @@ impl<'spi> WriteDisk for Disk<'spi> {
             directory.change_dir(dir.as_str())?;
         }
 
-        let mut file: File = directory.open_file_in_dir(path.filename.as_str(), Mode::ReadWriteCreate)?;
+        let mut file: File = directory.open_file_in_dir(path.filename.as_str(), Mode::ReadWriteCreateOrTruncate)?;
+
+        file.write(data_buffer)?;
+
+        Ok(())
+    }
+
+    fn append_to_file(&mut self, path: &FilePath, data_buffer: &[u8]) -> DiskResult<()> {
+        let mut volume: Volume = self.volume_manager.open_volume(VolumeIdx(0))?;
+        let mut directory: Directory = volume.open_root_dir()?;
+
+        for dir in &path.directories_path {
+            directory.change_dir(dir.as_str())?;
+        }
+
+        let mut file: File = directory.open_file_in_dir(path.filename.as_str(), Mode::ReadWriteCreateOrAppend)?;
 
         file.write(data_buffer)?;
 
@@ -1 +1,3 @@
 pub mod disk;
+#[cfg(not(target_os = "espidf"))]
+pub mod mock_block_device;
@@ -0,0 +1,145 @@
+use embedded_sdmmc::{Block, BlockCount, BlockDevice, BlockIdx, SdCardError};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/* FAT16 needs at least 4085 clusters, volume has one block per cluster */
+const VOLUME_BLOCKS: u32 = 8192;
+const VOLUME_START_BLOCK: u32 = 1;
+const FAT_BLOCKS: u16 = 32;
+const ROOT_ENTRIES: u16 = 512;
+
+const BOOT_SIGNATURE_OFFSET: usize = 510;
+const PARTITION_OFFSET: usize = 446;
+const PARTITION_ID_FAT16: u8 = 0x06;
+const MEDIA_FIXED_DISK: u8 = 0xF8;
+
+/**
+ * SD card of host build. Blank FAT16 volume is kept in memory. Only blocks which were written are stored,
+ * the other ones are read as zeros.
+ */
+pub struct MockBlockDevice<'a> {
+    blocks: RefCell<HashMap<u32, Block>>,
+    _spi: PhantomData<&'a ()>,
+}
+
+impl<'a> MockBlockDevice<'a> {
+    pub fn new() -> Self {
+        let blocks: HashMap<u32, Block> = [
+            (0, MockBlockDevice::master_boot_record()),
+            (VOLUME_START_BLOCK, MockBlockDevice::boot_sector()),
+            (VOLUME_START_BLOCK + 1, MockBlockDevice::first_fat_block()),
+            (VOLUME_START_BLOCK + 1 + FAT_BLOCKS as u32, MockBlockDevice::first_fat_block()),
+        ]
+        .into_iter()
+        .collect();
+
+        Self {
+            blocks: RefCell::new(blocks),
+            _spi: PhantomData,
+        }
+    }
+
+    fn master_boot_record() -> Block {
+        let mut block: Block = Block::new();
+        let partition: &mut [u8] = &mut block.contents[PARTITION_OFFSET..PARTITION_OFFSET + 16];
+
+        partition[4] = PARTITION_ID_FAT16;
+        partition[8..12].copy_from_slice(&VOLUME_START_BLOCK.to_le_bytes());
+        partition[12..16].copy_from_slice(&VOLUME_BLOCKS.to_le_bytes());
+        MockBlockDevice::sign(&mut block);
+
+        block
+    }
+
+    fn boot_sector() -> Block {
+        let mut block: Block = Block::new();
+        let contents: &mut [u8; Block::LEN] = &mut block.contents;
+
+        contents[0..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+        contents[3..11].copy_from_slice(b"MOCKDISK");
+        contents[11..13].copy_from_slice(&(Block::LEN as u16).to_le_bytes());
+        /* blocks per cluster */
+        contents[13] = 1;
+        /* reserved blocks */
+        contents[14..16].copy_from_slice(&1u16.to_le_bytes());
+        /* number of FATs */
+        contents[16] = 2;
+        contents[17..19].copy_from_slice(&ROOT_ENTRIES.to_le_bytes());
+        contents[19..21].copy_from_slice(&(VOLUME_BLOCKS as u16).to_le_bytes());
+        contents[21] = MEDIA_FIXED_DISK;
+        contents[22..24].copy_from_slice(&FAT_BLOCKS.to_le_bytes());
+        /* extended boot signature, so volume label is valid */
+        contents[38] = 0x29;
+        contents[43..54].copy_from_slice(b"NO NAME    ");
+        contents[54..62].copy_from_slice(b"FAT16   ");
+        MockBlockDevice::sign(&mut block);
+
+        block
+    }
+
+    /**
+     * First two FAT entries are reserved: media type and end of chain marker.
+     */
+    fn first_fat_block() -> Block {
+        let mut block: Block = Block::new();
+        block.contents[0..4].copy_from_slice(&[MEDIA_FIXED_DISK, 0xFF, 0xFF, 0xFF]);
+
+        block
+    }
+
+    fn sign(block: &mut Block) {
+        block.contents[BOOT_SIGNATURE_OFFSET..BOOT_SIGNATURE_OFFSET + 2].copy_from_slice(&[0x55, 0xAA]);
+    }
+
+    fn check_range(start_block_idx: BlockIdx, count: usize) -> Result<(), SdCardError> {
+        let end: u64 = start_block_idx.0 as u64 + count as u64;
+
+        if end > (VOLUME_START_BLOCK + VOLUME_BLOCKS) as u64 {
+            return Err(SdCardError::BadState);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for MockBlockDevice<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockDevice for MockBlockDevice<'_> {
+    type Error = SdCardError;
+
+    fn read(&self, blocks: &mut [Block], start_block_idx: BlockIdx, _reason: &str) -> Result<(), Self::Error> {
+        MockBlockDevice::check_range(start_block_idx, blocks.len())?;
+
+        let stored_blocks = self.blocks.borrow();
+
+        for (index, block) in (start_block_idx.0..).zip(blocks.iter_mut()) {
+            *block = stored_blocks
+                .get(&index)
+                .cloned()
+                .unwrap_or_else(Block::new);
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, blocks: &[Block], start_block_idx: BlockIdx) -> Result<(), Self::Error> {
+        MockBlockDevice::check_range(start_block_idx, blocks.len())?;
+
+        let mut stored_blocks = self.blocks.borrow_mut();
+
+        for (index, block) in (start_block_idx.0..).zip(blocks.iter()) {
+            stored_blocks.insert(index, block.clone());
+        }
+
+        Ok(())
+    }
+
+    fn num_blocks(&self) -> Result<BlockCount, Self::Error> {
+        Ok(BlockCount(VOLUME_START_BLOCK + VOLUME_BLOCKS))
+    }
+}
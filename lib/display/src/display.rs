@@ -5,7 +5,7 @@ use ssd1306::prelude::{DisplaySize128x32, I2CInterface};
 use ssd1306::rotation::DisplayRotation;
 use ssd1306::{I2CDisplayInterface, Ssd1306};
 use std::sync::Mutex;
-use display_interface::DisplayError;
+pub use display_interface::DisplayError;
 use embedded_graphics::Drawable;
 use embedded_graphics::geometry::Point;
 use embedded_graphics::mono_font::ascii::FONT_9X18_BOLD;
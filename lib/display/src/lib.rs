@@ -1,3 +1,7 @@
 extern crate core;
 
+#[cfg(target_os = "espidf")]
+pub mod display;
+#[cfg(not(target_os = "espidf"))]
+#[path = "mock_display.rs"]
 pub mod display;
@@ -0,0 +1,32 @@
+use std::marker::PhantomData;
+
+/**
+ * Error of display driver. Display of host build can't fail.
+ */
+#[derive(Debug)]
+pub enum DisplayError {}
+
+/**
+ * Display of host build. Text is kept instead of being drawn.
+ */
+pub struct Display<'a> {
+    text: String,
+    _i2c: PhantomData<&'a ()>,
+}
+
+impl<'a> Display<'a> {
+    pub fn new() -> Result<Self, DisplayError> {
+        Ok(Self { text: String::new(), _i2c: PhantomData })
+    }
+
+    pub fn write_text(&mut self, text: &str) -> Result<(), DisplayError> {
+        log::debug!("Display: {text}");
+        self.text = text.to_string();
+
+        Ok(())
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+}
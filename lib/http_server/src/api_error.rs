@@ -0,0 +1,84 @@
+use crate::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/**
+ * Body of every error response: {"code": "alarm_not_found", "message": "Alarm not found.", "details": null}.
+ * Code is stable and meant for clients, message is meant for people. Status is sent only in status line.
+ */
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    pub status: u16,
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(status: u16, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn bad_request(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(400, code, message)
+    }
+
+    pub fn unauthorized(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(401, code, message)
+    }
+
+    pub fn forbidden(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(403, code, message)
+    }
+
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(404, code, message)
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(409, code, message)
+    }
+
+    pub fn service_unavailable(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(503, code, message)
+    }
+
+    pub fn internal_server_error(code: &'static str, message: impl Into<String>) -> Self {
+        ApiError::new(500, code, message)
+    }
+
+    pub fn reason_phrase(&self) -> &'static str {
+        reason_phrase(self.status)
+    }
+}
+
+impl ToResponseData for ApiError {}
+
+pub fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        409 => "Conflict",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        429 => "Too Many Requests",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,84 @@
+use embedded_svc::http::server::{Connection, Request};
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::http::server::ws::EspHttpWsConnection;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::http::server::EspHttpConnection;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::sys::{httpd_req_to_sockfd, lwip_getpeername, sockaddr, sockaddr_in, sockaddr_in6, sockaddr_storage, socklen_t, AF_INET, AF_INET6};
+#[cfg(target_os = "espidf")]
+use std::mem::size_of;
+use std::net::IpAddr;
+#[cfg(target_os = "espidf")]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub trait ClientAddress {
+    /**
+     * IP address of connected client. IPv4 clients of IPv6 socket are returned as IPv4 addresses.
+     */
+    fn client_ip(&mut self) -> Option<IpAddr>;
+}
+
+impl<C: Connection + ClientAddress> ClientAddress for Request<C> {
+    fn client_ip(&mut self) -> Option<IpAddr> {
+        self.connection().client_ip()
+    }
+}
+
+impl<C: ClientAddress> ClientAddress for &mut C {
+    fn client_ip(&mut self) -> Option<IpAddr> {
+        (**self).client_ip()
+    }
+}
+
+#[cfg(target_os = "espidf")]
+impl ClientAddress for EspHttpConnection<'_> {
+    fn client_ip(&mut self) -> Option<IpAddr> {
+        let raw_connection = self.raw_connection().ok()?;
+
+        let socket_fd: i32 = unsafe { httpd_req_to_sockfd(raw_connection.handle()) };
+
+        socket_peer_ip(socket_fd)
+    }
+}
+
+#[cfg(target_os = "espidf")]
+impl ClientAddress for EspHttpWsConnection {
+    fn client_ip(&mut self) -> Option<IpAddr> {
+        socket_peer_ip(self.session())
+    }
+}
+
+/* peer of socket, so address is known even for WebSocket connections which have no request */
+#[cfg(target_os = "espidf")]
+fn socket_peer_ip(socket_fd: i32) -> Option<IpAddr> {
+    if socket_fd < 0 {
+        return None;
+    }
+
+    let mut address: sockaddr_storage = unsafe { std::mem::zeroed() };
+    let mut address_length: socklen_t = size_of::<sockaddr_storage>() as socklen_t;
+
+    let result: i32 = unsafe {
+        lwip_getpeername(socket_fd, &mut address as *mut sockaddr_storage as *mut sockaddr, &mut address_length)
+    };
+
+    if result != 0 {
+        return None;
+    }
+
+    match address.ss_family as u32 {
+        AF_INET => {
+            let address: &sockaddr_in = unsafe { &*(&address as *const sockaddr_storage as *const sockaddr_in) };
+
+            /* address is stored in network byte order */
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr))))
+        }
+        AF_INET6 => {
+            let address: &sockaddr_in6 = unsafe { &*(&address as *const sockaddr_storage as *const sockaddr_in6) };
+            let ipv6: Ipv6Addr = Ipv6Addr::from(unsafe { address.sin6_addr.un.u8_addr });
+
+            Some(ipv6.to_ipv4_mapped().map_or(IpAddr::V6(ipv6), IpAddr::V4))
+        }
+        _ => None,
+    }
+}
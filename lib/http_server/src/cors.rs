@@ -0,0 +1,121 @@
+use std::sync::RwLock;
+
+/* Headers which clients of API may send. */
+const ALLOWED_HEADERS: &str = "Content-Type, Authorization, Access-Token";
+const PREFLIGHT_MAX_AGE_SECONDS: &str = "600";
+
+/**
+ * Cross-origin access to the server. Empty list of origins allows only same origin requests,
+ * which is enough for web interface served by the device. "*" allows any origin.
+ */
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CorsPolicy {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allow_credentials: bool,
+}
+
+static CORS_POLICY: RwLock<CorsPolicy> = RwLock::new(CorsPolicy {
+    allowed_origins: Vec::new(),
+    allowed_methods: Vec::new(),
+    allow_credentials: false,
+});
+
+/**
+ * Policy is shared by all responses of all servers.
+ */
+pub fn set_cors_policy(cors_policy: CorsPolicy) {
+    if let Ok(mut current_cors_policy) = CORS_POLICY.write() {
+        *current_cors_policy = cors_policy;
+    }
+}
+
+pub fn get_cors_policy() -> CorsPolicy {
+    CORS_POLICY
+        .read()
+        .map(|cors_policy| cors_policy.clone())
+        .unwrap_or_default()
+}
+
+impl CorsPolicy {
+    /**
+     * Value of Access-Control-Allow-Origin for request origin. None when origin isn't allowed.
+     * Origin is echoed instead of "*" when credentials are allowed, because browsers reject "*" with credentials.
+     */
+    pub fn allowed_origin(&self, origin: &str) -> Option<String> {
+        let allows_any_origin: bool = self.allowed_origins.iter().any(|allowed_origin| allowed_origin == "*");
+
+        if allows_any_origin && !self.allow_credentials {
+            return Some("*".to_string());
+        }
+
+        if allows_any_origin || self.allowed_origins.iter().any(|allowed_origin| allowed_origin.eq_ignore_ascii_case(origin)) {
+            return Some(origin.to_string());
+        }
+
+        None
+    }
+
+    pub fn is_method_allowed(&self, method: &str) -> bool {
+        self.allowed_methods.iter().any(|allowed_method| allowed_method.eq_ignore_ascii_case(method))
+    }
+
+    /**
+     * Requests without origin and same origin requests are always allowed.
+     * Origin is the same when its host matches Host header, e.g. "https://192.168.71.1" and "192.168.71.1".
+     */
+    pub fn is_request_allowed(&self, origin: Option<&str>, host: Option<&str>, method: &str) -> bool {
+        let Some(origin) = origin else {
+            return true;
+        };
+
+        let origin_host: Option<&str> = origin
+            .strip_prefix("https://")
+            .or_else(|| origin.strip_prefix("http://"));
+
+        if origin_host.is_some_and(|origin_host| host.is_some_and(|host| origin_host.eq_ignore_ascii_case(host))) {
+            return true;
+        }
+
+        self.allowed_origin(origin).is_some() && self.is_method_allowed(method)
+    }
+
+    /**
+     * Headers added to regular responses. No headers are added for same origin requests and not allowed origins.
+     */
+    pub fn response_headers(&self, origin: Option<&str>) -> Vec<(&'static str, String)> {
+        let Some(allowed_origin) = origin.and_then(|origin| self.allowed_origin(origin)) else {
+            return vec![];
+        };
+
+        let mut headers: Vec<(&'static str, String)> = vec![
+            ("Access-Control-Allow-Origin", allowed_origin),
+            ("Vary", "Origin".to_string()),
+        ];
+
+        if self.allow_credentials {
+            headers.push(("Access-Control-Allow-Credentials", "true".to_string()));
+        }
+
+        headers
+    }
+
+    /**
+     * Headers of response to preflight OPTIONS request. None when origin or requested method isn't allowed.
+     */
+    pub fn preflight_headers(&self, origin: Option<&str>, requested_method: Option<&str>) -> Option<Vec<(&'static str, String)>> {
+        let origin: &str = origin?;
+        self.allowed_origin(origin)?;
+
+        if requested_method.is_some_and(|requested_method| !self.is_method_allowed(requested_method)) {
+            return None;
+        }
+
+        let mut headers: Vec<(&'static str, String)> = self.response_headers(Some(origin));
+        headers.push(("Access-Control-Allow-Methods", self.allowed_methods.join(", ")));
+        headers.push(("Access-Control-Allow-Headers", ALLOWED_HEADERS.to_string()));
+        headers.push(("Access-Control-Max-Age", PREFLIGHT_MAX_AGE_SECONDS.to_string()));
+
+        Some(headers)
+    }
+}
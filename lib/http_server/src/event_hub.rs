@@ -0,0 +1,283 @@
+use crate::api_error::ApiError;
+use embedded_svc::ws::{FrameType, Sender};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/**
+ * Message sent to subscriber, e.g. {"event": "alarm_fired", "data": {...}}.
+ */
+#[derive(Serialize)]
+struct EventMessage<'a, T: Serialize> {
+    event: &'a str,
+    data: &'a T,
+}
+
+/**
+ * Sent instead of messages which were dropped, so client knows it has to reload state.
+ */
+#[derive(Serialize)]
+struct LaggedDTO {
+    dropped: usize,
+}
+
+struct Subscriber<S, P> {
+    session: i32,
+    /* Sender is used outside of hub lock, because sending can wait for server task, which can call hub itself. */
+    sender: Arc<Mutex<S>>,
+    /* credentials of subscriber, so subscription can be ended when they become invalid */
+    principal: P,
+    buffer: VecDeque<Arc<str>>,
+    dropped: usize,
+}
+
+/**
+ * Connection which hasn't sent its credentials yet.
+ */
+struct PendingConnection<S> {
+    session: i32,
+    sender: S,
+    connected_at: Instant,
+}
+
+/**
+ * Messages of subscriber taken from its buffer for sending.
+ */
+struct Delivery<S> {
+    session: i32,
+    sender: Arc<Mutex<S>>,
+    messages: Vec<Arc<str>>,
+}
+
+/**
+ * Pushes events to subscribed WebSocket clients. Publishers never wait for network: every subscriber has
+ * bounded buffer and the oldest messages are dropped when it can't keep up. Buffers are sent by single delivery thread,
+ * which removes subscribers whose connection fails. Number of subscribers is limited, because every one keeps socket open.
+ * Connections which don't subscribe in time are closed, so they can't keep sockets open without credentials.
+ */
+pub struct EventHub<S: Sender, P> {
+    subscribers: Mutex<Vec<Subscriber<S, P>>>,
+    pending: Mutex<Vec<PendingConnection<S>>>,
+    published: Condvar,
+    max_subscribers: usize,
+    buffer_capacity: usize,
+}
+
+impl<S: Sender, P> EventHub<S, P> {
+    pub fn new(max_subscribers: usize, buffer_capacity: usize) -> Self {
+        Self {
+            subscribers: Mutex::new(vec![]),
+            pending: Mutex::new(vec![]),
+            published: Condvar::new(),
+            max_subscribers,
+            buffer_capacity: buffer_capacity.max(1),
+        }
+    }
+
+    /**
+     * Connection waits for its credentials. It is closed by close_unauthenticated when it doesn't subscribe in time.
+     */
+    pub fn connect(&self, session: i32, sender: S) {
+        let mut pending: MutexGuard<Vec<PendingConnection<S>>> = self.lock_pending();
+
+        pending.retain(|connection| connection.session != session);
+        pending.push(PendingConnection { session, sender, connected_at: Instant::now() });
+    }
+
+    /**
+     * Session identifies connection of subscriber. Subscribing the same session again replaces its sender.
+     * Fails with 503 when there are too many subscribers.
+     */
+    pub fn subscribe(&self, session: i32, sender: S, principal: P) -> Result<(), ApiError> {
+        self.lock_pending().retain(|connection| connection.session != session);
+
+        let mut subscribers: MutexGuard<Vec<Subscriber<S, P>>> = self.lock();
+
+        subscribers.retain(|subscriber| subscriber.session != session);
+
+        if subscribers.len() >= self.max_subscribers {
+            return Err(ApiError::service_unavailable("too_many_subscribers", format!("Only {} clients can receive live events.", self.max_subscribers)));
+        }
+
+        subscribers.push(Subscriber {
+            session,
+            sender: Arc::new(Mutex::new(sender)),
+            principal,
+            buffer: VecDeque::with_capacity(self.buffer_capacity),
+            dropped: 0,
+        });
+
+        Ok(())
+    }
+
+    pub fn unsubscribe(&self, session: i32) {
+        self.lock_pending().retain(|connection| connection.session != session);
+        self.lock().retain(|subscriber| subscriber.session != session);
+    }
+
+    pub fn is_subscribed(&self, session: i32) -> bool {
+        self.lock().iter().any(|subscriber| subscriber.session == session)
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    /**
+     * Message is serialized once and queued for every subscriber.
+     */
+    pub fn publish<T: Serialize>(&self, event: &str, data: &T) {
+        let Ok(message) = serde_json::to_string(&EventMessage { event, data }) else {
+            log::warn!("Can't serialize live event '{event}'.");
+            return;
+        };
+
+        let message: Arc<str> = Arc::from(message);
+        let mut subscribers: MutexGuard<Vec<Subscriber<S, P>>> = self.lock();
+
+        if subscribers.is_empty() {
+            return;
+        }
+
+        for subscriber in subscribers.iter_mut() {
+            if subscriber.buffer.len() >= self.buffer_capacity {
+                subscriber.buffer.pop_front();
+                subscriber.dropped += 1;
+            }
+
+            subscriber.buffer.push_back(Arc::clone(&message));
+        }
+
+        self.published.notify_all();
+    }
+
+    /**
+     * Blocks until anything is published or timeout elapses. Returns immediately when buffers aren't empty.
+     */
+    pub fn wait(&self, timeout: Duration) {
+        let subscribers: MutexGuard<Vec<Subscriber<S, P>>> = self.lock();
+
+        if subscribers.iter().any(|subscriber| !subscriber.buffer.is_empty()) {
+            return;
+        }
+
+        let _ = self.published.wait_timeout(subscribers, timeout);
+    }
+
+    /**
+     * Sends buffered messages. Subscribers whose connection fails are removed. Returns number of sent messages.
+     */
+    pub fn deliver(&self) -> usize {
+        let deliveries: Vec<Delivery<S>> = self
+            .lock()
+            .iter_mut()
+            .filter(|subscriber| !subscriber.buffer.is_empty())
+            .map(|subscriber| {
+                let mut messages: Vec<Arc<str>> = vec![];
+
+                if subscriber.dropped > 0 {
+                    let lagged: EventMessage<LaggedDTO> = EventMessage { event: "lagged", data: &LaggedDTO { dropped: subscriber.dropped } };
+                    messages.push(Arc::from(serde_json::to_string(&lagged).unwrap_or_default()));
+                    subscriber.dropped = 0;
+                }
+
+                messages.extend(subscriber.buffer.drain(..));
+
+                Delivery { session: subscriber.session, sender: Arc::clone(&subscriber.sender), messages }
+            })
+            .collect();
+
+        let mut sent: usize = 0;
+        let mut failed_sessions: Vec<i32> = vec![];
+
+        for Delivery { session, sender, messages } in deliveries {
+            let Ok(mut sender) = sender.lock() else {
+                failed_sessions.push(session);
+                continue;
+            };
+
+            for message in messages {
+                if let Err(error) = sender.send(FrameType::Text(false), message.as_bytes()) {
+                    log::warn!("Can't send live event to session {session}: {error:?}. Unsubscribing...");
+                    failed_sessions.push(session);
+                    break;
+                }
+
+                sent += 1;
+            }
+        }
+
+        if !failed_sessions.is_empty() {
+            self.lock().retain(|subscriber| !failed_sessions.contains(&subscriber.session));
+        }
+
+        sent
+    }
+
+    /**
+     * Ends subscriptions whose principal isn't accepted anymore, e.g. because access token expired.
+     * Their connections are closed.
+     */
+    pub fn retain<F: FnMut(&P) -> bool>(&self, mut keep: F) {
+        let mut removed: Vec<Subscriber<S, P>> = vec![];
+
+        {
+            let mut subscribers: MutexGuard<Vec<Subscriber<S, P>>> = self.lock();
+
+            for subscriber in std::mem::take(&mut *subscribers) {
+                match keep(&subscriber.principal) {
+                    true => subscribers.push(subscriber),
+                    false => removed.push(subscriber),
+                }
+            }
+        }
+
+        for subscriber in removed {
+            if let Ok(mut sender) = subscriber.sender.lock() {
+                let _ = sender.send(FrameType::Close, &[]);
+            }
+        }
+    }
+
+    /**
+     * Closes connections which didn't subscribe within timeout. Error envelope is sent before closing,
+     * the same as when subscription is rejected.
+     */
+    pub fn close_unauthenticated(&self, timeout: Duration) {
+        let expired: Vec<PendingConnection<S>> = {
+            let mut pending: MutexGuard<Vec<PendingConnection<S>>> = self.lock_pending();
+            let (expired, waiting): (Vec<PendingConnection<S>>, Vec<PendingConnection<S>>) = std::mem::take(&mut *pending)
+                .into_iter()
+                .partition(|connection| connection.connected_at.elapsed() >= timeout);
+
+            *pending = waiting;
+            expired
+        };
+
+        if expired.is_empty() {
+            return;
+        }
+
+        let error: ApiError = ApiError::unauthorized("authentication_timeout", format!("Access token wasn't sent within {} s.", timeout.as_secs()));
+        let envelope: String = serde_json::to_string(&error).unwrap_or_default();
+
+        for mut connection in expired {
+            let _ = connection.sender.send(FrameType::Text(false), envelope.as_bytes());
+            let _ = connection.sender.send(FrameType::Close, &[]);
+        }
+    }
+
+    /* subscribers stay usable when a thread panicked while holding lock */
+    fn lock(&self) -> MutexGuard<'_, Vec<Subscriber<S, P>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, Vec<PendingConnection<S>>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -1,10 +1,20 @@
-use esp_idf_svc::http::server::{Connection, Request, Response};
-use esp_idf_svc::io::Write;
-use esp_idf_svc::sys::EspError;
+use embedded_svc::http::server::{Connection, Request, Response};
+use embedded_svc::http::Method;
+use embedded_svc::io::Write;
+use interface::EspError;
 use serde::de::{DeserializeOwned, Error as _};
 use serde::{Serialize};
+use crate::api_error::ApiError;
+use crate::cors::get_cors_policy;
+use crate::media_type::MediaType;
+use crate::request_body::{get_max_body_size, read_body};
 use crate::to_response_data::ToResponseData;
+use std::fmt::{Debug, Display, Formatter};
 
+/**
+ * Data is written in media type negotiated from Accept header of request. Responds with 406 when no media type of data
+ * is acceptable. Errors are sent in default media type of envelope instead, so client always learns what failed.
+ */
 fn status_response<'a, C, Data>(request: Request<C>,
                                 status: u16,
                                 data: &Data,
@@ -13,23 +23,38 @@ fn status_response<'a, C, Data>(request: Request<C>,
 where C: Connection,
       Data: ToResponseData {
 
-    let cors_headers = &[
-        ("Access-Control-Allow-Origin", "*"),
-        ("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, PATCH"),
-        ("Access-Control-Allow-Headers", "Access-Control-Allow-Headers, Origin,Accept, X-Requested-With, Content-Type, Access-Token, Access-Control-Request-Method, Access-Control-Request-Headers"),
-    ];
+    let media_types: &[MediaType] = data.media_types();
 
-    let headers = &[headers, cors_headers].concat();
+    let media_type: MediaType = match MediaType::negotiate(request.header("Accept"), media_types) {
+        Some(media_type) => media_type,
+        None if status >= 400 => media_types.first().copied().unwrap_or(MediaType::Json),
+        None => {
+            let offered: String = media_types
+                .iter()
+                .map(MediaType::essence)
+                .collect::<Vec<&str>>()
+                .join(", ");
+
+            let error: ApiError = ApiError::new(406, "not_acceptable", format!("Response can be sent as {offered}."));
+            return status_response(request, error.status, &error, error.reason_phrase(), &[]);
+        }
+    };
+
+    let cors_headers: Vec<(&'static str, String)> = get_cors_policy().response_headers(request.header("Origin"));
+    let cors_headers: Vec<(&str, &str)> = cors_headers
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect();
+
+    let headers = &[headers, &[("Content-Type", media_type.content_type())], cors_headers.as_slice()].concat();
     let mut response: Response<C> = request
         .into_response(status, Some(message), headers)
         .map_err(RequestError::Connection)?;
 
-    let response_str = data.to_response_data();
-
-    let response_data: &[u8] = response_str.as_bytes();
+    let response_data: Vec<u8> = data.to_response_data(media_type);
 
     response
-        .write_all(response_data)
+        .write_all(&response_data)
         .map_err(RequestError::Connection)?;
 
     Ok(())
@@ -43,10 +68,60 @@ pub enum RequestError<ConnectionError> {
     Connection(ConnectionError),
     Security(String),
     General(String),
+    Api(ApiError),
+}
+
+impl<ConnectionError: Debug> RequestError<ConnectionError> {
+    /**
+     * Body and status of response sent for error which handler didn't respond to.
+     */
+    pub fn to_api_error(&self) -> ApiError {
+        match self {
+            RequestError::SerdeJson(_) => ApiError::bad_request("invalid_json", self.to_string()),
+            RequestError::SerdeURL(_) => ApiError::bad_request("invalid_parameters", self.to_string()),
+            RequestError::Security(_) => ApiError::unauthorized("unauthorized", self.to_string()),
+            RequestError::EspError(_) => ApiError::internal_server_error("internal_error", self.to_string()),
+            RequestError::Connection(_) => ApiError::internal_server_error("connection_error", self.to_string()),
+            RequestError::General(_) => ApiError::internal_server_error("internal_error", self.to_string()),
+            RequestError::Api(api_error) => api_error.clone(),
+        }
+    }
+}
+
+impl<ConnectionError> From<ApiError> for RequestError<ConnectionError> {
+    fn from(api_error: ApiError) -> Self {
+        RequestError::Api(api_error)
+    }
+}
+
+impl<ConnectionError: Debug> Display for RequestError<ConnectionError> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::EspError(error) => write!(f, "{error}"),
+            RequestError::SerdeJson(error) => write!(f, "Invalid JSON: {error}"),
+            RequestError::SerdeURL(error) => write!(f, "Invalid parameters: {error}"),
+            RequestError::Connection(error) => write!(f, "Connection error: {error:?}"),
+            RequestError::Security(message) | RequestError::General(message) => f.write_str(message),
+            RequestError::Api(api_error) => f.write_str(&api_error.message),
+        }
+    }
 }
 
 pub type RequestResult<V, ConnectionError> = Result<V, RequestError<ConnectionError>>;
 
+pub fn method_name(method: Method) -> &'static str {
+    match method {
+        Method::Get => "GET",
+        Method::Post => "POST",
+        Method::Put => "PUT",
+        Method::Patch => "PATCH",
+        Method::Delete => "DELETE",
+        Method::Head => "HEAD",
+        Method::Options => "OPTIONS",
+        _ => "",
+    }
+}
+
 pub enum ResponseData<Data: Serialize + ToString> {
     Json(Data),
     Str(Data),
@@ -66,7 +141,15 @@ where C: Connection,
 
     fn forbidden<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), C::Error>;
 
+    fn method_not_allowed<Data: ToResponseData>(self, data: &Data, allowed_methods: &str) -> RequestResult<(), C::Error>;
+
+    fn too_many_requests<Data: ToResponseData>(self, data: &Data, retry_after_seconds: u64) -> RequestResult<(), C::Error>;
+
+    fn status<Data: ToResponseData>(self, status: u16, message: &str, data: &Data) -> RequestResult<(), C::Error>;
+
     fn internal_server_error<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), C::Error>;
+
+    fn error(self, error: &ApiError) -> RequestResult<(), C::Error>;
 }
 
 impl<C> IntoResponse<C> for Request<C>
@@ -91,9 +174,27 @@ where C: Connection {
         status_response(self, 403, data, "Forbidden", &[])
     }
 
+    fn method_not_allowed<Data: ToResponseData>(self, data: &Data, allowed_methods: &str) -> RequestResult<(), C::Error> {
+        status_response(self, 405, data, "Method Not Allowed", &[("Allow", allowed_methods)])
+    }
+
+    fn too_many_requests<Data: ToResponseData>(self, data: &Data, retry_after_seconds: u64) -> RequestResult<(), C::Error> {
+        let retry_after: String = retry_after_seconds.to_string();
+
+        status_response(self, 429, data, "Too Many Requests", &[("Retry-After", retry_after.as_str())])
+    }
+
+    fn status<Data: ToResponseData>(self, status: u16, message: &str, data: &Data) -> RequestResult<(), C::Error> {
+        status_response(self, status, data, message, &[])
+    }
+
     fn internal_server_error<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), C::Error> {
         status_response(self, 500, data, "Internal Server Error", &[])
     }
+
+    fn error(self, error: &ApiError) -> RequestResult<(), C::Error> {
+        status_response(self, error.status, error, error.reason_phrase(), &[])
+    }
 }
 
 
@@ -124,21 +225,31 @@ where C: Connection {
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 pub trait ReadData<C>
 where C: Connection {
-    fn body<'a, Data: DeserializeOwned>(&mut self) -> RequestResult<Data, C::Error>;
+    fn body<Data: DeserializeOwned>(&mut self) -> RequestResult<Data, C::Error>;
 }
 
 impl<C> ReadData<C> for Request<C>
 where C: Connection {
-    fn body<'a, Data: DeserializeOwned>(&mut self) -> RequestResult<Data, C::Error> {
-        let content_length: usize = self
-            .header("Content-Length")
-            .and_then(|content_length| content_length.parse().ok())
-            .unwrap_or(0);
+    /**
+     * JSON body up to size set by set_max_body_size(). CBOR body is accepted when Content-Type is "application/cbor".
+     */
+    fn body<Data: DeserializeOwned>(&mut self) -> RequestResult<Data, C::Error> {
+        let body: Vec<u8> = read_body(self, get_max_body_size())?;
+
+        let is_cbor: bool = self
+            .header("Content-Type")
+            .and_then(|content_type| content_type.split(';').next())
+            .is_some_and(|essence| essence.trim().eq_ignore_ascii_case(MediaType::Cbor.essence()));
 
-        let mut buffer: Vec<u8> = vec![0; content_length];
-        self.read(buffer.as_mut_slice()).map_err(RequestError::Connection)?;
+        if is_cbor {
+            let data: Data = ciborium::from_reader(body.as_slice())
+                .map_err(|error| ApiError::bad_request("invalid_cbor", format!("Invalid CBOR: {error}")))?;
+
+            return Ok(data);
+        }
 
-        let data: String = String::from_utf8_lossy(&buffer).to_string();
+        let data: String = String::from_utf8(body)
+            .map_err(|error| ApiError::bad_request("invalid_utf8", format!("Request body isn't valid UTF-8: {error}")))?;
 
         let data: Data = serde_json::from_str(&data).map_err(RequestError::SerdeJson)?;
 
@@ -1,26 +1,113 @@
+use esp_idf_svc::http::server::ws::EspHttpWsConnection;
 use esp_idf_svc::http::server::{Configuration, EspHttpConnection, EspHttpServer, Request};
 use esp_idf_svc::http::Method;
 use esp_idf_svc::io::EspIOError;
 use esp_idf_svc::sys::{EspError};
+use esp_idf_svc::tls::X509;
+use std::sync::{Arc, RwLock};
+use crate::api_error::ApiError;
+use crate::cors::get_cors_policy;
 use crate::http_request::{IntoResponse, RequestError, RequestResult};
+use crate::middleware::cors_guard::CorsGuard;
+use crate::middleware::error_responder::ErrorResponder;
+use crate::middleware::request_logger::RequestLogger;
+use crate::middleware::{Middleware, Next};
+use crate::tls::TlsCertificate;
 
+/* Middleware of ESP-IDF connections of any lifetime. */
+type EspMiddleware = Arc<dyn for<'r> Middleware<EspHttpConnection<'r>>>;
+
+/**
+ * Every request passes chain of middlewares before its handler. Default chain maps errors and panics
+ * to responses, logs requests and rejects cross-origin requests not allowed by CORS policy.
+ * Authentication isn't part of the chain, because it depends on route matched by router.
+ */
 pub struct HttpServer<'a> {
     server: EspHttpServer<'a>,
+    /* Shared with registered handlers, so middlewares can be added before or after handlers. */
+    middlewares: Arc<RwLock<Vec<EspMiddleware>>>,
 }
 
 impl<'a> HttpServer<'a> {
     pub fn new() -> Result<Self, EspIOError> {
+        let mut configuration: Configuration = HttpServer::configuration();
+        configuration.stack_size = 8 * 1024;
+
+        HttpServer::start(&configuration)
+    }
+
+    /**
+     * Server listens on HTTPS port only. TLS handshake needs more stack and every TLS session takes a lot of heap,
+     * so fewer sockets are opened than by plain HTTP server.
+     */
+    pub fn new_https(tls_certificate: &TlsCertificate) -> Result<Self, EspIOError> {
+        let mut configuration: Configuration = HttpServer::configuration();
+        configuration.stack_size = 10 * 1024;
+        configuration.max_open_sockets = 4;
+        configuration.server_certificate = Some(X509::pem_until_nul(leak_pem(tls_certificate.certificate_pem())));
+        configuration.private_key = Some(X509::pem_until_nul(leak_pem(tls_certificate.private_key_pem())));
+
+        HttpServer::start(&configuration)
+    }
+
+    fn configuration() -> Configuration {
         let mut configuration: Configuration = Configuration::default();
         configuration.uri_match_wildcard = true;
-        configuration.stack_size = 8 * 1024;
+        /* every URI and method pair takes one handler */
+        configuration.max_uri_handlers = 64;
+
+        configuration
+    }
 
-        let mut server: EspHttpServer = EspHttpServer::new(&configuration)?;
+    fn start(configuration: &Configuration) -> Result<Self, EspIOError> {
+        let mut http_server: HttpServer = Self {
+            server: EspHttpServer::new(configuration)?,
+            middlewares: Arc::new(RwLock::new(vec![])),
+        };
 
-        server.fn_handler::<RequestError<EspIOError>, _>("/*?", Method::Options, move |esp_http_request: Request<&mut EspHttpConnection>| -> RequestResult<(), EspIOError> {
-            esp_http_request.ok(&"Returning OK response on OPTIONS request").map(|_| ())
-        })?;
+        /* error responder is the first one, so errors of other middlewares become responses too */
+        http_server.add_middleware(ErrorResponder);
+        http_server.add_middleware(RequestLogger);
+        http_server.add_middleware(CorsGuard);
+
+        /* OPTIONS is public, because browsers send preflight requests without credentials. */
+        http_server
+            .add_handler("/*?", Method::Options, |request| {
+                let preflight_headers: Option<Vec<(&'static str, String)>> = get_cors_policy().preflight_headers(
+                    request.header("Origin"),
+                    request.header("Access-Control-Request-Method"),
+                );
+
+                let Some(preflight_headers) = preflight_headers else {
+                    return request.error(&ApiError::forbidden("cross_origin_not_allowed", "Cross-origin request isn't allowed."));
+                };
+
+                let preflight_headers: Vec<(&str, &str)> = preflight_headers
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_str()))
+                    .collect();
+
+                request
+                    .into_response(204, Some("No Content"), &preflight_headers)
+                    .map_err(RequestError::Connection)?;
 
-        Ok(Self { server })
+                Ok(())
+            })
+            .map_err(EspIOError)?;
+
+        Ok(http_server)
+    }
+
+    /**
+     * Middleware is appended to the end of chain, i.e. it runs after middlewares added before.
+     */
+    pub fn add_middleware<M>(&mut self, middleware: M)
+    where
+        M: for<'r> Middleware<EspHttpConnection<'r>> + 'static,
+    {
+        if let Ok(mut middlewares) = self.middlewares.write() {
+            middlewares.push(Arc::new(middleware));
+        }
     }
 
     pub fn add_handler<F>(
@@ -32,10 +119,42 @@ impl<'a> HttpServer<'a> {
     where
         F: for<'r> Fn(Request<&mut EspHttpConnection<'r>>) -> RequestResult<(), EspIOError> + Send + 'static,
     {
+        let middlewares: Arc<RwLock<Vec<EspMiddleware>>> = Arc::clone(&self.middlewares);
+
         self.server.fn_handler::<RequestError<EspIOError>, _>(uri, method, move |esp_http_request: Request<&mut EspHttpConnection>| -> RequestResult<(), EspIOError> {
-            handle_request(esp_http_request).map(|_| ())
+            /* chain is copied, so lock isn't held while request is handled */
+            let middlewares: Vec<EspMiddleware> = middlewares
+                .read()
+                .map(|middlewares| middlewares.clone())
+                .unwrap_or_default();
+
+            Next::new(&middlewares, &handle_request).run(esp_http_request)
         })?;
 
         Ok(())
     }
+
+    /**
+     * Handler is called for new WebSocket connection, for every received frame and when connection is closed.
+     * WebSocket connections don't pass middleware chain, so handler checks credentials itself.
+     * Handlers run in server task, so pushing to clients is done via detached senders from other threads.
+     */
+    pub fn add_ws_handler<H>(&mut self, uri: &str, handler: H) -> Result<(), EspError>
+    where
+        H: for<'r> Fn(&'r mut EspHttpWsConnection) -> Result<(), EspError> + Send + Sync + 'static,
+    {
+        self.server.ws_handler(uri, handler)?;
+
+        Ok(())
+    }
+}
+
+/**
+ * ESP-IDF keeps pointers to certificate and key while server runs. Server is created once per boot, so they are leaked.
+ */
+fn leak_pem(pem: &str) -> &'static [u8] {
+    let mut pem: Vec<u8> = pem.as_bytes().to_vec();
+    pem.push(0);
+
+    Box::leak(pem.into_boxed_slice())
 }
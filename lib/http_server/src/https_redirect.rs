@@ -0,0 +1,74 @@
+use crate::api_error::ApiError;
+use crate::http_request::{IntoResponse, RequestError, RequestResult};
+use esp_idf_svc::http::server::{Configuration, EspHttpConnection, EspHttpServer, Request};
+use esp_idf_svc::http::Method;
+use esp_idf_svc::io::EspIOError;
+
+/* Control port should differ from the one used by HTTPS server. */
+const CONTROL_PORT: u16 = 32769;
+
+/**
+ * Plain HTTP listener which sends browsers to the same URL over HTTPS.
+ * Other requests are rejected, so API clients notice that they send credentials over plain HTTP.
+ */
+pub struct HttpsRedirect<'a> {
+    _server: EspHttpServer<'a>,
+}
+
+impl<'a> HttpsRedirect<'a> {
+    pub fn new() -> Result<Self, EspIOError> {
+        let mut configuration: Configuration = Configuration::default();
+        configuration.uri_match_wildcard = true;
+        configuration.ctrl_port = CONTROL_PORT;
+        configuration.max_open_sockets = 2;
+
+        let mut server: EspHttpServer = EspHttpServer::new(&configuration)?;
+
+        for method in [Method::Get, Method::Head] {
+            server.fn_handler::<RequestError<EspIOError>, _>("/*?", method, redirect)?;
+        }
+
+        for method in [Method::Post, Method::Put, Method::Patch, Method::Delete] {
+            server.fn_handler::<RequestError<EspIOError>, _>("/*?", method, |request: Request<&mut EspHttpConnection>| -> RequestResult<(), EspIOError> {
+                request.error(&ApiError::forbidden("https_required", "HTTPS required."))
+            })?;
+        }
+
+        Ok(Self { _server: server })
+    }
+}
+
+fn redirect(request: Request<&mut EspHttpConnection>) -> RequestResult<(), EspIOError> {
+    let Some(host) = request.header("Host").and_then(host_without_port) else {
+        return request.error(&ApiError::bad_request("invalid_host", "Host header is missing or invalid."));
+    };
+
+    /* HTTPS server listens on default port */
+    let location: String = format!("https://{host}{}", request.uri());
+
+    request
+        .into_response(301, Some("Moved Permanently"), &[("Location", location.as_str())])
+        .map_err(RequestError::Connection)?;
+
+    Ok(())
+}
+
+/**
+ * Host is copied to Location header, so only characters of host names and IP addresses are accepted.
+ */
+fn host_without_port(host: &str) -> Option<&str> {
+    let host: &str =
+        match host.rsplit_once(':') {
+            /* IPv6 address without port, e.g. [fe80::1] */
+            Some(_) if host.ends_with(']') => host,
+            Some((host, port)) if port.chars().all(|character| character.is_ascii_digit()) => host,
+            Some(_) => return None,
+            None => host,
+        };
+
+    let is_valid: bool = !host.is_empty() && host
+        .chars()
+        .all(|character| character.is_ascii_alphanumeric() || matches!(character, '.' | '-' | '[' | ']' | ':'));
+
+    is_valid.then_some(host)
+}
@@ -1,3 +1,20 @@
+#[cfg(target_os = "espidf")]
 pub mod http_server;
 pub mod http_request;
+pub mod api_error;
+pub mod request_body;
 pub mod to_response_data;
+pub mod media_type;
+pub mod client_address;
+pub mod cors;
+#[cfg(target_os = "espidf")]
+pub mod https_redirect;
+pub mod tls;
+pub mod router;
+pub mod middleware;
+pub mod openapi;
+pub mod event_hub;
+pub mod static_file;
+pub mod tar;
+#[cfg(not(target_os = "espidf"))]
+pub mod mock_connection;
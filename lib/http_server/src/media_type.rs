@@ -0,0 +1,107 @@
+/**
+ * Formats which response data can be written as.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MediaType {
+    Json,
+    Cbor,
+    Csv,
+    Text,
+}
+
+impl MediaType {
+    /**
+     * Type and subtype without parameters, e.g. "application/json".
+     */
+    pub fn essence(&self) -> &'static str {
+        match self {
+            MediaType::Json => "application/json",
+            MediaType::Cbor => "application/cbor",
+            MediaType::Csv => "text/csv",
+            MediaType::Text => "text/plain",
+        }
+    }
+
+    /**
+     * Value of Content-Type header. Text formats are always UTF-8.
+     */
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            MediaType::Json => "application/json",
+            MediaType::Cbor => "application/cbor",
+            MediaType::Csv => "text/csv; charset=utf-8",
+            MediaType::Text => "text/plain; charset=utf-8",
+        }
+    }
+
+    /**
+     * Offered media type preferred by Accept header, e.g. "application/cbor, application/json;q=0.5".
+     * Quality of media type is taken from the most specific matching range, ties are resolved by order of offered types.
+     * The first offered type is used when there is no Accept header. Returns None when no offered type is acceptable.
+     */
+    pub fn negotiate(accept: Option<&str>, offered: &[MediaType]) -> Option<MediaType> {
+        let Some(accept) = accept.filter(|accept| !accept.trim().is_empty()) else {
+            return offered.first().copied();
+        };
+
+        let ranges: Vec<MediaRange> = accept
+            .split(',')
+            .filter_map(MediaRange::parse)
+            .collect();
+
+        let mut preferred: Option<(MediaType, f32)> = None;
+
+        for media_type in offered {
+            let quality: f32 = ranges
+                .iter()
+                .filter_map(|range| range.specificity(media_type.essence()).map(|specificity| (specificity, range.quality)))
+                .max_by_key(|(specificity, _)| *specificity)
+                .map_or(0.0, |(_, quality)| quality);
+
+            if quality > 0.0 && preferred.map_or(true, |(_, preferred_quality)| quality > preferred_quality) {
+                preferred = Some((*media_type, quality));
+            }
+        }
+
+        preferred.map(|(media_type, _)| media_type)
+    }
+}
+
+/**
+ * One range of Accept header, e.g. "text/csv;q=0.8". Type and subtype can be wildcards.
+ */
+struct MediaRange<'a> {
+    main_type: &'a str,
+    subtype: &'a str,
+    quality: f32,
+}
+
+impl<'a> MediaRange<'a> {
+    fn parse(range: &'a str) -> Option<Self> {
+        let mut parameters = range.split(';');
+
+        let (main_type, subtype) = parameters.next()?.trim().split_once('/')?;
+
+        /* invalid quality is treated as default, so malformed header doesn't reject response */
+        let quality: f32 = parameters
+            .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+            .filter_map(|quality| quality.trim().parse::<f32>().ok())
+            .next()
+            .unwrap_or(1.0)
+            .clamp(0.0, 1.0);
+
+        Some(Self { main_type: main_type.trim(), subtype: subtype.trim(), quality })
+    }
+
+    /* 2 for exact match, 1 for subtype wildcard, 0 for full wildcard */
+    fn specificity(&self, essence: &str) -> Option<u8> {
+        let (main_type, subtype) = essence.split_once('/')?;
+
+        match (self.main_type, self.subtype) {
+            ("*", "*") => Some(0),
+            (range_type, "*") if range_type.eq_ignore_ascii_case(main_type) => Some(1),
+            (range_type, range_subtype) if range_type.eq_ignore_ascii_case(main_type) && range_subtype.eq_ignore_ascii_case(subtype) => Some(2),
+            _ => None,
+        }
+    }
+}
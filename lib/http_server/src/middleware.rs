@@ -0,0 +1,43 @@
+pub mod cors_guard;
+pub mod error_responder;
+pub mod request_logger;
+
+use crate::http_request::RequestResult;
+use embedded_svc::http::server::{Connection, Request};
+use embedded_svc::io::ErrorType;
+use std::sync::Arc;
+
+/**
+ * Step of request processing which runs before handler. Middleware passes request to the next step
+ * or responds itself, e.g. rejects request. Implementations are generic over connection, so the same
+ * middleware serves every connection lifetime.
+ */
+pub trait Middleware<C: Connection>: Send + Sync {
+    fn handle(&self, request: Request<&mut C>, next: Next<'_, C>) -> RequestResult<(), C::Error>;
+}
+
+/**
+ * Handler at the end of middleware chain.
+ */
+pub type Handler<'n, C> = dyn Fn(Request<&mut C>) -> RequestResult<(), <C as ErrorType>::Error> + 'n;
+
+/**
+ * Remaining middlewares of chain followed by handler.
+ */
+pub struct Next<'n, C: Connection> {
+    middlewares: &'n [Arc<dyn Middleware<C>>],
+    handler: &'n Handler<'n, C>,
+}
+
+impl<'n, C: Connection> Next<'n, C> {
+    pub fn new(middlewares: &'n [Arc<dyn Middleware<C>>], handler: &'n Handler<'n, C>) -> Self {
+        Self { middlewares, handler }
+    }
+
+    pub fn run(self, request: Request<&mut C>) -> RequestResult<(), C::Error> {
+        match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => middleware.handle(request, Next::new(middlewares, self.handler)),
+            None => (self.handler)(request),
+        }
+    }
+}
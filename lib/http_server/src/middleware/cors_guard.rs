@@ -0,0 +1,30 @@
+use crate::api_error::ApiError;
+use crate::cors::get_cors_policy;
+use crate::http_request::{method_name, IntoResponse, RequestResult};
+use crate::middleware::{Middleware, Next};
+use embedded_svc::http::server::{Connection, Request};
+use embedded_svc::http::Method;
+
+/**
+ * Rejects cross-origin requests which CORS policy doesn't allow, so pages of other sites can't use
+ * the API even when browser sends request without preflight. Preflight requests are answered by OPTIONS handler.
+ */
+pub struct CorsGuard;
+
+impl<C: Connection> Middleware<C> for CorsGuard {
+    fn handle(&self, request: Request<&mut C>, next: Next<'_, C>) -> RequestResult<(), C::Error> {
+        let method: Method = request.method();
+
+        let is_allowed: bool = method == Method::Options || get_cors_policy().is_request_allowed(
+            request.header("Origin"),
+            request.header("Host"),
+            method_name(method),
+        );
+
+        if !is_allowed {
+            return request.error(&ApiError::forbidden("cross_origin_not_allowed", "Cross-origin request isn't allowed."));
+        }
+
+        next.run(request)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::api_error::ApiError;
+use crate::http_request::{IntoResponse, RequestError, RequestResult};
+use crate::middleware::{Middleware, Next};
+use embedded_svc::http::server::{Connection, Request};
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+/**
+ * Responds to errors returned by handlers with error envelope, e.g. RequestError::SerdeJson becomes 400 "invalid_json".
+ * Panic of handler becomes 500 when panics unwind, so the server keeps running.
+ * Nothing is sent when handler already started response.
+ */
+pub struct ErrorResponder;
+
+impl<C: Connection> Middleware<C> for ErrorResponder {
+    fn handle(&self, request: Request<&mut C>, next: Next<'_, C>) -> RequestResult<(), C::Error> {
+        /* connection is kept, because handler consumes request also when it fails */
+        let connection: &mut C = request.release();
+
+        let result: RequestResult<(), C::Error> = panic::catch_unwind(AssertUnwindSafe(|| next.run(Request::wrap(&mut *connection))))
+            .unwrap_or_else(|_| Err(RequestError::General("Request handler panicked.".to_string())));
+
+        let Err(error) = result else {
+            return Ok(());
+        };
+
+        if connection.is_response_initiated() {
+            return Err(error);
+        }
+
+        let api_error: ApiError = error.to_api_error();
+
+        Request::wrap(connection).error(&api_error)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::http_request::{method_name, RequestResult};
+use crate::middleware::{Middleware, Next};
+use embedded_svc::http::server::{Connection, Request};
+use std::time::{Duration, Instant};
+
+/**
+ * Logs method, path and processing time of every request. Query is omitted, so logs stay short.
+ */
+pub struct RequestLogger;
+
+impl<C: Connection> Middleware<C> for RequestLogger {
+    fn handle(&self, request: Request<&mut C>, next: Next<'_, C>) -> RequestResult<(), C::Error> {
+        let method: &str = method_name(request.method());
+        let path: String = request
+            .uri()
+            .split_once('?')
+            .map_or(request.uri(), |(path, _)| path)
+            .to_string();
+
+        let started_at: Instant = Instant::now();
+        let result: RequestResult<(), C::Error> = next.run(request);
+        let elapsed: Duration = started_at.elapsed();
+
+        match &result {
+            Ok(()) => log::info!("{method} {path} - {} ms", elapsed.as_millis()),
+            Err(error) => log::warn!("{method} {path} - failed after {} ms: {error}", elapsed.as_millis()),
+        }
+
+        result
+    }
+}
@@ -0,0 +1,396 @@
+use crate::client_address::ClientAddress;
+use crate::router::{ConnectionFamily, Router};
+use embedded_svc::http::server::Connection;
+use embedded_svc::http::{Headers, Method, Query};
+use embedded_svc::io::{ErrorKind, ErrorType, Read, Write};
+use embedded_svc::ws::{FrameType, Sender};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+/**
+ * In-memory connection for host tests of handlers, routers and middlewares.
+ * Request is built or replayed from raw HTTP text, response status, headers and body are captured:
+ *
+ * let mut connection = MockConnection::new(Method::Get, "/api/v1/clock").with_header("Access-Token", token);
+ * router.handle(Request::wrap(&mut connection))?;
+ * assert_eq!(connection.status(), Some(200));
+ */
+pub struct MockConnection {
+    request_head: MockRequestHead,
+    body: MockBody,
+    client_ip: Option<IpAddr>,
+    status: Option<u16>,
+    status_message: Option<String>,
+    response_headers: Vec<(String, String)>,
+    response_body: Vec<u8>,
+}
+
+pub struct MockRequestHead {
+    method: Method,
+    uri: String,
+    headers: Vec<(String, String)>,
+}
+
+/**
+ * Body of request. It is also raw connection, so bytes written to raw connection are kept separately.
+ */
+pub struct MockBody {
+    data: Vec<u8>,
+    position: usize,
+    /* real connections may return less than requested */
+    max_read_size: usize,
+    raw_output: Vec<u8>,
+}
+
+impl MockConnection {
+    pub fn new(method: Method, uri: &str) -> Self {
+        Self {
+            request_head: MockRequestHead {
+                method,
+                uri: uri.to_string(),
+                headers: vec![],
+            },
+            body: MockBody {
+                data: vec![],
+                position: 0,
+                max_read_size: usize::MAX,
+                raw_output: vec![],
+            },
+            client_ip: None,
+            status: None,
+            status_message: None,
+            response_headers: vec![],
+            response_body: vec![],
+        }
+    }
+
+    /**
+     * Request in HTTP/1.1 format, e.g. "POST /api/v1/login HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}".
+     * Body is taken as is, so chunked bodies can be replayed too.
+     */
+    pub fn replay(raw_request: &[u8]) -> Result<Self, String> {
+        let head_end: usize = raw_request
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .ok_or("Request head isn't terminated by empty line.")?;
+
+        let head: &str = std::str::from_utf8(&raw_request[..head_end]).map_err(|error| error.to_string())?;
+        let mut lines = head.split("\r\n");
+
+        let request_line: &str = lines.next().unwrap_or_default();
+        let mut request_line_parts = request_line.split(' ');
+
+        let (Some(method), Some(uri)) = (request_line_parts.next(), request_line_parts.next()) else {
+            return Err(format!("Invalid request line '{request_line}'."));
+        };
+
+        let mut connection: MockConnection = MockConnection::new(parse_method(method)?, uri);
+
+        for line in lines {
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid header '{line}'."))?;
+
+            connection = connection.with_header(name.trim(), value.trim());
+        }
+
+        connection.body.data = raw_request[head_end + 4..].to_vec();
+
+        Ok(connection)
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.request_head.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /**
+     * Content-Length header is added.
+     */
+    pub fn with_body(mut self, body: &[u8]) -> Self {
+        self.body.data = body.to_vec();
+        self.with_header("Content-Length", &body.len().to_string())
+    }
+
+    pub fn with_json<T: Serialize>(self, value: &T) -> Self {
+        let body: Vec<u8> = serde_json::to_vec(value).unwrap_or_default();
+
+        self.with_header("Content-Type", "application/json").with_body(&body)
+    }
+
+    pub fn with_client_ip(mut self, client_ip: IpAddr) -> Self {
+        self.client_ip = Some(client_ip);
+        self
+    }
+
+    /**
+     * Limit of bytes returned by single read, so handlers are tested with partially delivered bodies.
+     */
+    pub fn with_max_read_size(mut self, max_read_size: usize) -> Self {
+        self.body.max_read_size = max_read_size.max(1);
+        self
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    pub fn status_message(&self) -> Option<&str> {
+        self.status_message.as_deref()
+    }
+
+    pub fn response_header(&self, name: &str) -> Option<&str> {
+        self.response_headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn response_headers(&self) -> &[(String, String)] {
+        &self.response_headers
+    }
+
+    pub fn response_body(&self) -> &[u8] {
+        &self.response_body
+    }
+
+    pub fn response_text(&self) -> String {
+        String::from_utf8_lossy(&self.response_body).to_string()
+    }
+
+    pub fn response_json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.response_body)
+    }
+
+    /**
+     * Bytes of request body which weren't read by handler.
+     */
+    pub fn unread_body(&self) -> &[u8] {
+        &self.body.data[self.body.position..]
+    }
+
+    pub fn raw_output(&self) -> &[u8] {
+        &self.body.raw_output
+    }
+}
+
+fn parse_method(method: &str) -> Result<Method, String> {
+    match method {
+        "GET" => Ok(Method::Get),
+        "POST" => Ok(Method::Post),
+        "PUT" => Ok(Method::Put),
+        "PATCH" => Ok(Method::Patch),
+        "DELETE" => Ok(Method::Delete),
+        "HEAD" => Ok(Method::Head),
+        "OPTIONS" => Ok(Method::Options),
+        method => Err(format!("Unsupported method '{method}'.")),
+    }
+}
+
+impl Query for MockRequestHead {
+    fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    fn method(&self) -> Method {
+        self.method
+    }
+}
+
+impl Headers for MockRequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl ErrorType for MockBody {
+    type Error = ErrorKind;
+}
+
+impl Read for MockBody {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        let size: usize = buffer
+            .len()
+            .min(self.max_read_size)
+            .min(self.data.len() - self.position);
+
+        buffer[..size].copy_from_slice(&self.data[self.position..self.position + size]);
+        self.position += size;
+
+        Ok(size)
+    }
+}
+
+impl Write for MockBody {
+    fn write(&mut self, buffer: &[u8]) -> Result<usize, Self::Error> {
+        self.raw_output.extend_from_slice(buffer);
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ErrorType for MockConnection {
+    type Error = ErrorKind;
+}
+
+impl Read for MockConnection {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        self.body.read(buffer)
+    }
+}
+
+impl Write for MockConnection {
+    /**
+     * Response is started implicitly like by ESP-IDF server.
+     */
+    fn write(&mut self, buffer: &[u8]) -> Result<usize, Self::Error> {
+        if self.status.is_none() {
+            self.initiate_response(200, None, &[])?;
+        }
+
+        self.response_body.extend_from_slice(buffer);
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl Query for MockConnection {
+    fn uri(&self) -> &str {
+        self.request_head.uri()
+    }
+
+    fn method(&self) -> Method {
+        self.request_head.method()
+    }
+}
+
+impl Headers for MockConnection {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.request_head.header(name)
+    }
+}
+
+impl Connection for MockConnection {
+    type Headers = MockRequestHead;
+    type Read = MockBody;
+    type RawConnectionError = ErrorKind;
+    type RawConnection = MockBody;
+
+    fn split(&mut self) -> (&Self::Headers, &mut Self::Read) {
+        (&self.request_head, &mut self.body)
+    }
+
+    fn initiate_response<'a>(&'a mut self, status: u16, message: Option<&'a str>, headers: &'a [(&'a str, &'a str)]) -> Result<(), Self::Error> {
+        if self.status.is_some() {
+            return Err(ErrorKind::Other);
+        }
+
+        self.status = Some(status);
+        self.status_message = message.map(str::to_string);
+        self.response_headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        Ok(())
+    }
+
+    fn is_response_initiated(&self) -> bool {
+        self.status.is_some()
+    }
+
+    fn raw_connection(&mut self) -> Result<&mut Self::RawConnection, Self::Error> {
+        Ok(&mut self.body)
+    }
+}
+
+impl ClientAddress for MockConnection {
+    fn client_ip(&mut self) -> Option<IpAddr> {
+        self.client_ip
+    }
+}
+
+/**
+ * Mock connections borrowed by requests, e.g. Request::wrap(&mut connection).
+ */
+pub struct MockConnections;
+
+impl ConnectionFamily for MockConnections {
+    type Error = ErrorKind;
+    type Connection<'r, 'c> = &'r mut MockConnection where 'c: 'r;
+}
+
+/**
+ * Router which handles requests of mock connections.
+ */
+pub type MockRouter<A> = Router<A, MockConnections>;
+
+type Frames = Vec<(FrameType, Vec<u8>)>;
+
+/**
+ * In-memory sender of WebSocket frames. Frames are shared between clones, because event hub owns sender.
+ * Closed sender fails like connection of disconnected client.
+ */
+#[derive(Clone, Default)]
+pub struct MockSender {
+    frames: Arc<Mutex<Frames>>,
+    closed: bool,
+}
+
+impl MockSender {
+    pub fn closed() -> Self {
+        Self { closed: true, ..Self::default() }
+    }
+
+    /**
+     * Text frames parsed as JSON.
+     */
+    pub fn messages(&self) -> Vec<serde_json::Value> {
+        self.frames
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter(|(frame_type, _)| *frame_type == FrameType::Text(false))
+            .filter_map(|(_, data)| serde_json::from_slice(data).ok())
+            .collect()
+    }
+
+    pub fn frame_types(&self) -> Vec<FrameType> {
+        self.frames
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(frame_type, _)| *frame_type)
+            .collect()
+    }
+}
+
+impl embedded_svc::ws::ErrorType for MockSender {
+    type Error = ErrorKind;
+}
+
+impl Sender for MockSender {
+    fn send(&mut self, frame_type: FrameType, frame_data: &[u8]) -> Result<(), Self::Error> {
+        if self.closed {
+            return Err(ErrorKind::NotConnected);
+        }
+
+        self.frames
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((frame_type, frame_data.to_vec()));
+
+        Ok(())
+    }
+}
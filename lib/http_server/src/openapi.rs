@@ -0,0 +1,283 @@
+use crate::api_error::ApiError;
+use crate::http_request::method_name;
+use crate::router::{served_routes, Access, Api, ConnectionFamily, Route};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use std::fmt::{Display, Formatter};
+
+const OPENAPI_VERSION: &str = "3.0.3";
+
+pub type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/**
+ * Body of request or response as documented in specification.
+ */
+#[derive(Clone, Copy)]
+pub enum Body {
+    /* JSON or CBOR, depending on Accept header */
+    Json(SchemaFn),
+    /* like JSON, but list can be sent as CSV too */
+    Table(SchemaFn),
+    /* plain text, e.g. "Alarm added" or access token */
+    Text,
+    /* streamed body documented only by content type, e.g. "application/x-ndjson" */
+    Media(&'static str),
+}
+
+/**
+ * Documentation of route. Path and query parameters are structs deserialized by RequestContext::path and RequestContext::query.
+ */
+#[derive(Clone, Copy, Default)]
+pub struct Operation {
+    pub summary: Option<&'static str>,
+    pub path: Option<SchemaFn>,
+    pub query: Option<SchemaFn>,
+    pub request: Option<Body>,
+    pub response: Option<Body>,
+}
+
+/**
+ * Schema of type itself, not reference to it, so properties of parameter structs can be listed.
+ */
+pub fn inline_schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    T::json_schema(generator)
+}
+
+/**
+ * Reference to schema in components when type is named, e.g. "#/components/schemas/AlarmDTO".
+ */
+pub fn schema_ref<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<T>()
+}
+
+/**
+ * Routes which can't be documented. Specification isn't generated while there is any.
+ */
+#[derive(Debug, Eq, PartialEq)]
+pub struct UndocumentedRoutes(pub Vec<String>);
+
+impl Display for UndocumentedRoutes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Routes without schema: {}", self.0.join(", "))
+    }
+}
+
+/**
+ * OpenAPI 3 specification of API built from its routes. Access of route is documented as security requirement.
+ */
+pub struct OpenApi {
+    title: &'static str,
+    version: &'static str,
+    description: Option<&'static str>,
+}
+
+impl OpenApi {
+    pub fn new(title: &'static str, version: &'static str) -> Self {
+        Self { title, version, description: None }
+    }
+
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn document<A: Api, F: ConnectionFamily>(&self) -> Result<Value, UndocumentedRoutes> {
+        let routes: Vec<Route<F, A>> = A::routes::<F>();
+
+        let undocumented_routes: Vec<String> = undocumented_routes(&routes);
+
+        if !undocumented_routes.is_empty() {
+            return Err(UndocumentedRoutes(undocumented_routes));
+        }
+
+        let mut generator: SchemaGenerator = SchemaSettings::openapi3().into_generator();
+        let mut paths: Map<String, Value> = Map::new();
+
+        let error_schema: Schema = generator.subschema_for::<ApiError>();
+
+        /* routes inherited by newer API version are documented under its paths too */
+        for (path, route) in served_routes(&routes) {
+            let operation: Value = document_route(route, &mut generator, &error_schema);
+
+            let path_item: &mut Value = paths
+                .entry(path)
+                .or_insert_with(|| json!({}));
+
+            path_item[method_name(route.method()).to_lowercase()] = operation;
+        }
+
+        let schemas: Map<String, Value> = generator
+            .take_definitions()
+            .into_iter()
+            .map(|(name, schema)| (name, to_value(&schema)))
+            .collect();
+
+        let mut info: Value = json!({ "title": self.title, "version": self.version });
+
+        if let Some(description) = self.description {
+            info["description"] = json!(description);
+        }
+
+        Ok(json!({
+            "openapi": OPENAPI_VERSION,
+            "info": info,
+            "paths": paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": {
+                    "bearer": { "type": "http", "scheme": "bearer" },
+                    "accessToken": { "type": "apiKey", "in": "header", "name": "Access-Token" },
+                },
+            },
+        }))
+    }
+}
+
+/**
+ * Routes without response schema and routes with path parameters which aren't described by path schema,
+ * e.g. "GET /api/v1/outputs/{output_index}/alarms".
+ */
+pub fn undocumented_routes<F: ConnectionFamily, A: Api>(routes: &[Route<F, A>]) -> Vec<String> {
+    let mut generator: SchemaGenerator = SchemaSettings::openapi3().into_generator();
+
+    routes
+        .iter()
+        .filter(|route| {
+            let operation: Operation = route.operation();
+
+            let path_params_documented: bool = match operation.path {
+                Some(path) => {
+                    let properties: Vec<String> = properties(&path(&mut generator))
+                        .into_iter()
+                        .map(|(name, _, _)| name)
+                        .collect();
+
+                    path_params(route).iter().all(|name| properties.iter().any(|property| property == name))
+                }
+                None => path_params(route).is_empty(),
+            };
+
+            operation.response.is_none() || !path_params_documented
+        })
+        .map(|route| format!("{} {}", method_name(route.method()), route.pattern().as_str()))
+        .collect()
+}
+
+fn document_route<F: ConnectionFamily, A: Api>(route: &Route<F, A>, generator: &mut SchemaGenerator, error_schema: &Schema) -> Value {
+    let operation: Operation = route.operation();
+    let mut document: Map<String, Value> = Map::new();
+
+    if let Some(summary) = operation.summary {
+        document.insert("summary".to_string(), json!(summary));
+    }
+
+    let mut parameters: Vec<Value> = vec![];
+
+    if let Some(path) = operation.path {
+        parameters.extend(
+            properties(&path(generator))
+                .into_iter()
+                .map(|(name, schema, _)| json!({ "name": name, "in": "path", "required": true, "schema": schema }))
+        );
+    }
+
+    if let Some(query) = operation.query {
+        parameters.extend(
+            properties(&query(generator))
+                .into_iter()
+                .map(|(name, schema, required)| json!({ "name": name, "in": "query", "required": required, "schema": schema }))
+        );
+    }
+
+    if !parameters.is_empty() {
+        document.insert("parameters".to_string(), json!(parameters));
+    }
+
+    if let Some(request) = operation.request {
+        document.insert("requestBody".to_string(), json!({ "required": true, "content": content(request, generator) }));
+    }
+
+    let mut responses: Map<String, Value> = Map::new();
+
+    if let Some(response) = operation.response {
+        responses.insert("200".to_string(), json!({ "description": "OK", "content": content(response, generator) }));
+    }
+
+    responses.insert("default".to_string(), json!({
+        "description": "Error",
+        "content": { "application/json": { "schema": to_value(error_schema) } },
+    }));
+
+    document.insert("responses".to_string(), json!(responses));
+
+    match route.access() {
+        Access::Public => {
+            document.insert("security".to_string(), json!([]));
+        }
+        Access::Authenticated => {
+            document.insert("security".to_string(), security_requirement());
+        }
+        Access::Permission(permission) => {
+            document.insert("security".to_string(), security_requirement());
+            document.insert("description".to_string(), json!(format!("Requires permission {permission:?}.")));
+        }
+    }
+
+    Value::Object(document)
+}
+
+/* either of the headers is accepted */
+fn security_requirement() -> Value {
+    json!([{ "bearer": [] }, { "accessToken": [] }])
+}
+
+fn content(body: Body, generator: &mut SchemaGenerator) -> Value {
+    match body {
+        Body::Json(schema) => {
+            let schema: Value = to_value(&schema(generator));
+
+            json!({ "application/json": { "schema": schema }, "application/cbor": { "schema": schema } })
+        }
+        Body::Table(schema) => {
+            let schema: Value = to_value(&schema(generator));
+
+            json!({
+                "application/json": { "schema": schema },
+                "application/cbor": { "schema": schema },
+                "text/csv": { "schema": { "type": "string" } },
+            })
+        }
+        Body::Text => json!({ "text/plain": { "schema": { "type": "string" } } }),
+        Body::Media(media_type) => json!({ media_type: { "schema": { "type": "string", "format": "binary" } } }),
+    }
+}
+
+/**
+ * Name, schema and requiredness of every property of object schema.
+ */
+fn properties(schema: &Schema) -> Vec<(String, Value, bool)> {
+    let Schema::Object(SchemaObject { object: Some(object), .. }) = schema else {
+        return vec![];
+    };
+
+    object
+        .properties
+        .iter()
+        .map(|(name, schema)| (name.clone(), to_value(schema), object.required.contains(name)))
+        .collect()
+}
+
+fn path_params<F: ConnectionFamily, A: Api>(route: &Route<F, A>) -> Vec<&'static str> {
+    route
+        .pattern()
+        .as_str()
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{').and_then(|name| name.strip_suffix('}')))
+        .collect()
+}
+
+fn to_value(schema: &Schema) -> Value {
+    serde_json::to_value(schema).unwrap_or_default()
+}
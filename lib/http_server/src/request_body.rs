@@ -0,0 +1,104 @@
+use crate::api_error::ApiError;
+use crate::http_request::{RequestError, RequestResult};
+use embedded_svc::http::server::{Connection, Request};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const DEFAULT_MAX_BODY_SIZE: usize = 16 * 1024;
+
+/* Body is read in pieces of this size, so streamed uploads don't need memory of their size. */
+const READ_BUFFER_SIZE: usize = 1024;
+
+static MAX_BODY_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_BODY_SIZE);
+
+/**
+ * Limit of bodies read into memory by ReadData::body. Larger bodies are rejected with 413.
+ * Limit is shared by all servers. Streamed uploads pass their own limit.
+ */
+pub fn set_max_body_size(max_body_size: usize) {
+    MAX_BODY_SIZE.store(max_body_size, Ordering::SeqCst);
+}
+
+pub fn get_max_body_size() -> usize {
+    MAX_BODY_SIZE.load(Ordering::SeqCst)
+}
+
+/**
+ * Whole body in memory. Fails with 413 when body is larger than max_size.
+ */
+pub fn read_body<C: Connection>(request: &mut Request<C>, max_size: usize) -> RequestResult<Vec<u8>, C::Error> {
+    let mut body: Vec<u8> = vec![];
+
+    stream_body(request, max_size, |data: &[u8]| {
+        body.extend_from_slice(data);
+        Ok(())
+    })?;
+
+    Ok(body)
+}
+
+/**
+ * Passes body to sink piece by piece and returns its size. Body is expected to be Content-Length bytes long.
+ * ESP-IDF server reads at most Content-Length bytes of body, so chunked body can't be read and is rejected with 411,
+ * the same as request without Content-Length.
+ * Size is checked before sink gets data exceeding max_size, so sink never receives more than max_size bytes.
+ */
+pub fn stream_body<C, F>(request: &mut Request<C>, max_size: usize, sink: F) -> RequestResult<usize, C::Error>
+where C: Connection,
+      F: FnMut(&[u8]) -> RequestResult<(), C::Error> {
+
+    let is_chunked: bool = request
+        .header("Transfer-Encoding")
+        .is_some_and(|encodings| encodings.split(',').any(|encoding| encoding.trim().eq_ignore_ascii_case("chunked")));
+
+    if is_chunked {
+        return Err(ApiError::new(411, "length_required", "Chunked request body isn't supported, Content-Length is expected.").into());
+    }
+
+    let Some(content_length) = request.header("Content-Length") else {
+        return Err(ApiError::new(411, "length_required", "Request body with Content-Length is expected.").into());
+    };
+
+    let content_length: usize = content_length
+        .trim()
+        .parse()
+        .map_err(|_| ApiError::bad_request("invalid_content_length", format!("Invalid Content-Length '{content_length}'.")))?;
+
+    stream_sized_body(request, content_length, max_size, sink)
+}
+
+fn stream_sized_body<C, F>(request: &mut Request<C>, content_length: usize, max_size: usize, mut sink: F) -> RequestResult<usize, C::Error>
+where C: Connection,
+      F: FnMut(&[u8]) -> RequestResult<(), C::Error> {
+
+    if content_length > max_size {
+        return Err(body_too_large(max_size).into());
+    }
+
+    let mut buffer: [u8; READ_BUFFER_SIZE] = [0; READ_BUFFER_SIZE];
+    let mut remaining: usize = content_length;
+
+    /* read may return fewer bytes than requested, e.g. when body is split into several TCP segments */
+    while remaining > 0 {
+        let size: usize = remaining.min(buffer.len());
+        let read: usize = request
+            .read(&mut buffer[..size])
+            .map_err(RequestError::Connection)?;
+
+        if read == 0 {
+            return Err(incomplete_body(content_length - remaining, content_length).into());
+        }
+
+        sink(&buffer[..read])?;
+        remaining -= read;
+    }
+
+    Ok(content_length)
+}
+
+fn body_too_large(max_size: usize) -> ApiError {
+    ApiError::new(413, "body_too_large", format!("Request body is larger than {max_size} bytes."))
+}
+
+fn incomplete_body(read: usize, content_length: usize) -> ApiError {
+    ApiError::bad_request("incomplete_body", format!("Connection closed after {read} of {content_length} bytes of body."))
+}
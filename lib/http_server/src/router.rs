@@ -0,0 +1,384 @@
+pub mod path_pattern;
+pub mod request_context;
+
+use crate::api_error::ApiError;
+use crate::client_address::ClientAddress;
+use crate::http_request::{method_name, IntoResponse, RequestResult};
+#[cfg(target_os = "espidf")]
+use crate::http_server::HttpServer;
+use crate::openapi::{inline_schema, schema_ref, Body, Operation};
+use crate::router::path_pattern::{PathParams, PathPattern};
+use crate::router::request_context::RequestContext;
+use embedded_svc::http::server::{Connection, Request};
+use embedded_svc::http::Method;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::http::server::EspHttpConnection;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::io::EspIOError;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::sys::EspError;
+use schemars::JsonSchema;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/* Methods which router is registered for. */
+#[cfg(target_os = "espidf")]
+const ROUTED_METHODS: [Method; 5] = [Method::Get, Method::Post, Method::Put, Method::Patch, Method::Delete];
+
+/**
+ * Connection which routes are served by. Implemented by ESP-IDF connection.
+ */
+pub trait ServerConnection: Connection + ClientAddress {}
+
+impl<C: Connection + ClientAddress> ServerConnection for C {}
+
+/**
+ * Connection types which differ only by lifetimes of request, e.g. &'r mut EspHttpConnection<'c>.
+ * Routes are built once for family and their handlers are called with connection of every request.
+ */
+pub trait ConnectionFamily: 'static {
+    type Error: Debug;
+    type Connection<'r, 'c>: ServerConnection<Error = Self::Error> where 'c: 'r;
+}
+
+/**
+ * Connections of ESP-IDF server.
+ */
+#[cfg(target_os = "espidf")]
+pub struct EspConnections;
+
+#[cfg(target_os = "espidf")]
+impl ConnectionFamily for EspConnections {
+    type Error = EspIOError;
+    type Connection<'r, 'c> = &'r mut EspHttpConnection<'c> where 'c: 'r;
+}
+
+/**
+ * Router mounted in ESP-IDF server.
+ */
+#[cfg(target_os = "espidf")]
+pub type EspRouter<A> = Router<A, EspConnections>;
+
+/**
+ * Credentials required by route. Router checks them before handler is called, so no handler can miss the check.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Access<P> {
+    Public,
+    /* any valid credentials */
+    Authenticated,
+    Permission(P),
+}
+
+/**
+ * Handler of route gets request context with path parameters, state and principal of API.
+ * It accepts connection of any request lifetime, so one route serves every request.
+ */
+pub type RouteHandler<F, A> =
+    for<'r, 'c> fn(RequestContext<'r, 'c, F, <A as Api>::State, <A as Api>::Principal>) -> RequestResult<(), <F as ConnectionFamily>::Error>;
+
+pub struct Route<F: ConnectionFamily, A: Api> {
+    method: Method,
+    pattern: PathPattern,
+    access: Access<A::Permission>,
+    handler: RouteHandler<F, A>,
+    operation: Operation,
+}
+
+impl<F: ConnectionFamily, A: Api> Route<F, A> {
+    pub fn new(method: Method, pattern: &'static str, access: Access<A::Permission>, handler: RouteHandler<F, A>) -> Self {
+        Self { method, pattern: PathPattern::new(pattern), access, handler, operation: Operation::default() }
+    }
+
+    pub fn get(pattern: &'static str, access: Access<A::Permission>, handler: RouteHandler<F, A>) -> Self {
+        Route::new(Method::Get, pattern, access, handler)
+    }
+
+    pub fn post(pattern: &'static str, access: Access<A::Permission>, handler: RouteHandler<F, A>) -> Self {
+        Route::new(Method::Post, pattern, access, handler)
+    }
+
+    pub fn put(pattern: &'static str, access: Access<A::Permission>, handler: RouteHandler<F, A>) -> Self {
+        Route::new(Method::Put, pattern, access, handler)
+    }
+
+    pub fn patch(pattern: &'static str, access: Access<A::Permission>, handler: RouteHandler<F, A>) -> Self {
+        Route::new(Method::Patch, pattern, access, handler)
+    }
+
+    pub fn delete(pattern: &'static str, access: Access<A::Permission>, handler: RouteHandler<F, A>) -> Self {
+        Route::new(Method::Delete, pattern, access, handler)
+    }
+
+    pub fn summary(mut self, summary: &'static str) -> Self {
+        self.operation.summary = Some(summary);
+        self
+    }
+
+    /**
+     * Struct which path parameters are deserialized into. Its fields should be named like parameters of pattern.
+     */
+    pub fn path_params<T: JsonSchema>(mut self) -> Self {
+        self.operation.path = Some(inline_schema::<T>);
+        self
+    }
+
+    pub fn query_params<T: JsonSchema>(mut self) -> Self {
+        self.operation.query = Some(inline_schema::<T>);
+        self
+    }
+
+    pub fn json_body<T: JsonSchema>(mut self) -> Self {
+        self.operation.request = Some(Body::Json(schema_ref::<T>));
+        self
+    }
+
+    /**
+     * Body which handler streams itself, e.g. uploaded file. It is documented as binary.
+     */
+    pub fn media_body(mut self, media_type: &'static str) -> Self {
+        self.operation.request = Some(Body::Media(media_type));
+        self
+    }
+
+    pub fn json_response<T: JsonSchema>(mut self) -> Self {
+        self.operation.response = Some(Body::Json(schema_ref::<T>));
+        self
+    }
+
+    /**
+     * List which can be sent as CSV too. Response data should offer CSV media type.
+     */
+    pub fn table_response<T: JsonSchema>(mut self) -> Self {
+        self.operation.response = Some(Body::Table(schema_ref::<T>));
+        self
+    }
+
+    pub fn text_response(mut self) -> Self {
+        self.operation.response = Some(Body::Text);
+        self
+    }
+
+    pub fn media_response(mut self, media_type: &'static str) -> Self {
+        self.operation.response = Some(Body::Media(media_type));
+        self
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn pattern(&self) -> PathPattern {
+        self.pattern
+    }
+
+    pub fn access(&self) -> Access<A::Permission> {
+        self.access
+    }
+
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+}
+
+/**
+ * Routes of application and the way their requests are authenticated and authorized.
+ * Routes are generic over connection family, so the same handlers serve ESP-IDF and mock connections.
+ */
+pub trait Api: Sized + 'static {
+    type State: Send + Sync + 'static;
+    type Principal;
+    type Permission: Copy + Eq + Debug + Send + Sync;
+
+    /**
+     * Principal of request credentials. None when request has no valid credentials.
+     * Request is mutable, so client address can be read from its connection.
+     */
+    fn authenticate<C: ServerConnection>(request: &mut Request<C>, state: &Self::State) -> Option<Self::Principal>;
+
+    /**
+     * Check permission of authenticated principal.
+     */
+    fn authorize(principal: &Self::Principal, permission: Self::Permission, state: &Self::State) -> bool;
+
+    fn routes<F: ConnectionFamily>() -> Vec<Route<F, Self>>;
+
+    /**
+     * Path prefixes of API versions served side by side, from the oldest, e.g. ["/api/v1", "/api/v2"].
+     * Route of older version is served under newer prefix too, unless newer version has route with the same method and path.
+     * So new version declares only routes whose DTOs changed and old clients keep working during migration.
+     */
+    const VERSIONS: &'static [&'static str] = &[];
+}
+
+/**
+ * Dispatches requests to routes of API. Shared state is passed to every handler in request context.
+ * Routes are built once for family F of connections, see ConnectionFamily.
+ */
+pub struct Router<A: Api, F: ConnectionFamily> {
+    state: Arc<A::State>,
+    routes: Vec<Route<F, A>>,
+}
+
+impl<A: Api, F: ConnectionFamily> Router<A, F> {
+    pub fn new(state: Arc<A::State>) -> Self {
+        Self { state, routes: A::routes::<F>() }
+    }
+
+    /**
+     * Responds with 404 when no route matches path and with 405 when route matches path, but not method.
+     * Responds with 401 or 403 when request credentials don't satisfy access of route.
+     */
+    pub fn handle<'r, 'c>(&self, mut request: Request<F::Connection<'r, 'c>>) -> RequestResult<(), F::Error>
+    where 'c: 'r {
+        /* owned, because request is borrowed mutably by authentication */
+        let path: String = request
+            .uri()
+            .split_once('?')
+            .map_or(request.uri(), |(path, _)| path)
+            .to_string();
+
+        let method: Method = request.method();
+        let mut allowed_methods: Vec<Method> = vec![];
+
+        /* route of requested version has priority over inherited one */
+        let candidates = version_paths(A::VERSIONS, &path)
+            .into_iter()
+            .flat_map(|path| self.routes.iter().map(move |route| (route, route.pattern.matches(&path))));
+
+        for (route, path_params) in candidates {
+            let Some(path_params): Option<PathParams> = path_params else {
+                continue;
+            };
+
+            if route.method != method {
+                if !allowed_methods.contains(&route.method) {
+                    allowed_methods.push(route.method);
+                }
+                continue;
+            }
+
+            let principal: Option<A::Principal> = A::authenticate(&mut request, &self.state);
+
+            match (route.access, &principal) {
+                (Access::Public, _) | (Access::Authenticated, Some(_)) => {}
+                (_, None) => return request.error(&ApiError::unauthorized("invalid_access_token", "Missing or invalid access token.")),
+                (Access::Permission(permission), Some(principal)) => {
+                    if !A::authorize(principal, permission, &self.state) {
+                        return request.error(&ApiError::forbidden("permission_denied", "Permission denied."));
+                    }
+                }
+            }
+
+            let context: RequestContext<F, A::State, A::Principal> = RequestContext::new(request, path_params, Arc::clone(&self.state), principal);
+
+            return (route.handler)(context);
+        }
+
+        if allowed_methods.is_empty() {
+            let message: String = format!("No route for {path}.");
+            return request.error(&ApiError::not_found("route_not_found", message));
+        }
+
+        let allow: String = allowed_methods
+            .into_iter()
+            .map(method_name)
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        request.method_not_allowed(&ApiError::new(405, "method_not_allowed", "Method not allowed."), &allow)
+    }
+}
+
+#[cfg(target_os = "espidf")]
+impl<A: Api> EspRouter<A> {
+    /**
+     * Register router in server for wildcard URI covering all routes, e.g. "/api/" followed by "*".
+     */
+    pub fn mount(self, http_server: &mut HttpServer, uri: &str) -> Result<(), EspError> {
+        let router: Arc<EspRouter<A>> = Arc::new(self);
+
+        for method in ROUTED_METHODS {
+            let router: Arc<EspRouter<A>> = Arc::clone(&router);
+            http_server.add_handler(uri, method, move |request| router.handle(request))?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Path followed by the same path in older versions, from the newest, e.g. "/api/v2/clock" and "/api/v1/clock".
+ */
+pub fn version_paths(versions: &[&str], path: &str) -> Vec<String> {
+    let mut paths: Vec<String> = vec![path.to_string()];
+
+    let Some((index, rest)) = versions
+        .iter()
+        .enumerate()
+        .find_map(|(index, version)| version_suffix(version, path).map(|rest| (index, rest))) else {
+        return paths;
+    };
+
+    paths.extend(
+        versions[..index]
+            .iter()
+            .rev()
+            .map(|version| format!("{version}{rest}"))
+    );
+
+    paths
+}
+
+/**
+ * Path after version prefix, which identifies route in every version, e.g. "/clock" of "/api/v2/clock".
+ * None when path doesn't belong to any version.
+ */
+pub fn unversioned_path<'a>(versions: &[&str], path: &'a str) -> Option<&'a str> {
+    versions
+        .iter()
+        .find_map(|version| version_suffix(version, path))
+}
+
+/**
+ * Routes with every path they are served under, i.e. declared pattern and patterns of newer versions which inherit them.
+ */
+pub fn served_routes<F: ConnectionFamily, A: Api>(routes: &[Route<F, A>]) -> Vec<(String, &Route<F, A>)> {
+    let mut served_routes: Vec<(String, &Route<F, A>)> = vec![];
+
+    for route in routes {
+        let pattern: &str = route.pattern.as_str();
+        served_routes.push((pattern.to_string(), route));
+
+        let Some((index, rest)) = A::VERSIONS
+            .iter()
+            .enumerate()
+            .find_map(|(index, version)| version_suffix(version, pattern).map(|rest| (index, rest))) else {
+            continue;
+        };
+
+        /* inheritance stops at version which declares route itself */
+        for version in &A::VERSIONS[index + 1..] {
+            let inherited_pattern: String = format!("{version}{rest}");
+
+            let is_declared: bool = routes
+                .iter()
+                .any(|declared| declared.method == route.method && declared.pattern.as_str() == inherited_pattern);
+
+            if is_declared {
+                break;
+            }
+
+            served_routes.push((inherited_pattern, route));
+        }
+    }
+
+    served_routes
+}
+
+/* rest of path after version prefix, e.g. "/clock" of "/api/v1/clock" */
+fn version_suffix<'a>(version: &str, path: &'a str) -> Option<&'a str> {
+    path
+        .strip_prefix(version)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
@@ -0,0 +1,71 @@
+use serde::de::DeserializeOwned;
+
+/**
+ * URI path with parameters in braces, e.g. "/api/v1/outputs/{index}/alarms/{id}".
+ * Every parameter matches one non-empty path segment. Trailing slash is ignored.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PathPattern(&'static str);
+
+impl PathPattern {
+    pub const fn new(pattern: &'static str) -> Self {
+        Self(pattern)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /**
+     * Path shouldn't contain query. Returns None when path doesn't match.
+     */
+    pub fn matches(&self, path: &str) -> Option<PathParams> {
+        let mut pattern_segments = self.0.trim_end_matches('/').split('/');
+        let mut path_segments = path.trim_end_matches('/').split('/');
+        let mut path_params: PathParams = PathParams::default();
+
+        loop {
+            match (pattern_segments.next(), path_segments.next()) {
+                (None, None) => return Some(path_params),
+                (Some(pattern_segment), Some(path_segment)) => {
+                    match pattern_segment.strip_prefix('{').and_then(|name| name.strip_suffix('}')) {
+                        Some(_) if path_segment.is_empty() => return None,
+                        Some(name) => path_params.0.push((name, path_segment.to_string())),
+                        None if pattern_segment != path_segment => return None,
+                        None => {}
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/**
+ * Percent-encoded values of path parameters.
+ */
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PathParams(Vec<(&'static str, String)>);
+
+impl PathParams {
+    /**
+     * Parameters are deserialized like URL query, so struct fields should be named like parameters.
+     */
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, serde_urlencoded::de::Error> {
+        let query: String = self.0
+            .iter()
+            .map(|(name, value)| format!("{name}={}", PathParams::escape_query_delimiters(value)))
+            .collect::<Vec<String>>()
+            .join("&");
+
+        serde_urlencoded::from_str(&query)
+    }
+
+    /* characters which are allowed in path segment, but have special meaning in query */
+    fn escape_query_delimiters(value: &str) -> String {
+        value
+            .replace('&', "%26")
+            .replace('=', "%3D")
+            .replace('+', "%2B")
+    }
+}
@@ -0,0 +1,140 @@
+use crate::api_error::ApiError;
+use crate::client_address::ClientAddress;
+use crate::http_request::{IntoResponse, ReadData, RequestError, RequestResult};
+use crate::request_body;
+use crate::router::path_pattern::PathParams;
+use crate::router::ConnectionFamily;
+use crate::to_response_data::ToResponseData;
+use embedded_svc::http::server::Request;
+use serde::de::DeserializeOwned;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/**
+ * Request passed to route handler together with path parameters, shared state and authenticated principal.
+ * Responses are sent the same way as with plain request, e.g. context.ok(&data).
+ * Lifetimes are those of connection of family F, see ConnectionFamily.
+ */
+pub struct RequestContext<'r, 'c, F: ConnectionFamily, S, P>
+where 'c: 'r {
+    request: Request<F::Connection<'r, 'c>>,
+    path_params: PathParams,
+    state: Arc<S>,
+    principal: Option<P>,
+}
+
+impl<'r, 'c, F: ConnectionFamily, S, P> RequestContext<'r, 'c, F, S, P>
+where 'c: 'r {
+    pub fn new(request: Request<F::Connection<'r, 'c>>, path_params: PathParams, state: Arc<S>, principal: Option<P>) -> Self {
+        Self { request, path_params, state, principal }
+    }
+
+    /**
+     * Shared pointer, so state can be used while response is sent by context.
+     */
+    pub fn state(&self) -> Arc<S> {
+        Arc::clone(&self.state)
+    }
+
+    /**
+     * None when request has no valid credentials.
+     */
+    pub fn principal(&self) -> Option<&P> {
+        self.principal.as_ref()
+    }
+
+    /**
+     * Path parameters of route pattern, e.g. struct with fields index and id for "/api/v1/outputs/{index}/alarms/{id}".
+     */
+    pub fn path<T: DeserializeOwned>(&self) -> RequestResult<T, F::Error> {
+        self.path_params
+            .deserialize()
+            .map_err(RequestError::SerdeURL)
+    }
+
+    /**
+     * Missing query is deserialized like empty one, so structs with optional fields don't need it.
+     */
+    pub fn query<T: DeserializeOwned>(&self) -> RequestResult<T, F::Error> {
+        let query: &str = self.request
+            .uri()
+            .split_once('?')
+            .map_or("", |(_, query)| query);
+
+        serde_urlencoded::from_str(query).map_err(RequestError::SerdeURL)
+    }
+
+    pub fn json<T: DeserializeOwned>(&mut self) -> RequestResult<T, F::Error> {
+        self.request.body()
+    }
+
+    /**
+     * Body is passed to sink piece by piece, so uploads larger than memory can be stored, e.g. on disk.
+     */
+    pub fn stream_body<Sink>(&mut self, max_size: usize, sink: Sink) -> RequestResult<usize, F::Error>
+    where Sink: FnMut(&[u8]) -> RequestResult<(), F::Error> {
+        request_body::stream_body(&mut self.request, max_size, sink)
+    }
+
+    pub fn request(&self) -> &Request<F::Connection<'r, 'c>> {
+        &self.request
+    }
+
+    pub fn request_mut(&mut self) -> &mut Request<F::Connection<'r, 'c>> {
+        &mut self.request
+    }
+
+    pub fn into_request(self) -> Request<F::Connection<'r, 'c>> {
+        self.request
+    }
+}
+
+impl<'r, 'c, F: ConnectionFamily, S, P> ClientAddress for RequestContext<'r, 'c, F, S, P>
+where 'c: 'r {
+    fn client_ip(&mut self) -> Option<IpAddr> {
+        self.request.client_ip()
+    }
+}
+
+impl<'r, 'c, F: ConnectionFamily, S, P> IntoResponse<F::Connection<'r, 'c>> for RequestContext<'r, 'c, F, S, P>
+where 'c: 'r {
+    fn ok<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.ok(data)
+    }
+
+    fn bad_request<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.bad_request(data)
+    }
+
+    fn not_found<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.not_found(data)
+    }
+
+    fn unauthorized<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.unauthorized(data)
+    }
+
+    fn forbidden<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.forbidden(data)
+    }
+
+    fn method_not_allowed<Data: ToResponseData>(self, data: &Data, allowed_methods: &str) -> RequestResult<(), F::Error> {
+        self.request.method_not_allowed(data, allowed_methods)
+    }
+
+    fn too_many_requests<Data: ToResponseData>(self, data: &Data, retry_after_seconds: u64) -> RequestResult<(), F::Error> {
+        self.request.too_many_requests(data, retry_after_seconds)
+    }
+
+    fn status<Data: ToResponseData>(self, status: u16, message: &str, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.status(status, message, data)
+    }
+
+    fn internal_server_error<Data: ToResponseData>(self, data: &Data) -> RequestResult<(), F::Error> {
+        self.request.internal_server_error(data)
+    }
+
+    fn error(self, error: &ApiError) -> RequestResult<(), F::Error> {
+        self.request.error(error)
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/**
+ * Version of static file used for conditional and range requests. Files on SD card are identified by length and
+ * modification time, because hashing content would mean reading whole file for every request.
+ * Modification time is unknown when file system stores invalid date, then file is never considered unmodified by date.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StaticFile {
+    pub length: u64,
+    pub modified_seconds: Option<u64>,
+    /* gzip sibling is different representation, so it has different entity tag */
+    pub gzip: bool,
+}
+
+/**
+ * Part of file to send. Range is inclusive, like in Content-Range header.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ByteRange {
+    Full,
+    Partial { start: u64, end: u64 },
+    Unsatisfiable,
+}
+
+impl StaticFile {
+    pub fn etag(&self) -> String {
+        let encoding: &str = if self.gzip { "-gzip" } else { "" };
+
+        format!("\"{:x}-{:x}{encoding}\"", self.length, self.modified_seconds.unwrap_or(0))
+    }
+
+    /**
+     * Value of Last-Modified header, e.g. "Tue, 15 Nov 1994 08:12:31 GMT". Header isn't sent when time is unknown.
+     */
+    pub fn last_modified(&self) -> Option<String> {
+        self.modified().map(httpdate::fmt_http_date)
+    }
+
+    /**
+     * If-None-Match has priority over If-Modified-Since. Entity tags are compared weakly, as required for GET.
+     */
+    pub fn is_not_modified(&self, if_none_match: Option<&str>, if_modified_since: Option<&str>) -> bool {
+        if let Some(if_none_match) = if_none_match {
+            let etag: String = self.etag();
+
+            return if_none_match
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag);
+        }
+
+        let Some(if_modified_since) = if_modified_since.and_then(|date| httpdate::parse_http_date(date).ok()) else {
+            return false;
+        };
+
+        self.modified().is_some_and(|modified| modified <= if_modified_since)
+    }
+
+    /**
+     * Only single range is supported, e.g. "bytes=0-1023", "bytes=1024-" or "bytes=-512". Whole file is sent for multiple
+     * or malformed ranges and when If-Range doesn't match current version.
+     */
+    pub fn range(&self, range: Option<&str>, if_range: Option<&str>) -> ByteRange {
+        let Some(range) = range.and_then(|range| range.trim().strip_prefix("bytes=")) else {
+            return ByteRange::Full;
+        };
+
+        if if_range.is_some_and(|if_range| !self.matches_if_range(if_range)) || range.contains(',') {
+            return ByteRange::Full;
+        }
+
+        let Some((start, end)) = range.split_once('-') else {
+            return ByteRange::Full;
+        };
+
+        let (start, end): (&str, &str) = (start.trim(), end.trim());
+
+        let (start, end): (u64, u64) = match (start.parse::<u64>(), end.parse::<u64>()) {
+            /* suffix range, i.e. last bytes of file */
+            (Err(_), Ok(suffix_length)) if start.is_empty() => {
+                if suffix_length == 0 || self.length == 0 {
+                    return ByteRange::Unsatisfiable;
+                }
+
+                (self.length.saturating_sub(suffix_length), self.length - 1)
+            }
+            (Ok(start), Err(_)) if end.is_empty() => (start, self.length.saturating_sub(1)),
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(self.length.saturating_sub(1))),
+            _ => return ByteRange::Full,
+        };
+
+        if start >= self.length {
+            return ByteRange::Unsatisfiable;
+        }
+
+        ByteRange::Partial { start, end }
+    }
+
+    /* If-Range is either entity tag or date, date must match exactly */
+    fn matches_if_range(&self, if_range: &str) -> bool {
+        let if_range: &str = if_range.trim();
+
+        if if_range.starts_with('"') {
+            return if_range == self.etag();
+        }
+
+        httpdate::parse_http_date(if_range)
+            .is_ok_and(|date| self.modified() == Some(date))
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        self.modified_seconds.map(|modified_seconds| UNIX_EPOCH + Duration::from_secs(modified_seconds))
+    }
+}
+
+/**
+ * Accept-Encoding allows gzip unless its quality is zero, e.g. "gzip, deflate, br" or "gzip;q=0.5".
+ */
+pub fn accepts_gzip(accept_encoding: Option<&str>) -> bool {
+    let Some(accept_encoding) = accept_encoding else {
+        return false;
+    };
+
+    accept_encoding
+        .split(',')
+        .filter_map(|coding| {
+            let mut parameters = coding.split(';');
+            let name: &str = parameters.next()?.trim();
+
+            let quality: f32 = parameters
+                .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                .find_map(|quality| quality.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            (name.eq_ignore_ascii_case("gzip") || name == "*").then_some((name, quality))
+        })
+        /* explicit gzip has priority over wildcard */
+        .max_by_key(|(name, _)| name.eq_ignore_ascii_case("gzip"))
+        .is_some_and(|(_, quality)| quality > 0.0)
+}
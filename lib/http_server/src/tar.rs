@@ -0,0 +1,222 @@
+use std::fmt::{Display, Formatter};
+
+const BLOCK_SIZE: usize = 512;
+
+/**
+ * Part of archive passed to sink. Content of file follows its File entry in one or more Data entries and ends with
+ * FileEnd, so files are written without holding whole archive in memory.
+ */
+#[derive(Debug, Eq, PartialEq)]
+pub enum TarEntry<'a> {
+    Directory { path: &'a str },
+    File { path: &'a str, size: u64 },
+    Data(&'a [u8]),
+    FileEnd,
+}
+
+#[derive(Debug)]
+pub enum TarError<E> {
+    InvalidHeader(String),
+    Truncated,
+    Sink(E),
+}
+
+impl<E: Display> Display for TarError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TarError::InvalidHeader(message) => write!(f, "Invalid archive header: {message}"),
+            TarError::Truncated => f.write_str("Archive is truncated."),
+            TarError::Sink(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+enum State {
+    Header { filled: usize },
+    /* content of regular file is passed to sink, content of other entries is skipped */
+    Content { remaining: u64, padding: usize, is_file: bool },
+    Padding { remaining: usize },
+    End,
+}
+
+/**
+ * Streaming reader of ustar and GNU tar archives, fed with body of request piece by piece.
+ * Regular files and directories are passed to sink, other entries like links and extended headers are skipped.
+ */
+pub struct TarReader {
+    header: [u8; BLOCK_SIZE],
+    state: State,
+}
+
+impl Default for TarReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TarReader {
+    pub fn new() -> Self {
+        Self { header: [0; BLOCK_SIZE], state: State::Header { filled: 0 } }
+    }
+
+    pub fn feed<E, F>(&mut self, mut data: &[u8], sink: &mut F) -> Result<(), TarError<E>>
+    where F: FnMut(TarEntry) -> Result<(), E> {
+        while !data.is_empty() {
+            match self.state {
+                State::Header { filled } => {
+                    let length: usize = data.len().min(BLOCK_SIZE - filled);
+                    self.header[filled..filled + length].copy_from_slice(&data[..length]);
+                    data = &data[length..];
+
+                    self.state =
+                        if filled + length == BLOCK_SIZE {
+                            self.read_header(sink)?
+                        } else {
+                            State::Header { filled: filled + length }
+                        };
+                }
+                State::Content { remaining, padding, is_file } => {
+                    let length: usize = data.len().min(usize::try_from(remaining).unwrap_or(usize::MAX));
+
+                    if is_file {
+                        sink(TarEntry::Data(&data[..length])).map_err(TarError::Sink)?;
+                    }
+
+                    data = &data[length..];
+                    let remaining: u64 = remaining - length as u64;
+
+                    if remaining > 0 {
+                        self.state = State::Content { remaining, padding, is_file };
+                    } else {
+                        self.state = end_of_content(padding, is_file, sink)?;
+                    }
+                }
+                State::Padding { remaining } => {
+                    let length: usize = data.len().min(remaining);
+                    data = &data[length..];
+
+                    self.state =
+                        if remaining > length {
+                            State::Padding { remaining: remaining - length }
+                        } else {
+                            State::Header { filled: 0 }
+                        };
+                }
+                /* anything after end of archive is ignored, e.g. second zero block and record padding */
+                State::End => return Ok(()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Checks that archive doesn't end in the middle of entry. Archives without end-of-archive blocks are accepted.
+     */
+    pub fn finish<E>(&self) -> Result<(), TarError<E>> {
+        match self.state {
+            State::Header { filled: 0 } | State::End => Ok(()),
+            _ => Err(TarError::Truncated),
+        }
+    }
+
+    fn read_header<E, F>(&self, sink: &mut F) -> Result<State, TarError<E>>
+    where F: FnMut(TarEntry) -> Result<(), E> {
+        let header: &[u8; BLOCK_SIZE] = &self.header;
+
+        if header.iter().all(|byte| *byte == 0) {
+            return Ok(State::End);
+        }
+
+        /* checksum is computed with checksum field filled with spaces */
+        let checksum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(index, byte)| if (148..156).contains(&index) { b' ' as u64 } else { *byte as u64 })
+            .sum();
+
+        if parse_octal(&header[148..156])? != checksum {
+            return Err(TarError::InvalidHeader("checksum mismatch".to_string()));
+        }
+
+        let size: u64 = parse_octal(&header[124..136])?;
+        let padding: usize = (BLOCK_SIZE - (size % BLOCK_SIZE as u64) as usize) % BLOCK_SIZE;
+
+        let name: &str = parse_string(&header[0..100])?;
+        /* GNU format has "ustar  " magic and other fields in place of prefix */
+        let prefix: &str =
+            if &header[257..263] == b"ustar\0" {
+                parse_string(&header[345..500])?
+            } else {
+                ""
+            };
+
+        let path: String =
+            if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{prefix}/{name}")
+            };
+
+        let path: &str = path.trim_start_matches("./").trim_end_matches('/');
+
+        match header[156] {
+            b'0' | 0 => {
+                sink(TarEntry::File { path, size }).map_err(TarError::Sink)?;
+
+                if size == 0 {
+                    return end_of_content(padding, true, sink);
+                }
+
+                Ok(State::Content { remaining: size, padding, is_file: true })
+            }
+            b'5' => {
+                /* archive root is not an entry of its own */
+                if !path.is_empty() && path != "." {
+                    sink(TarEntry::Directory { path }).map_err(TarError::Sink)?;
+                }
+
+                Ok(State::Header { filled: 0 })
+            }
+            _ if size == 0 => Ok(State::Header { filled: 0 }),
+            _ => Ok(State::Content { remaining: size, padding, is_file: false }),
+        }
+    }
+}
+
+fn end_of_content<E, F>(padding: usize, is_file: bool, sink: &mut F) -> Result<State, TarError<E>>
+where F: FnMut(TarEntry) -> Result<(), E> {
+    if is_file {
+        sink(TarEntry::FileEnd).map_err(TarError::Sink)?;
+    }
+
+    if padding > 0 {
+        Ok(State::Padding { remaining: padding })
+    } else {
+        Ok(State::Header { filled: 0 })
+    }
+}
+
+/* numeric fields are octal, terminated by NUL or space */
+fn parse_octal<E>(field: &[u8]) -> Result<u64, TarError<E>> {
+    /* GNU base-256 encoding is used for sizes over 8 GiB only */
+    if field.first().is_some_and(|byte| byte & 0x80 != 0) {
+        return Err(TarError::InvalidHeader("entry is too large".to_string()));
+    }
+
+    let digits: &str = std::str::from_utf8(field)
+        .map_err(|_| TarError::InvalidHeader("invalid number".to_string()))?
+        .trim_matches(|character: char| character == '\0' || character == ' ');
+
+    if digits.is_empty() {
+        return Ok(0);
+    }
+
+    u64::from_str_radix(digits, 8).map_err(|_| TarError::InvalidHeader(format!("invalid number '{digits}'")))
+}
+
+fn parse_string<E>(field: &[u8]) -> Result<&str, TarError<E>> {
+    let length: usize = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+
+    std::str::from_utf8(&field[..length]).map_err(|_| TarError::InvalidHeader("name is not UTF-8".to_string()))
+}
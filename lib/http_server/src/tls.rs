@@ -0,0 +1,280 @@
+use crate::api_error::ApiError;
+use p256::ecdsa::{DerSignature, SigningKey};
+use p256::pkcs8::{EncodePrivateKey, LineEnding, ObjectIdentifier, PrivateKeyInfo};
+use p256::{NistP256, PublicKey, SecretKey};
+use rand_core::{OsRng, RngCore};
+use std::fmt::{Display, Formatter};
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+use std::time::Duration;
+use x509_cert::builder::{Builder, CertificateBuilder, Profile};
+use x509_cert::der::asn1::{OctetString, UtcTime};
+use x509_cert::der::{DateTime, Decode};
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::{ExtendedKeyUsage, SubjectAltName};
+use x509_cert::name::Name;
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::der::oid::AssociatedOid;
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+use x509_cert::time::{Time, Validity};
+use x509_cert::Certificate;
+
+const EC_PUBLIC_KEY_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const RSA_ENCRYPTION_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const SERVER_AUTH_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.3.1");
+
+/* Device clock may be unset when certificate is generated, so validity doesn't depend on it. */
+const SELF_SIGNED_VALID_FROM_YEAR: u16 = 2024;
+const SELF_SIGNED_VALID_TO_YEAR: u16 = 2049;
+
+#[derive(Debug)]
+pub enum TlsError {
+    InvalidCertificate(String),
+    InvalidPrivateKey(String),
+    UnsupportedPrivateKey(String),
+    KeyMismatch,
+    Generation(String),
+}
+
+impl Display for TlsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsError::InvalidCertificate(message) => write!(f, "Invalid certificate: {message}"),
+            TlsError::InvalidPrivateKey(message) => write!(f, "Invalid private key: {message}"),
+            TlsError::UnsupportedPrivateKey(message) => write!(f, "Unsupported private key: {message}. Use RSA or EC P-256 key."),
+            TlsError::KeyMismatch => write!(f, "Private key doesn't match certificate."),
+            TlsError::Generation(message) => write!(f, "Can't generate certificate: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {}
+
+impl From<TlsError> for ApiError {
+    fn from(error: TlsError) -> Self {
+        match error {
+            TlsError::Generation(_) => ApiError::internal_server_error("tls_generation_failed", error.to_string()),
+            _ => ApiError::bad_request("invalid_tls_certificate", error.to_string()),
+        }
+    }
+}
+
+pub type TlsResult<T> = Result<T, TlsError>;
+
+/**
+ * PEM encoded server certificate (optionally followed by intermediate certificates) and its private key.
+ * Instances are always valid: certificate chain is parsed and private key matches the first certificate.
+ */
+#[derive(Clone)]
+pub struct TlsCertificate {
+    certificate_pem: String,
+    private_key_pem: String,
+    certificate: Certificate,
+}
+
+impl TlsCertificate {
+    pub fn new(certificate_pem: String, private_key_pem: String) -> TlsResult<Self> {
+        let certificate: Certificate = Certificate::load_pem_chain(certificate_pem.as_bytes())
+            .map_err(|error| TlsError::InvalidCertificate(error.to_string()))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| TlsError::InvalidCertificate("No certificate found.".to_string()))?;
+
+        TlsCertificate::check_key_pair(&certificate, &private_key_pem)?;
+
+        Ok(Self { certificate_pem, private_key_pem, certificate })
+    }
+
+    /**
+     * ECDSA P-256 certificate for common name and IP addresses the device is reachable at.
+     */
+    pub fn generate_self_signed(common_name: &str, ip_addresses: &[Ipv4Addr]) -> TlsResult<Self> {
+        let generation_error = |error: &dyn Display| TlsError::Generation(error.to_string());
+
+        let secret_key: SecretKey = SecretKey::random(&mut OsRng);
+        let signing_key: SigningKey = SigningKey::from(&secret_key);
+
+        let subject: Name = Name::from_str(&format!("CN={}", TlsCertificate::escape_name_value(common_name)))
+            .map_err(|error| generation_error(&error))?;
+
+        /* serial number should be positive and shouldn't start with zero byte */
+        let mut serial_number: [u8; 16] = [0; 16];
+        OsRng.fill_bytes(&mut serial_number);
+        serial_number[0] = (serial_number[0] & 0x7F) | 0x40;
+
+        let profile: Profile = Profile::Leaf {
+            issuer: subject.clone(),
+            enable_key_agreement: false,
+            enable_key_encipherment: false,
+        };
+
+        let public_key_info: SubjectPublicKeyInfoOwned = SubjectPublicKeyInfoOwned::from_key(secret_key.public_key())
+            .map_err(|error| generation_error(&error))?;
+
+        let mut builder: CertificateBuilder<SigningKey> = CertificateBuilder::new(
+            profile,
+            SerialNumber::new(&serial_number).map_err(|error| generation_error(&error))?,
+            TlsCertificate::self_signed_validity().map_err(|error| generation_error(&error))?,
+            subject,
+            public_key_info,
+            &signing_key,
+        ).map_err(|error| generation_error(&error))?;
+
+        builder
+            .add_extension(&ExtendedKeyUsage(vec![SERVER_AUTH_OID]))
+            .map_err(|error| generation_error(&error))?;
+
+        if !ip_addresses.is_empty() {
+            let alternative_names: Vec<GeneralName> = ip_addresses
+                .iter()
+                .map(|ip_address| OctetString::new(ip_address.octets().to_vec()).map(GeneralName::IpAddress))
+                .collect::<Result<_, _>>()
+                .map_err(|error| generation_error(&error))?;
+
+            builder
+                .add_extension(&SubjectAltName(alternative_names))
+                .map_err(|error| generation_error(&error))?;
+        }
+
+        let certificate: Certificate = builder
+            .build::<DerSignature>()
+            .map_err(|error| generation_error(&error))?;
+
+        let certificate_pem: String = x509_cert::der::EncodePem::to_pem(&certificate, LineEnding::LF)
+            .map_err(|error| generation_error(&error))?;
+
+        let private_key_pem: String = secret_key
+            .to_pkcs8_pem(LineEnding::LF)
+            .map_err(|error| generation_error(&error))?
+            .to_string();
+
+        Ok(Self { certificate_pem, private_key_pem, certificate })
+    }
+
+    pub fn certificate_pem(&self) -> &str {
+        &self.certificate_pem
+    }
+
+    pub fn private_key_pem(&self) -> &str {
+        &self.private_key_pem
+    }
+
+    pub fn subject(&self) -> String {
+        self.certificate.tbs_certificate.subject.to_string()
+    }
+
+    pub fn issuer(&self) -> String {
+        self.certificate.tbs_certificate.issuer.to_string()
+    }
+
+    pub fn is_self_signed(&self) -> bool {
+        self.certificate.tbs_certificate.subject == self.certificate.tbs_certificate.issuer
+    }
+
+    pub fn not_before(&self) -> Duration {
+        self.certificate.tbs_certificate.validity.not_before.to_unix_duration()
+    }
+
+    pub fn not_after(&self) -> Duration {
+        self.certificate.tbs_certificate.validity.not_after.to_unix_duration()
+    }
+
+    fn self_signed_validity() -> x509_cert::der::Result<Validity> {
+        let not_before: DateTime = DateTime::new(SELF_SIGNED_VALID_FROM_YEAR, 1, 1, 0, 0, 0)?;
+        let not_after: DateTime = DateTime::new(SELF_SIGNED_VALID_TO_YEAR, 12, 31, 23, 59, 59)?;
+
+        Ok(Validity {
+            not_before: Time::UtcTime(UtcTime::from_date_time(not_before)?),
+            not_after: Time::UtcTime(UtcTime::from_date_time(not_after)?),
+        })
+    }
+
+    /**
+     * RFC 4514 escaping, so any device name can be used as common name.
+     */
+    fn escape_name_value(value: &str) -> String {
+        let mut escaped: String = String::with_capacity(value.len());
+
+        for (index, character) in value.chars().enumerate() {
+            let is_special: bool = matches!(character, ',' | '+' | '"' | '\\' | '<' | '>' | ';' | '=')
+                || (index == 0 && matches!(character, ' ' | '#'))
+                || (index == value.chars().count() - 1 && character == ' ');
+
+            if is_special {
+                escaped.push('\\');
+            }
+            escaped.push(character);
+        }
+
+        escaped
+    }
+
+    /**
+     * Supported keys: PKCS#8 ("PRIVATE KEY") with RSA or EC P-256 key, SEC1 ("EC PRIVATE KEY") and PKCS#1 ("RSA PRIVATE KEY").
+     */
+    fn check_key_pair(certificate: &Certificate, private_key_pem: &str) -> TlsResult<()> {
+        let (label, private_key_der) = x509_cert::der::pem::decode_vec(private_key_pem.trim().as_bytes())
+            .map_err(|error| TlsError::InvalidPrivateKey(error.to_string()))?;
+
+        let public_key_info: &SubjectPublicKeyInfoOwned = &certificate.tbs_certificate.subject_public_key_info;
+        let certificate_public_key: &[u8] = public_key_info.subject_public_key.raw_bytes();
+
+        let matches: bool =
+            match label {
+                "PRIVATE KEY" => {
+                    let private_key_info: PrivateKeyInfo = PrivateKeyInfo::from_der(&private_key_der)
+                        .map_err(|error| TlsError::InvalidPrivateKey(error.to_string()))?;
+
+                    if private_key_info.algorithm.oid != public_key_info.algorithm.oid {
+                        return Err(TlsError::KeyMismatch);
+                    }
+
+                    match private_key_info.algorithm.oid {
+                        EC_PUBLIC_KEY_OID => TlsCertificate::ec_key_matches(private_key_info.private_key, certificate_public_key)?,
+                        RSA_ENCRYPTION_OID => TlsCertificate::rsa_key_matches(private_key_info.private_key, certificate_public_key)?,
+                        oid => return Err(TlsError::UnsupportedPrivateKey(format!("algorithm {oid}"))),
+                    }
+                }
+                "EC PRIVATE KEY" => TlsCertificate::ec_key_matches(&private_key_der, certificate_public_key)?,
+                "RSA PRIVATE KEY" => TlsCertificate::rsa_key_matches(&private_key_der, certificate_public_key)?,
+                label => return Err(TlsError::UnsupportedPrivateKey(format!("PEM label '{label}'"))),
+            };
+
+        if matches {
+            Ok(())
+        } else {
+            Err(TlsError::KeyMismatch)
+        }
+    }
+
+    fn ec_key_matches(sec1_der: &[u8], certificate_public_key: &[u8]) -> TlsResult<bool> {
+        let private_key: sec1::EcPrivateKey = sec1::EcPrivateKey::from_der(sec1_der)
+            .map_err(|error| TlsError::InvalidPrivateKey(error.to_string()))?;
+
+        let curve: Option<ObjectIdentifier> = private_key.parameters.and_then(|parameters| parameters.named_curve());
+
+        if curve.is_some_and(|curve| curve != NistP256::OID) {
+            return Err(TlsError::UnsupportedPrivateKey(format!("curve {}", curve.unwrap_or(NistP256::OID))));
+        }
+
+        let secret_key: SecretKey = SecretKey::from_slice(private_key.private_key)
+            .map_err(|error| TlsError::InvalidPrivateKey(error.to_string()))?;
+
+        let Ok(public_key) = PublicKey::from_sec1_bytes(certificate_public_key) else {
+            return Ok(false);
+        };
+
+        Ok(secret_key.public_key() == public_key)
+    }
+
+    fn rsa_key_matches(pkcs1_der: &[u8], certificate_public_key: &[u8]) -> TlsResult<bool> {
+        let private_key: pkcs1::RsaPrivateKey = pkcs1::RsaPrivateKey::from_der(pkcs1_der)
+            .map_err(|error| TlsError::InvalidPrivateKey(error.to_string()))?;
+
+        let Ok(public_key) = pkcs1::RsaPublicKey::from_der(certificate_public_key) else {
+            return Ok(false);
+        };
+
+        Ok(private_key.public_key() == public_key)
+    }
+}
@@ -1,22 +1,91 @@
-use std::collections::HashMap;
+use crate::media_type::MediaType;
 use serde::Serialize;
+use serde_json::Value;
 
+/**
+ * Data of response. Media type is negotiated from media types of data and Accept header of request.
+ */
 pub trait ToResponseData where Self: Serialize {
-    fn to_response_data(&self) -> String {
-        serde_json::to_string(&self).unwrap_or_default()
+    /**
+     * Media types data can be written as. The first one is used when request has no Accept header.
+     */
+    fn media_types(&self) -> &'static [MediaType] {
+        &[MediaType::Json, MediaType::Cbor]
+    }
+
+    /**
+     * Media type is one of media types of data.
+     */
+    fn to_response_data(&self, media_type: MediaType) -> Vec<u8> {
+        serialize(self, media_type)
     }
 }
 
+/* Messages are plain text unless client asks for structured format. */
+const TEXT_MEDIA_TYPES: &[MediaType] = &[MediaType::Text, MediaType::Json, MediaType::Cbor];
+
 impl ToResponseData for String {
-    fn to_response_data(&self) -> String {
-        self.to_string()
+    fn media_types(&self) -> &'static [MediaType] {
+        TEXT_MEDIA_TYPES
+    }
+
+    fn to_response_data(&self, media_type: MediaType) -> Vec<u8> {
+        self.as_str().to_response_data(media_type)
     }
 }
 
 impl ToResponseData for &str {
-    fn to_response_data(&self) -> String {
-        self.to_string()
+    fn media_types(&self) -> &'static [MediaType] {
+        TEXT_MEDIA_TYPES
+    }
+
+    fn to_response_data(&self, media_type: MediaType) -> Vec<u8> {
+        match media_type {
+            MediaType::Text => self.as_bytes().to_vec(),
+            _ => serialize(self, media_type),
+        }
     }
 }
 
 impl<T: Serialize> ToResponseData for Vec<T> {}
+
+impl ToResponseData for Value {}
+
+/**
+ * Data as JSON or CBOR. Serialization of response data isn't expected to fail, so failure is logged and body is empty.
+ */
+pub fn serialize<T: Serialize + ?Sized>(data: &T, media_type: MediaType) -> Vec<u8> {
+    let result: Result<Vec<u8>, String> = match media_type {
+        MediaType::Json => serde_json::to_vec(data).map_err(|error| error.to_string()),
+        MediaType::Cbor => {
+            let mut buffer: Vec<u8> = vec![];
+
+            ciborium::into_writer(data, &mut buffer)
+                .map(|_| buffer)
+                .map_err(|error| error.to_string())
+        }
+        /* text formats are written by data types which offer them */
+        MediaType::Csv | MediaType::Text => Err(format!("Data can't be serialized as {}.", media_type.essence())),
+    };
+
+    result.unwrap_or_else(|error| {
+        log::error!("Can't serialize response data: {error}");
+        vec![]
+    })
+}
+
+/**
+ * Rows as CSV with header made of field names. Rows should be flat structs, nested ones can't be written as CSV.
+ */
+pub fn to_csv<T: Serialize>(rows: &[T]) -> Vec<u8> {
+    let mut writer: csv::Writer<Vec<u8>> = csv::Writer::from_writer(vec![]);
+
+    for row in rows {
+        if let Err(error) = writer.serialize(row) {
+            log::error!("Can't write CSV row: {error}");
+            return vec![];
+        }
+    }
+
+    writer.into_inner().unwrap_or_default()
+}
@@ -0,0 +1,152 @@
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::mock_connection::{MockConnection, MockConnections, MockRouter};
+use http_server::openapi::OpenApi;
+use http_server::router::request_context::RequestContext;
+use http_server::router::{unversioned_path, version_paths, Access, Api, ConnectionFamily, Route, ServerConnection};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+struct ClockDTO {
+    timestamp_millis: i64,
+}
+
+/* v2 sends time in seconds */
+#[derive(Deserialize, Serialize, JsonSchema)]
+struct ClockV2DTO {
+    timestamp_seconds: i64,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct OutputIndexDTO {
+    output_index: u8,
+}
+
+/* v2 changes clock only, v3 changes nothing yet */
+struct VersionedApi;
+
+impl Api for VersionedApi {
+    type State = ();
+    type Principal = ();
+    type Permission = ();
+
+    fn authenticate<C: ServerConnection>(_request: &mut Request<C>, _state: &()) -> Option<()> {
+        None
+    }
+
+    fn authorize(_principal: &(), _permission: (), _state: &()) -> bool {
+        false
+    }
+
+    fn routes<C: ConnectionFamily>() -> Vec<Route<C, Self>> {
+        vec![
+            Route::get("/api/v1/clock", Access::Public, get_clock)
+                .json_response::<ClockDTO>(),
+            Route::put("/api/v1/clock", Access::Public, set_clock)
+                .json_body::<ClockDTO>()
+                .text_response(),
+            Route::get("/api/v1/outputs/{output_index}", Access::Public, get_output)
+                .path_params::<OutputIndexDTO>()
+                .text_response(),
+            Route::get("/api/v2/clock", Access::Public, get_clock_v2)
+                .json_response::<ClockV2DTO>(),
+        ]
+    }
+
+    const VERSIONS: &'static [&'static str] = &["/api/v1", "/api/v2", "/api/v3"];
+}
+
+fn get_clock<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&"v1")
+}
+
+fn set_clock<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&"Clock set")
+}
+
+fn get_output<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    let OutputIndexDTO { output_index } = context.path()?;
+
+    context.ok(&format!("output {output_index}"))
+}
+
+fn get_clock_v2<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&"v2")
+}
+
+fn send(method: Method, uri: &str) -> MockConnection {
+    let router: MockRouter<VersionedApi> = MockRouter::new(Arc::new(()));
+    let mut connection: MockConnection = MockConnection::new(method, uri);
+
+    router.handle(Request::wrap(&mut connection)).unwrap();
+
+    connection
+}
+
+#[test]
+fn path_is_looked_up_in_older_versions() {
+    let versions: &[&str] = &["/api/v1", "/api/v2", "/api/v3"];
+
+    assert_eq!(version_paths(versions, "/api/v3/clock"), vec!["/api/v3/clock", "/api/v2/clock", "/api/v1/clock"]);
+    assert_eq!(version_paths(versions, "/api/v1/clock"), vec!["/api/v1/clock"]);
+    /* prefix must end at segment boundary */
+    assert_eq!(version_paths(versions, "/api/v10/clock"), vec!["/api/v10/clock"]);
+}
+
+#[test]
+fn version_prefix_is_removed_from_path() {
+    let versions: &[&str] = &["/api/v1", "/api/v2"];
+
+    assert_eq!(unversioned_path(versions, "/api/v2/login"), Some("/login"));
+    assert_eq!(unversioned_path(versions, "/api/v1/login"), Some("/login"));
+    assert_eq!(unversioned_path(versions, "/api/v10/login"), None);
+    assert_eq!(unversioned_path(versions, "/index.htm"), None);
+}
+
+#[test]
+fn newer_version_overrides_route_of_older_one() {
+    assert_eq!(send(Method::Get, "/api/v1/clock").response_text(), "v1");
+    assert_eq!(send(Method::Get, "/api/v2/clock").response_text(), "v2");
+    assert_eq!(send(Method::Get, "/api/v3/clock").response_text(), "v2");
+}
+
+#[test]
+fn routes_which_newer_version_doesnt_declare_are_inherited() {
+    let connection: MockConnection = send(Method::Get, "/api/v3/outputs/1");
+    assert_eq!(connection.status(), Some(200));
+    assert_eq!(connection.response_text(), "output 1");
+
+    /* inheritance is decided per method */
+    assert_eq!(send(Method::Put, "/api/v2/clock").response_text(), "Clock set");
+
+    let connection: MockConnection = send(Method::Delete, "/api/v2/clock");
+    assert_eq!(connection.status(), Some(405));
+    assert_eq!(connection.response_header("Allow"), Some("GET, PUT"));
+
+    assert_eq!(send(Method::Get, "/api/v4/clock").status(), Some(404));
+}
+
+#[test]
+fn inherited_routes_are_documented_under_every_version() {
+    let document: Value = OpenApi::new("Test API", "1.0.0")
+        .document::<VersionedApi, MockConnections>()
+        .unwrap();
+
+    let paths: Vec<&String> = document["paths"].as_object().unwrap().keys().collect();
+    assert_eq!(paths, vec![
+        "/api/v1/clock",
+        "/api/v1/outputs/{output_index}",
+        "/api/v2/clock",
+        "/api/v2/outputs/{output_index}",
+        "/api/v3/clock",
+        "/api/v3/outputs/{output_index}",
+    ]);
+
+    let clock: &Value = &document["paths"]["/api/v3/clock"];
+    assert_eq!(clock["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/ClockV2DTO");
+    assert!(clock["put"].is_object());
+}
@@ -0,0 +1,197 @@
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::media_type::MediaType;
+use http_server::mock_connection::{MockConnection, MockRouter};
+use http_server::router::request_context::RequestContext;
+use http_server::router::{Access, Api, ConnectionFamily, Route, ServerConnection};
+use http_server::to_response_data::{serialize, to_csv, ToResponseData};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct AlarmDTO {
+    output_index: u8,
+    identifier: String,
+    impulse_length_millis: u64,
+}
+
+impl ToResponseData for AlarmDTO {}
+
+/* alarm list which can be exported as CSV */
+#[derive(Serialize)]
+#[serde(transparent)]
+struct AlarmListDTO(Vec<AlarmDTO>);
+
+impl ToResponseData for AlarmListDTO {
+    fn media_types(&self) -> &'static [MediaType] {
+        &[MediaType::Json, MediaType::Cbor, MediaType::Csv]
+    }
+
+    fn to_response_data(&self, media_type: MediaType) -> Vec<u8> {
+        match media_type {
+            MediaType::Csv => to_csv(&self.0),
+            _ => serialize(self, media_type),
+        }
+    }
+}
+
+struct TestApi;
+
+impl Api for TestApi {
+    type State = ();
+    type Principal = ();
+    type Permission = ();
+
+    fn authenticate<C: ServerConnection>(_request: &mut Request<C>, _state: &()) -> Option<()> {
+        None
+    }
+
+    fn authorize(_principal: &(), _permission: (), _state: &()) -> bool {
+        false
+    }
+
+    fn routes<C: ConnectionFamily>() -> Vec<Route<C, Self>> {
+        vec![
+            Route::get("/api/v1/alarm", Access::Public, get_alarm),
+            Route::post("/api/v1/alarm", Access::Public, echo_alarm),
+            Route::get("/api/v1/alarms", Access::Public, get_alarms),
+            Route::get("/api/v1/message", Access::Public, get_message),
+            Route::get("/api/v1/missing", Access::Public, get_missing),
+        ]
+    }
+}
+
+fn alarm() -> AlarmDTO {
+    AlarmDTO { output_index: 1, identifier: "morning, bell".to_string(), impulse_length_millis: 3000 }
+}
+
+fn get_alarm<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&alarm())
+}
+
+fn echo_alarm<C: ConnectionFamily>(mut context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    let alarm: AlarmDTO = context.json()?;
+
+    context.ok(&alarm)
+}
+
+fn get_alarms<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&AlarmListDTO(vec![alarm(), AlarmDTO { output_index: 0, identifier: "lunch".to_string(), impulse_length_millis: 500 }]))
+}
+
+fn get_message<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&"Alarm added")
+}
+
+fn get_missing<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.error(&ApiError::not_found("alarm_not_found", "Alarm not found."))
+}
+
+fn send(mut connection: MockConnection) -> MockConnection {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(()));
+
+    router.handle(Request::wrap(&mut connection)).unwrap();
+
+    connection
+}
+
+fn get(uri: &str, accept: &str) -> MockConnection {
+    send(MockConnection::new(Method::Get, uri).with_header("Accept", accept))
+}
+
+#[test]
+fn first_offered_media_type_is_used_without_accept_header() {
+    assert_eq!(MediaType::negotiate(None, &[MediaType::Json, MediaType::Cbor]), Some(MediaType::Json));
+    assert_eq!(MediaType::negotiate(Some(" "), &[MediaType::Text, MediaType::Json]), Some(MediaType::Text));
+}
+
+#[test]
+fn media_type_with_highest_quality_is_preferred() {
+    let offered: &[MediaType] = &[MediaType::Json, MediaType::Cbor, MediaType::Csv];
+
+    assert_eq!(MediaType::negotiate(Some("application/cbor"), offered), Some(MediaType::Cbor));
+    assert_eq!(MediaType::negotiate(Some("application/json;q=0.5, application/cbor"), offered), Some(MediaType::Cbor));
+    assert_eq!(MediaType::negotiate(Some("text/html, */*;q=0.1"), offered), Some(MediaType::Json));
+    assert_eq!(MediaType::negotiate(Some("TEXT/CSV"), offered), Some(MediaType::Csv));
+
+    /* the most specific range decides quality */
+    assert_eq!(MediaType::negotiate(Some("application/*, application/json;q=0"), offered), Some(MediaType::Cbor));
+
+    assert_eq!(MediaType::negotiate(Some("image/png"), offered), None);
+    assert_eq!(MediaType::negotiate(Some("*/*;q=0"), offered), None);
+}
+
+#[test]
+fn json_response_has_content_type() {
+    let connection: MockConnection = send(MockConnection::new(Method::Get, "/api/v1/alarm"));
+
+    assert_eq!(connection.status(), Some(200));
+    assert_eq!(connection.response_header("Content-Type"), Some("application/json"));
+    assert_eq!(connection.response_json::<AlarmDTO>().unwrap(), alarm());
+}
+
+#[test]
+fn cbor_is_sent_and_read_when_requested() {
+    let connection: MockConnection = get("/api/v1/alarm", "application/cbor");
+
+    assert_eq!(connection.response_header("Content-Type"), Some("application/cbor"));
+    assert_eq!(ciborium::from_reader::<AlarmDTO, &[u8]>(connection.response_body()).unwrap(), alarm());
+
+    let mut body: Vec<u8> = vec![];
+    ciborium::into_writer(&alarm(), &mut body).unwrap();
+
+    let connection: MockConnection = send(
+        MockConnection::new(Method::Post, "/api/v1/alarm")
+            .with_header("Content-Type", "application/cbor")
+            .with_body(&body)
+    );
+
+    assert_eq!(connection.status(), Some(200));
+    assert_eq!(connection.response_json::<AlarmDTO>().unwrap(), alarm());
+}
+
+#[test]
+fn alarm_list_is_sent_as_csv() {
+    let connection: MockConnection = get("/api/v1/alarms", "text/csv");
+
+    assert_eq!(connection.response_header("Content-Type"), Some("text/csv; charset=utf-8"));
+    assert_eq!(
+        connection.response_text(),
+        "output_index,identifier,impulse_length_millis\n1,\"morning, bell\",3000\n0,lunch,500\n",
+    );
+}
+
+#[test]
+fn unacceptable_response_is_rejected_with_406() {
+    let connection: MockConnection = get("/api/v1/alarm", "text/csv");
+
+    assert_eq!(connection.status(), Some(406));
+    assert_eq!(connection.response_header("Content-Type"), Some("application/json"));
+
+    let error: Value = connection.response_json().unwrap();
+    assert_eq!(error["code"], "not_acceptable");
+    assert_eq!(error["message"], "Response can be sent as application/json, application/cbor.");
+}
+
+#[test]
+fn text_is_plain_unless_structured_format_is_requested() {
+    let connection: MockConnection = send(MockConnection::new(Method::Get, "/api/v1/message"));
+    assert_eq!(connection.response_header("Content-Type"), Some("text/plain; charset=utf-8"));
+    assert_eq!(connection.response_text(), "Alarm added");
+
+    let connection: MockConnection = get("/api/v1/message", "application/json");
+    assert_eq!(connection.response_header("Content-Type"), Some("application/json"));
+    assert_eq!(connection.response_text(), "\"Alarm added\"");
+}
+
+#[test]
+fn error_is_sent_even_when_not_acceptable() {
+    let connection: MockConnection = get("/api/v1/missing", "text/csv");
+
+    assert_eq!(connection.status(), Some(404));
+    assert_eq!(connection.response_header("Content-Type"), Some("application/json"));
+    assert_eq!(connection.response_json::<Value>().unwrap()["code"], "alarm_not_found");
+}
@@ -0,0 +1,157 @@
+use embedded_svc::ws::FrameType;
+use http_server::event_hub::EventHub;
+use http_server::mock_connection::MockSender;
+use serde_json::json;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+type TestHub = EventHub<MockSender, &'static str>;
+
+#[test]
+fn published_events_are_delivered_to_every_subscriber() {
+    let hub: TestHub = EventHub::new(3, 8);
+    let first: MockSender = MockSender::default();
+    let second: MockSender = MockSender::default();
+
+    hub.subscribe(1, first.clone(), "admin").unwrap();
+    hub.subscribe(2, second.clone(), "viewer").unwrap();
+
+    hub.publish("clock", &json!({ "timestamp_millis": 1000 }));
+    hub.publish("alarm_fired", &json!({ "details": "Output 0, alarm bell." }));
+
+    assert_eq!(hub.deliver(), 4);
+
+    for sender in [first, second] {
+        assert_eq!(sender.messages(), vec![
+            json!({ "event": "clock", "data": { "timestamp_millis": 1000 } }),
+            json!({ "event": "alarm_fired", "data": { "details": "Output 0, alarm bell." } }),
+        ]);
+    }
+
+    /* buffers are empty after delivery */
+    assert_eq!(hub.deliver(), 0);
+}
+
+#[test]
+fn slow_subscriber_loses_oldest_events_and_is_told_so() {
+    let hub: TestHub = EventHub::new(3, 2);
+    let sender: MockSender = MockSender::default();
+
+    hub.subscribe(1, sender.clone(), "admin").unwrap();
+
+    for timestamp_millis in 0..5 {
+        hub.publish("clock", &timestamp_millis);
+    }
+
+    hub.deliver();
+
+    assert_eq!(sender.messages(), vec![
+        json!({ "event": "lagged", "data": { "dropped": 3 } }),
+        json!({ "event": "clock", "data": 3 }),
+        json!({ "event": "clock", "data": 4 }),
+    ]);
+}
+
+#[test]
+fn subscribers_are_limited() {
+    let hub: TestHub = EventHub::new(1, 8);
+
+    hub.subscribe(1, MockSender::default(), "admin").unwrap();
+
+    let error = hub.subscribe(2, MockSender::default(), "viewer").unwrap_err();
+    assert_eq!((error.status, error.code), (503, "too_many_subscribers"));
+
+    /* the same session can subscribe again */
+    hub.subscribe(1, MockSender::default(), "admin").unwrap();
+    assert_eq!(hub.subscriber_count(), 1);
+
+    hub.unsubscribe(1);
+    hub.subscribe(2, MockSender::default(), "viewer").unwrap();
+    assert!(hub.is_subscribed(2));
+}
+
+#[test]
+fn disconnected_subscribers_are_removed() {
+    let hub: TestHub = EventHub::new(3, 8);
+    let connected: MockSender = MockSender::default();
+
+    hub.subscribe(1, connected.clone(), "admin").unwrap();
+    hub.subscribe(2, MockSender::closed(), "viewer").unwrap();
+
+    hub.publish("clock", &1000);
+
+    assert_eq!(hub.deliver(), 1);
+    assert!(hub.is_subscribed(1));
+    assert!(!hub.is_subscribed(2));
+    assert_eq!(connected.messages().len(), 1);
+}
+
+#[test]
+fn subscriptions_with_rejected_principal_are_closed() {
+    let hub: TestHub = EventHub::new(3, 8);
+    let admin: MockSender = MockSender::default();
+    let viewer: MockSender = MockSender::default();
+
+    hub.subscribe(1, admin.clone(), "admin").unwrap();
+    hub.subscribe(2, viewer.clone(), "viewer").unwrap();
+
+    hub.retain(|principal| *principal == "admin");
+
+    assert_eq!(hub.subscriber_count(), 1);
+    assert!(admin.frame_types().is_empty());
+    assert_eq!(viewer.frame_types(), vec![FrameType::Close]);
+}
+
+#[test]
+fn connections_without_credentials_are_closed_after_timeout() {
+    let hub: TestHub = EventHub::new(3, 8);
+    let late: MockSender = MockSender::default();
+    let subscribed: MockSender = MockSender::default();
+
+    hub.connect(1, late.clone());
+    hub.connect(2, subscribed.clone());
+    hub.subscribe(2, subscribed.clone(), "admin").unwrap();
+
+    /* connections within timeout stay open */
+    hub.close_unauthenticated(Duration::from_secs(5));
+    assert!(late.frame_types().is_empty());
+
+    hub.close_unauthenticated(Duration::ZERO);
+
+    assert_eq!(late.frame_types(), vec![FrameType::Text(false), FrameType::Close]);
+    assert_eq!(late.messages()[0]["code"], "authentication_timeout");
+    assert!(subscribed.frame_types().is_empty());
+    assert_eq!(hub.subscriber_count(), 1);
+}
+
+#[test]
+fn disconnected_connection_without_credentials_is_forgotten() {
+    let hub: TestHub = EventHub::new(3, 8);
+    let sender: MockSender = MockSender::default();
+
+    hub.connect(1, sender.clone());
+    hub.unsubscribe(1);
+    hub.close_unauthenticated(Duration::ZERO);
+
+    assert!(sender.frame_types().is_empty());
+}
+
+#[test]
+fn wait_returns_when_event_is_published() {
+    let hub: Arc<TestHub> = Arc::new(EventHub::new(3, 8));
+    hub.subscribe(1, MockSender::default(), "admin").unwrap();
+
+    let publisher_hub: Arc<TestHub> = Arc::clone(&hub);
+    let publisher = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        publisher_hub.publish("clock", &1000);
+    });
+
+    let started: Instant = Instant::now();
+    hub.wait(Duration::from_secs(10));
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    publisher.join().unwrap();
+    assert_eq!(hub.deliver(), 1);
+}
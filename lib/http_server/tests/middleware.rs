@@ -0,0 +1,111 @@
+use embedded_svc::http::server::{Connection, Request};
+use embedded_svc::http::Method;
+use embedded_svc::io::ErrorKind;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
+use http_server::middleware::cors_guard::CorsGuard;
+use http_server::middleware::error_responder::ErrorResponder;
+use http_server::middleware::request_logger::RequestLogger;
+use http_server::middleware::{Middleware, Next};
+use http_server::mock_connection::MockConnection;
+use serde_json::Value;
+use std::sync::Arc;
+
+/* Chain in the same order as HttpServer::start() builds it. */
+fn default_chain() -> Vec<Arc<dyn Middleware<MockConnection>>> {
+    vec![Arc::new(ErrorResponder), Arc::new(RequestLogger), Arc::new(CorsGuard)]
+}
+
+fn handler(request: Request<&mut MockConnection>) -> RequestResult<(), ErrorKind> {
+    match request.uri() {
+        "/json" => {
+            let mut request: Request<&mut MockConnection> = request;
+            let value: Value = http_server::http_request::ReadData::body(&mut request)?;
+            request.ok(&value.to_string())
+        }
+        "/security" => Err(RequestError::Security("Invalid access token.".to_string())),
+        "/panic" => panic!("Handler failed."),
+        _ => request.ok(&"fine"),
+    }
+}
+
+fn send(middlewares: &[Arc<dyn Middleware<MockConnection>>], connection: &mut MockConnection) -> RequestResult<(), ErrorKind> {
+    Next::new(middlewares, &handler).run(Request::wrap(connection))
+}
+
+fn error_code(connection: &MockConnection) -> String {
+    connection.response_json::<Value>().unwrap()["code"].as_str().unwrap().to_string()
+}
+
+/**
+ * Rejects requests to "/guarded" before they reach handler.
+ */
+struct Guard;
+
+impl<C: Connection> Middleware<C> for Guard {
+    fn handle(&self, request: Request<&mut C>, next: Next<'_, C>) -> RequestResult<(), C::Error> {
+        if request.uri() == "/guarded" {
+            return request.forbidden(&"guarded");
+        }
+
+        next.run(request)
+    }
+}
+
+#[test]
+fn handler_errors_become_error_envelope() {
+    let middlewares = default_chain();
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/security");
+    send(&middlewares, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(401));
+    assert_eq!(error_code(&connection), "unauthorized");
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/json").with_body(b"{\"broken\"");
+    send(&middlewares, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(400));
+    assert_eq!(error_code(&connection), "invalid_json");
+}
+
+#[test]
+fn handler_panic_becomes_500() {
+    let middlewares = default_chain();
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/panic");
+
+    send(&middlewares, &mut connection).unwrap();
+
+    assert_eq!(connection.status(), Some(500));
+    assert_eq!(error_code(&connection), "internal_error");
+}
+
+#[test]
+fn middleware_can_reject_request() {
+    let mut middlewares = default_chain();
+    middlewares.push(Arc::new(Guard));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/guarded");
+    send(&middlewares, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(403));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/open");
+    send(&middlewares, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+    assert_eq!(connection.response_text(), "fine");
+}
+
+#[test]
+fn cross_origin_request_is_rejected_unless_same_origin() {
+    let middlewares = default_chain();
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/open")
+        .with_header("Host", "192.168.71.1")
+        .with_header("Origin", "http://attacker.example");
+    send(&middlewares, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(403));
+    assert_eq!(error_code(&connection), "cross_origin_not_allowed");
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/open")
+        .with_header("Host", "192.168.71.1")
+        .with_header("Origin", "http://192.168.71.1");
+    send(&middlewares, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+}
@@ -0,0 +1,201 @@
+use embedded_svc::http::server::Request;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::mock_connection::MockConnections;
+use http_server::openapi::{undocumented_routes, OpenApi, UndocumentedRoutes};
+use http_server::router::request_context::RequestContext;
+use http_server::router::{Access, Api, ConnectionFamily, Route, ServerConnection};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Permission {
+    Read,
+    Write,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+struct AlarmDTO {
+    impulse_length_millis: u64,
+    label: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct AlarmIdDTO {
+    output_index: u8,
+    identifier: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct EventFilterDTO {
+    kind: Option<String>,
+    limit: Option<usize>,
+}
+
+fn handler<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    context.ok(&"done")
+}
+
+fn get_alarm<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    let AlarmIdDTO { output_index, identifier } = context.path()?;
+
+    context.ok(&format!("{output_index}/{identifier}"))
+}
+
+fn get_events<C: ConnectionFamily>(context: RequestContext<C, (), ()>) -> RequestResult<(), C::Error> {
+    let EventFilterDTO { kind, limit } = context.query()?;
+
+    context.ok(&format!("{kind:?} {limit:?}"))
+}
+
+/* API with documented routes only */
+struct DocumentedApi;
+
+impl Api for DocumentedApi {
+    type State = ();
+    type Principal = ();
+    type Permission = Permission;
+
+    fn authenticate<C: ServerConnection>(_request: &mut Request<C>, _state: &()) -> Option<()> {
+        None
+    }
+
+    fn authorize(_principal: &(), _permission: Permission, _state: &()) -> bool {
+        false
+    }
+
+    fn routes<C: ConnectionFamily>() -> Vec<Route<C, Self>> {
+        vec![
+            Route::post("/api/v1/login", Access::Public, handler)
+                .summary("Get access token")
+                .text_response(),
+            Route::get("/api/v1/outputs/{output_index}/alarms/{identifier}", Access::Permission(Permission::Read), get_alarm)
+                .path_params::<AlarmIdDTO>()
+                .json_response::<AlarmDTO>(),
+            Route::post("/api/v1/outputs/{output_index}/alarms/{identifier}", Access::Permission(Permission::Write), handler)
+                .path_params::<AlarmIdDTO>()
+                .json_body::<AlarmDTO>()
+                .text_response(),
+            Route::get("/api/v1/events", Access::Authenticated, get_events)
+                .query_params::<EventFilterDTO>()
+                .json_response::<Vec<AlarmDTO>>(),
+            Route::get("/api/v1/audit/export", Access::Authenticated, handler)
+                .media_response("application/x-ndjson"),
+        ]
+    }
+}
+
+/* API with routes which lack schema */
+struct UndocumentedApi;
+
+impl Api for UndocumentedApi {
+    type State = ();
+    type Principal = ();
+    type Permission = Permission;
+
+    fn authenticate<C: ServerConnection>(_request: &mut Request<C>, _state: &()) -> Option<()> {
+        None
+    }
+
+    fn authorize(_principal: &(), _permission: Permission, _state: &()) -> bool {
+        false
+    }
+
+    fn routes<C: ConnectionFamily>() -> Vec<Route<C, Self>> {
+        vec![
+            Route::get("/api/v1/clock", Access::Authenticated, handler).text_response(),
+            /* no response */
+            Route::put("/api/v1/clock", Access::Authenticated, handler),
+            /* path parameters without schema */
+            Route::delete("/api/v1/outputs/{output_index}/alarms", Access::Authenticated, handler).text_response(),
+            /* schema doesn't describe "output_index" */
+            Route::get("/api/v1/outputs/{output_index}/alarms/{id}", Access::Authenticated, handler)
+                .path_params::<AlarmIdDTO>()
+                .text_response(),
+        ]
+    }
+}
+
+fn document() -> Value {
+    OpenApi::new("Test API", "1.0.0")
+        .document::<DocumentedApi, MockConnections>()
+        .unwrap()
+}
+
+#[test]
+fn every_route_of_documented_api_has_schema() {
+    assert_eq!(undocumented_routes(&DocumentedApi::routes::<MockConnections>()), Vec::<String>::new());
+}
+
+#[test]
+fn routes_without_schema_are_reported() {
+    let expected: Vec<String> = vec![
+        "PUT /api/v1/clock".to_string(),
+        "DELETE /api/v1/outputs/{output_index}/alarms".to_string(),
+        "GET /api/v1/outputs/{output_index}/alarms/{id}".to_string(),
+    ];
+
+    assert_eq!(undocumented_routes(&UndocumentedApi::routes::<MockConnections>()), expected);
+
+    let result: Result<Value, UndocumentedRoutes> = OpenApi::new("Test API", "1.0.0").document::<UndocumentedApi, MockConnections>();
+    assert_eq!(result.unwrap_err(), UndocumentedRoutes(expected));
+}
+
+#[test]
+fn document_has_paths_and_components() {
+    let document: Value = document();
+
+    assert_eq!(document["openapi"], "3.0.3");
+    assert_eq!(document["info"]["title"], "Test API");
+
+    let alarm: &Value = &document["paths"]["/api/v1/outputs/{output_index}/alarms/{identifier}"];
+    assert_eq!(alarm["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/AlarmDTO");
+    assert_eq!(alarm["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/AlarmDTO");
+    assert_eq!(alarm["post"]["responses"]["200"]["content"]["text/plain"]["schema"]["type"], "string");
+
+    let alarm_schema: &Value = &document["components"]["schemas"]["AlarmDTO"];
+    assert_eq!(alarm_schema["properties"]["impulse_length_millis"]["type"], "integer");
+    assert_eq!(alarm_schema["required"], serde_json::json!(["impulse_length_millis"]));
+
+    /* errors are described by envelope */
+    assert_eq!(alarm["get"]["responses"]["default"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/ApiError");
+    assert!(document["components"]["schemas"]["ApiError"]["properties"]["code"].is_object());
+    assert!(document["components"]["schemas"]["ApiError"]["properties"]["status"].is_null());
+
+    let export: &Value = &document["paths"]["/api/v1/audit/export"]["get"];
+    assert!(export["responses"]["200"]["content"]["application/x-ndjson"].is_object());
+}
+
+#[test]
+fn path_and_query_parameters_are_listed() {
+    let document: Value = document();
+
+    let parameters: &Value = &document["paths"]["/api/v1/outputs/{output_index}/alarms/{identifier}"]["get"]["parameters"];
+    let names: Vec<(&str, &str, bool)> = parameters
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|parameter| (parameter["name"].as_str().unwrap(), parameter["in"].as_str().unwrap(), parameter["required"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(names, vec![("identifier", "path", true), ("output_index", "path", true)]);
+
+    let parameters: &Value = &document["paths"]["/api/v1/events"]["get"]["parameters"];
+    assert_eq!(parameters[0]["name"], "kind");
+    assert_eq!(parameters[0]["in"], "query");
+    assert_eq!(parameters[0]["required"], false);
+}
+
+#[test]
+fn access_is_documented_as_security() {
+    let document: Value = document();
+
+    let login: &Value = &document["paths"]["/api/v1/login"]["post"];
+    assert_eq!(login["security"], serde_json::json!([]));
+    assert_eq!(login["summary"], "Get access token");
+
+    let alarm: &Value = &document["paths"]["/api/v1/outputs/{output_index}/alarms/{identifier}"]["post"];
+    assert_eq!(alarm["security"], serde_json::json!([{ "bearer": [] }, { "accessToken": [] }]));
+    assert_eq!(alarm["description"], "Requires permission Write.");
+
+    assert_eq!(document["components"]["securitySchemes"]["accessToken"]["name"], "Access-Token");
+}
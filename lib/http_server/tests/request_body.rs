@@ -0,0 +1,73 @@
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use embedded_svc::io::ErrorKind;
+use http_server::http_request::{ReadData, RequestError, RequestResult};
+use http_server::mock_connection::MockConnection;
+use http_server::request_body::{read_body, stream_body};
+use serde_json::Value;
+
+fn error_status<T>(result: RequestResult<T, ErrorKind>) -> (u16, &'static str) {
+    match result {
+        Err(RequestError::Api(error)) => (error.status, error.code),
+        Err(error) => panic!("Unexpected error {error}"),
+        Ok(_) => panic!("Request should fail"),
+    }
+}
+
+#[test]
+fn body_delivered_in_pieces_is_read_completely() {
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/api/v1/settings")
+        .with_body(br#"{"access_point_ssid":"Scheduler System","https_enabled":true}"#)
+        .with_max_read_size(5);
+
+    let value: Value = Request::wrap(&mut connection).body().unwrap();
+
+    assert_eq!(value["access_point_ssid"], "Scheduler System");
+}
+
+#[test]
+fn chunked_body_is_rejected_with_411() {
+    let mut connection: MockConnection = MockConnection::replay(
+        b"POST /api/v1/alarms HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n"
+    ).unwrap();
+
+    assert_eq!(error_status(read_body(&mut Request::wrap(&mut connection), 64)), (411, "length_required"));
+}
+
+#[test]
+fn body_above_limit_is_rejected_with_413() {
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/").with_body(&[b'a'; 100]);
+    assert_eq!(error_status(read_body(&mut Request::wrap(&mut connection), 99)), (413, "body_too_large"));
+    /* body isn't read when Content-Length is above limit */
+    assert_eq!(connection.unread_body().len(), 100);
+}
+
+#[test]
+fn malformed_bodies_are_rejected() {
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/");
+    assert_eq!(error_status(read_body(&mut Request::wrap(&mut connection), 64)), (411, "length_required"));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/").with_header("Content-Length", "ten");
+    assert_eq!(error_status(read_body(&mut Request::wrap(&mut connection), 64)), (400, "invalid_content_length"));
+
+    let mut connection: MockConnection = MockConnection::replay(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort").unwrap();
+    assert_eq!(error_status(read_body(&mut Request::wrap(&mut connection), 64)), (400, "incomplete_body"));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/").with_body(b"\"\xff\xfe\"");
+    assert_eq!(error_status(Request::wrap(&mut connection).body::<Value>()), (400, "invalid_utf8"));
+}
+
+#[test]
+fn body_is_streamed_to_sink() {
+    let mut connection: MockConnection = MockConnection::new(Method::Put, "/upload").with_body(&[7; 5000]);
+    let mut pieces: Vec<usize> = vec![];
+
+    let size: usize = stream_body(&mut Request::wrap(&mut connection), 8192, |data: &[u8]| {
+        pieces.push(data.len());
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(size, 5000);
+    assert_eq!(pieces.iter().sum::<usize>(), 5000);
+    assert!(pieces.iter().all(|piece| *piece <= 1024));
+}
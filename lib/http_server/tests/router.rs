@@ -0,0 +1,236 @@
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::mock_connection::{MockConnection, MockRouter};
+use http_server::router::request_context::RequestContext;
+use http_server::router::{Access, Api, ConnectionFamily, Route, ServerConnection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/* API shaped like REST interface of the device: login, alarms of outputs and clock. */
+struct TestApi;
+
+#[derive(Default)]
+struct TestState {
+    alarms: Mutex<Vec<AlarmDTO>>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Permission {
+    Read,
+    Write,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct AlarmDTO {
+    output_index: u8,
+    identifier: String,
+}
+
+#[derive(Deserialize)]
+struct OutputIndexDTO {
+    output_index: u8,
+}
+
+#[derive(Deserialize)]
+struct AlarmIdDTO {
+    output_index: u8,
+    identifier: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct LoginDTO {
+    username: String,
+}
+
+type TestContext<'r, 'c, C> = RequestContext<'r, 'c, C, TestState, String>;
+
+impl Api for TestApi {
+    type State = TestState;
+    type Principal = String;
+    type Permission = Permission;
+
+    fn authenticate<C: ServerConnection>(request: &mut Request<C>, _state: &TestState) -> Option<String> {
+        request
+            .header("Authorization")
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .map(str::to_string)
+    }
+
+    /* "viewer" can only read, "admin" can do everything */
+    fn authorize(principal: &String, permission: Permission, _state: &TestState) -> bool {
+        principal == "admin" || (principal == "viewer" && permission == Permission::Read)
+    }
+
+    fn routes<C: ConnectionFamily>() -> Vec<Route<C, Self>> {
+        vec![
+            Route::post("/api/v1/login", Access::Public, login),
+            Route::get("/api/v1/clock", Access::Authenticated, get_clock),
+            Route::post("/api/v1/outputs/{output_index}/alarms", Access::Permission(Permission::Write), add_alarm),
+            Route::get("/api/v1/outputs/{output_index}/alarms/{identifier}", Access::Permission(Permission::Read), get_alarm),
+            Route::delete("/api/v1/outputs/{output_index}/alarms/{identifier}", Access::Permission(Permission::Write), delete_alarm),
+        ]
+    }
+}
+
+fn login<C: ConnectionFamily>(mut context: TestContext<C>) -> RequestResult<(), C::Error> {
+    let LoginDTO { username } = context.json()?;
+
+    context.ok(&username)
+}
+
+fn get_clock<C: ConnectionFamily>(context: TestContext<C>) -> RequestResult<(), C::Error> {
+    context.ok(&"1700000000000")
+}
+
+fn add_alarm<C: ConnectionFamily>(mut context: TestContext<C>) -> RequestResult<(), C::Error> {
+    let OutputIndexDTO { output_index } = context.path()?;
+    let identifier: String = context.json()?;
+
+    context
+        .state()
+        .alarms
+        .lock()
+        .unwrap()
+        .push(AlarmDTO { output_index, identifier });
+
+    context.ok(&"Alarm added")
+}
+
+fn get_alarm<C: ConnectionFamily>(context: TestContext<C>) -> RequestResult<(), C::Error> {
+    let AlarmIdDTO { output_index, identifier } = context.path()?;
+
+    let alarm: Option<AlarmDTO> = context
+        .state()
+        .alarms
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|alarm| alarm.output_index == output_index && alarm.identifier == identifier)
+        .cloned();
+
+    match alarm {
+        Some(alarm) => context.ok(&serde_json::to_string(&alarm).unwrap()),
+        None => Err(ApiError::not_found("alarm_not_found", "Alarm not found.").into()),
+    }
+}
+
+fn delete_alarm<C: ConnectionFamily>(context: TestContext<C>) -> RequestResult<(), C::Error> {
+    let AlarmIdDTO { output_index, identifier } = context.path()?;
+
+    context
+        .state()
+        .alarms
+        .lock()
+        .unwrap()
+        .retain(|alarm| alarm.output_index != output_index || alarm.identifier != identifier);
+
+    context.ok(&"Alarm removed")
+}
+
+fn send(router: &MockRouter<TestApi>, connection: &mut MockConnection) -> RequestResult<(), embedded_svc::io::ErrorKind> {
+    router.handle(Request::wrap(connection))
+}
+
+fn error_code(connection: &MockConnection) -> String {
+    connection.response_json::<Value>().unwrap()["code"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn public_route_reads_json_body() {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(TestState::default()));
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/api/v1/login")
+        .with_json(&LoginDTO { username: "admin".to_string() })
+        .with_max_read_size(3);
+
+    send(&router, &mut connection).unwrap();
+
+    assert_eq!(connection.status(), Some(200));
+    assert_eq!(connection.response_text(), "admin");
+}
+
+#[test]
+fn missing_credentials_are_rejected_with_401() {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(TestState::default()));
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/clock");
+
+    send(&router, &mut connection).unwrap();
+
+    assert_eq!(connection.status(), Some(401));
+    assert_eq!(error_code(&connection), "invalid_access_token");
+}
+
+#[test]
+fn missing_permission_is_rejected_with_403() {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(TestState::default()));
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/api/v1/outputs/1/alarms")
+        .with_header("Authorization", "Bearer viewer")
+        .with_json(&"morning");
+
+    send(&router, &mut connection).unwrap();
+
+    assert_eq!(connection.status(), Some(403));
+    assert_eq!(error_code(&connection), "permission_denied");
+}
+
+#[test]
+fn alarm_is_added_read_and_removed() {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(TestState::default()));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/api/v1/outputs/1/alarms")
+        .with_header("Authorization", "Bearer admin")
+        .with_json(&"morning bell");
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    /* path parameters are percent-decoded */
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/outputs/1/alarms/morning%20bell?verbose=true")
+        .with_header("Authorization", "Bearer viewer");
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+    assert_eq!(
+        serde_json::from_str::<AlarmDTO>(&connection.response_text()).unwrap(),
+        AlarmDTO { output_index: 1, identifier: "morning bell".to_string() },
+    );
+
+    let mut connection: MockConnection = MockConnection::new(Method::Delete, "/api/v1/outputs/1/alarms/morning%20bell")
+        .with_header("Authorization", "Bearer admin");
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/outputs/1/alarms/morning%20bell")
+        .with_header("Authorization", "Bearer admin");
+    let error: ApiError = match send(&router, &mut connection) {
+        Err(http_server::http_request::RequestError::Api(error)) => error,
+        result => panic!("Unexpected result {result:?}"),
+    };
+    assert_eq!((error.status, error.code), (404, "alarm_not_found"));
+}
+
+#[test]
+fn invalid_path_parameter_is_bad_request() {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(TestState::default()));
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/outputs/first/alarms/bell")
+        .with_header("Authorization", "Bearer admin");
+
+    let error: ApiError = send(&router, &mut connection).unwrap_err().to_api_error();
+
+    assert_eq!((error.status, error.code), (400, "invalid_parameters"));
+}
+
+#[test]
+fn unknown_path_is_404_and_unknown_method_is_405() {
+    let router: MockRouter<TestApi> = MockRouter::new(Arc::new(TestState::default()));
+
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/unknown");
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(404));
+    assert_eq!(error_code(&connection), "route_not_found");
+
+    let mut connection: MockConnection = MockConnection::new(Method::Put, "/api/v1/outputs/1/alarms/bell");
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(405));
+    assert_eq!(connection.response_header("Allow"), Some("GET, DELETE"));
+}
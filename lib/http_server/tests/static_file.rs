@@ -0,0 +1,83 @@
+use http_server::static_file::{accepts_gzip, ByteRange, StaticFile};
+
+fn file() -> StaticFile {
+    /* 2024-01-02 03:04:05 UTC */
+    StaticFile { length: 1000, modified_seconds: Some(1_704_164_645), gzip: false }
+}
+
+#[test]
+fn version_is_sent_as_entity_tag_and_date() {
+    assert_eq!(file().etag(), "\"3e8-65937d25\"");
+    assert_eq!(StaticFile { gzip: true, ..file() }.etag(), "\"3e8-65937d25-gzip\"");
+    assert_eq!(file().last_modified().as_deref(), Some("Tue, 02 Jan 2024 03:04:05 GMT"));
+}
+
+#[test]
+fn unknown_modification_time_is_never_matched_by_date() {
+    let file: StaticFile = StaticFile { modified_seconds: None, ..file() };
+
+    assert_eq!(file.last_modified(), None);
+    assert!(!file.is_not_modified(None, Some("Tue, 02 Jan 2024 03:04:05 GMT")));
+    assert!(!file.is_not_modified(None, Some("Thu, 01 Jan 1970 00:00:00 GMT")));
+    assert_eq!(file.range(Some("bytes=0-9"), Some("Thu, 01 Jan 1970 00:00:00 GMT")), ByteRange::Full);
+
+    /* entity tag still works */
+    assert!(file.is_not_modified(Some("\"3e8-0\""), None));
+}
+
+#[test]
+fn entity_tag_decides_if_file_is_not_modified() {
+    assert!(file().is_not_modified(Some("\"3e8-65937d25\""), None));
+    assert!(file().is_not_modified(Some("\"old\", W/\"3e8-65937d25\""), None));
+    assert!(file().is_not_modified(Some("*"), None));
+    assert!(!StaticFile { gzip: true, ..file() }.is_not_modified(Some("\"3e8-65937d25\""), None));
+
+    /* If-None-Match has priority over date */
+    assert!(!file().is_not_modified(Some("\"old\""), Some("Tue, 02 Jan 2024 03:04:05 GMT")));
+}
+
+#[test]
+fn date_decides_if_file_is_not_modified_without_entity_tag() {
+    assert!(file().is_not_modified(None, Some("Tue, 02 Jan 2024 03:04:05 GMT")));
+    assert!(file().is_not_modified(None, Some("Wed, 03 Jan 2024 00:00:00 GMT")));
+    assert!(!file().is_not_modified(None, Some("Mon, 01 Jan 2024 00:00:00 GMT")));
+    assert!(!file().is_not_modified(None, Some("yesterday")));
+    assert!(!file().is_not_modified(None, None));
+}
+
+#[test]
+fn single_byte_range_is_partial() {
+    assert_eq!(file().range(Some("bytes=0-99"), None), ByteRange::Partial { start: 0, end: 99 });
+    assert_eq!(file().range(Some("bytes=900-"), None), ByteRange::Partial { start: 900, end: 999 });
+    assert_eq!(file().range(Some("bytes=-100"), None), ByteRange::Partial { start: 900, end: 999 });
+    assert_eq!(file().range(Some("bytes=-5000"), None), ByteRange::Partial { start: 0, end: 999 });
+    assert_eq!(file().range(Some("bytes=500-5000"), None), ByteRange::Partial { start: 500, end: 999 });
+
+    assert_eq!(file().range(Some("bytes=1000-"), None), ByteRange::Unsatisfiable);
+    assert_eq!(file().range(Some("bytes=-0"), None), ByteRange::Unsatisfiable);
+}
+
+#[test]
+fn whole_file_is_sent_for_unsupported_or_outdated_range() {
+    assert_eq!(file().range(None, None), ByteRange::Full);
+    assert_eq!(file().range(Some("bytes=0-1,5-9"), None), ByteRange::Full);
+    assert_eq!(file().range(Some("bytes=9-1"), None), ByteRange::Full);
+    assert_eq!(file().range(Some("items=0-9"), None), ByteRange::Full);
+
+    assert_eq!(file().range(Some("bytes=0-9"), Some("\"3e8-65937d25\"")), ByteRange::Partial { start: 0, end: 9 });
+    assert_eq!(file().range(Some("bytes=0-9"), Some("Tue, 02 Jan 2024 03:04:05 GMT")), ByteRange::Partial { start: 0, end: 9 });
+    assert_eq!(file().range(Some("bytes=0-9"), Some("\"old\"")), ByteRange::Full);
+    assert_eq!(file().range(Some("bytes=0-9"), Some("Mon, 01 Jan 2024 00:00:00 GMT")), ByteRange::Full);
+}
+
+#[test]
+fn gzip_is_accepted_unless_refused() {
+    assert!(accepts_gzip(Some("gzip, deflate, br")));
+    assert!(accepts_gzip(Some("br;q=1.0, GZIP;q=0.5")));
+    assert!(accepts_gzip(Some("*")));
+
+    assert!(!accepts_gzip(None));
+    assert!(!accepts_gzip(Some("br, deflate")));
+    assert!(!accepts_gzip(Some("gzip;q=0")));
+    assert!(!accepts_gzip(Some("gzip;q=0, *")));
+}
@@ -0,0 +1,140 @@
+use http_server::tar::{TarEntry, TarError, TarReader};
+
+/* entry as written by tar, type '0' is regular file and '5' is directory */
+fn header(path: &str, size: usize, entry_type: u8) -> Vec<u8> {
+    let mut header: Vec<u8> = vec![0; 512];
+
+    header[..path.len()].copy_from_slice(path.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[124..136].copy_from_slice(format!("{size:011o}\0").as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = entry_type;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|byte| *byte as u32).sum();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    header
+}
+
+fn file(path: &str, content: &[u8]) -> Vec<u8> {
+    let mut entry: Vec<u8> = header(path, content.len(), b'0');
+
+    entry.extend_from_slice(content);
+    entry.resize(entry.len().div_ceil(512) * 512, 0);
+
+    entry
+}
+
+fn archive() -> Vec<u8> {
+    let mut archive: Vec<u8> = vec![];
+
+    archive.extend(header("./", 0, b'5'));
+    archive.extend(file("./index.htm", b"<html></html>"));
+    archive.extend(header("./gz/", 0, b'5'));
+    archive.extend(file("./gz/app.js", &[7; 700]));
+    archive.extend(header("./link", 0, b'2'));
+    archive.extend(file("./empty.txt", b""));
+    archive.extend([0; 1024]);
+
+    archive
+}
+
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Directory(String),
+    File(String, u64, Vec<u8>),
+}
+
+/* archive fed in pieces of given size, content of files is joined */
+fn read(archive: &[u8], piece_size: usize) -> Result<Vec<Entry>, TarError<String>> {
+    let mut reader: TarReader = TarReader::new();
+    let mut entries: Vec<Entry> = vec![];
+    let mut is_open: bool = false;
+
+    let mut sink = |entry: TarEntry| -> Result<(), String> {
+        match entry {
+            TarEntry::Directory { path } => entries.push(Entry::Directory(path.to_string())),
+            TarEntry::File { path, size } => {
+                is_open = true;
+                entries.push(Entry::File(path.to_string(), size, vec![]));
+            }
+            TarEntry::Data(data) => match entries.last_mut() {
+                Some(Entry::File(_, _, content)) if is_open => content.extend_from_slice(data),
+                _ => return Err("data outside of file".to_string()),
+            },
+            TarEntry::FileEnd => is_open = false,
+        }
+
+        Ok(())
+    };
+
+    for piece in archive.chunks(piece_size) {
+        reader.feed(piece, &mut sink)?;
+    }
+
+    reader.finish()?;
+
+    Ok(entries)
+}
+
+fn expected_entries() -> Vec<Entry> {
+    vec![
+        Entry::File("index.htm".to_string(), 13, b"<html></html>".to_vec()),
+        Entry::Directory("gz".to_string()),
+        Entry::File("gz/app.js".to_string(), 700, vec![7; 700]),
+        Entry::File("empty.txt".to_string(), 0, vec![]),
+    ]
+}
+
+#[test]
+fn files_and_directories_are_read_regardless_of_piece_size() {
+    for piece_size in [1, 100, 512, 1000, 64 * 1024] {
+        assert_eq!(read(&archive(), piece_size).unwrap(), expected_entries(), "piece size {piece_size}");
+    }
+}
+
+#[test]
+fn archive_without_end_blocks_is_accepted() {
+    let archive: Vec<u8> = archive();
+
+    assert_eq!(read(&archive[..archive.len() - 1024], 1000).unwrap(), expected_entries());
+}
+
+#[test]
+fn truncated_archive_is_rejected() {
+    let archive: Vec<u8> = file("index.htm", &[1; 600]);
+
+    assert!(matches!(read(&archive[..700], 100), Err(TarError::Truncated)));
+    assert!(matches!(read(&archive[..300], 100), Err(TarError::Truncated)));
+}
+
+#[test]
+fn corrupted_header_is_rejected() {
+    let mut archive: Vec<u8> = file("index.htm", b"<html></html>");
+    archive[0] = b'X';
+
+    assert!(matches!(read(&archive, 512), Err(TarError::InvalidHeader(_))));
+}
+
+#[test]
+fn sink_error_stops_reading() {
+    let mut reader: TarReader = TarReader::new();
+    let mut files: usize = 0;
+
+    let result: Result<(), TarError<&str>> = reader.feed(&archive(), &mut |entry: TarEntry| {
+        match entry {
+            TarEntry::File { .. } if files > 0 => Err("disk full"),
+            TarEntry::File { .. } => {
+                files += 1;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    });
+
+    assert!(matches!(result, Err(TarError::Sink("disk full"))));
+    assert_eq!(files, 1);
+}
@@ -0,0 +1,86 @@
+use esp_idf_svc::hal::gpio::{AnyInputPin, Input as InputMode, Level, PinDriver, Pull};
+use esp_idf_svc::sys::EspError;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InputEvent {
+    /* Input became active and stayed stable for debounce time. */
+    Pressed,
+    /* Input became inactive. Contains the time input was held active. */
+    Released(Duration),
+}
+
+/**
+ * Debounced digital input.
+ * Input should be polled periodically. Polling interval should be smaller than debounce time.
+ */
+pub struct Input<'a> {
+    driver: PinDriver<'a, AnyInputPin, InputMode>,
+    active_level: Level,
+    debounce: Duration,
+
+    /* raw (not debounced) state of the input and the moment it was changed */
+    raw_active: bool,
+    raw_changed_at: Instant,
+
+    /* debounced state of the input */
+    pressed_at: Option<Instant>,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(pin: AnyInputPin, pull: Pull, active_level: Level, debounce_millis: u64) -> Result<Self, EspError> {
+        let mut driver: PinDriver<AnyInputPin, InputMode> = PinDriver::input(pin)?;
+        driver.set_pull(pull)?;
+
+        let raw_active: bool = driver.get_level() == active_level;
+
+        Ok(Self {
+            driver,
+            active_level,
+            debounce: Duration::from_millis(debounce_millis),
+            raw_active,
+            raw_changed_at: Instant::now(),
+            pressed_at: None,
+        })
+    }
+
+    pub fn poll(&mut self) -> Option<InputEvent> {
+        let now: Instant = Instant::now();
+        let raw_active: bool = self.driver.get_level() == self.active_level;
+
+        /* restart debounce timer on every raw state change */
+        if raw_active != self.raw_active {
+            self.raw_active = raw_active;
+            self.raw_changed_at = now;
+
+            return None;
+        }
+
+        if now.duration_since(self.raw_changed_at) < self.debounce {
+            return None;
+        }
+
+        match (self.raw_active, self.pressed_at) {
+            (true, None) => {
+                self.pressed_at = Some(now);
+                Some(InputEvent::Pressed)
+            }
+            (false, Some(pressed_at)) => {
+                self.pressed_at = None;
+                Some(InputEvent::Released(now.duration_since(pressed_at)))
+            }
+            _ => None
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.pressed_at.is_some()
+    }
+
+    /**
+     * Time passed since input became active. None if input is not active.
+     */
+    pub fn held_for(&self) -> Option<Duration> {
+        self.pressed_at.map(|pressed_at| pressed_at.elapsed())
+    }
+}
@@ -0,0 +1,3 @@
+/* inputs are GPIO pins of ESP32, host build has none */
+#[cfg(target_os = "espidf")]
+pub mod input;
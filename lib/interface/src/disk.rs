@@ -12,6 +12,7 @@ pub trait ReadDisk {
 
 pub trait WriteDisk {
     fn write_to_file(&mut self, path: &FilePath, data_buffer: &[u8]) -> DiskResult<()>;
+    fn append_to_file(&mut self, path: &FilePath, data_buffer: &[u8]) -> DiskResult<()>;
 }
 
 pub trait ReadWriteDisk: ReadDisk + WriteDisk {}
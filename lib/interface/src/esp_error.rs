@@ -0,0 +1,43 @@
+/**
+ * Error of ESP-IDF call. Host build has its own type with the same name and methods,
+ * so code which passes ESP-IDF errors through builds on host too.
+ */
+#[cfg(target_os = "espidf")]
+pub use esp_idf_svc::sys::EspError;
+
+#[cfg(not(target_os = "espidf"))]
+use std::fmt::{Display, Formatter};
+
+/* ESP_FAIL */
+#[cfg(not(target_os = "espidf"))]
+const ESP_FAIL: i32 = -1;
+
+#[cfg(not(target_os = "espidf"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EspError(i32);
+
+#[cfg(not(target_os = "espidf"))]
+impl EspError {
+    /* zero code is ESP_OK, which isn't an error */
+    pub fn from(code: i32) -> Option<Self> {
+        (code != 0).then_some(Self(code))
+    }
+
+    pub fn from_infallible<const CODE: i32>() -> Self {
+        Self(if CODE == 0 { ESP_FAIL } else { CODE })
+    }
+
+    pub fn code(&self) -> i32 {
+        self.0
+    }
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl Display for EspError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ESP-IDF error {}", self.0)
+    }
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl std::error::Error for EspError {}
@@ -0,0 +1,21 @@
+/**
+ * Pull and level of GPIO. Host build has its own enums with the same variants, so pin configuration builds on host too.
+ */
+#[cfg(target_os = "espidf")]
+pub use esp_idf_svc::hal::gpio::{Level, Pull};
+
+#[cfg(not(target_os = "espidf"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pull {
+    Floating,
+    Up,
+    Down,
+    UpDown,
+}
+
+#[cfg(not(target_os = "espidf"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Level {
+    Low,
+    High,
+}
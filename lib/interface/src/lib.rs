@@ -1,5 +1,8 @@
 pub mod clock;
 pub mod disk;
+pub mod gpio;
+pub mod esp_error;
 
 pub use clock::error::ClockError;
-pub use disk::path::error::PathParseError;
\ No newline at end of file
+pub use disk::path::error::PathParseError;
+pub use esp_error::EspError;
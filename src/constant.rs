@@ -2,7 +2,106 @@ pub const SYSTEM_DIR: &str = "schedule";
 pub const WEB_UI_DIR: &str = "www";
 pub const ALARMS_DIR: &str = "alarms";
 pub const OUTPUT_DIR: &str = "output";
+pub const INPUTS_FILE: &str = "inputs";
+pub const PROFILE_FILE: &str = "profile";
+pub const EMERGENCY_FILE: &str = "emergncy";
+pub const SETTINGS_FILE: &str = "settings";
+/* Precompressed copy of web interface file is kept in subdirectory of its directory, e.g. www/gz/app.js for www/app.js,
+   because FAT short names can't have extension appended. */
+pub const WEB_UI_GZIP_DIR: &str = "gz";
+pub const WEB_UI_INDEX_FILE: &str = "index.htm";
+/* Uploaded web interface is written to inactive slot directory and activated by rewriting pointer file,
+   because FAT driver can't rename directories. Files directly in WEB_UI_DIR are served until first activation. */
+pub const WEB_UI_SLOT_DIRS: [&str; 2] = ["ui_a", "ui_b"];
+pub const WEB_UI_ACTIVE_SLOT_FILE: &str = "active";
+pub const WEB_UI_MAX_UPLOAD_BYTES: usize = 8 * 1024 * 1024;
+/* Uploaded data is collected before it's appended to file, because every disk write opens file again. */
+pub const WEB_UI_WRITE_BUFFER_BYTES: usize = 8 * 1024;
+/* Files are streamed in chunks of this size, so large files don't need large buffer. */
+pub const FILE_READ_CHUNK_BYTES: usize = 4 * 1024;
 pub const ACCESS_POINT_SSID: &str = "Scheduler System";
+/* Server certificate and its private key in PEM format. Self-signed certificate is generated when files are missing. */
+pub const TLS_DIR: &str = "tls";
+pub const TLS_CERTIFICATE_FILE: &str = "cert.pem";
+pub const TLS_PRIVATE_KEY_FILE: &str = "key.pem";
 
-pub const RESET_BUTTON_PRESS_TIME_SECONDS: u64 = 3;
+/* Incremented when stored settings format changes. Constants below are defaults of runtime settings. */
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/* Reset button shows device info on short press, resets passwords or wipes all data on long hold. */
+pub const RESET_BUTTON_DEBOUNCE_MS: u64 = 50;
+pub const RESET_BUTTON_PASSWORDS_HOLD_SECONDS: u64 = 3;
+pub const RESET_BUTTON_FACTORY_HOLD_SECONDS: u64 = 10;
+pub const DEVICE_INFO_DISPLAY_SECONDS: u64 = 15;
+pub const RESTART_DELAY_SECONDS: u64 = 2;
+
+/* Cross-origin requests are denied by default, web interface is served from the same origin. */
+pub const CORS_METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/* Larger JSON bodies are rejected with 413. Certificate chains are the largest expected bodies. */
+pub const MAX_REQUEST_BODY_BYTES: u32 = 16 * 1024;
+
+/* Info of OpenAPI specification served at /api/v1/openapi.json. */
+pub const API_TITLE: &str = "Automatic Bell System";
+pub const API_VERSION: &str = env!("CARGO_PKG_VERSION");
+/* Path prefixes of API versions from the oldest. Newer version inherits every route it doesn't declare itself. */
+pub const API_VERSIONS: &[&str] = &["/api/v1"];
+
+pub const DISPLAY_REFRESH_INTERVAL_MS: u64 = 200;
 pub const ALARM_MATCH_CHECK_INTERVAL_MS: u64 = 500;
+pub const INPUT_POLL_INTERVAL_MS: u64 = 10;
+pub const EMERGENCY_SIGNAL_TICK_MS: u64 = 50;
+
+/* Profile 0 is stored in ALARMS_DIR, others in ALARMS_DIR + profile index (e.g. "alarms1"). */
+pub const SCHEDULE_PROFILES_COUNT: u8 = 4;
+/* GPIOs which are not used by board peripherals and can be configured as inputs. */
+pub const FREE_INPUT_GPIOS: [i32; 9] = [25, 26, 27, 32, 33, 34, 35, 36, 39];
+/* Input-only pads have no internal pull resistors, so they need external ones. */
+pub const NO_PULL_INPUT_GPIOS: [i32; 4] = [34, 35, 36, 39];
+
+/* Event history is stored in SYSTEM_DIR/EVENTS_DIR. Files are rotated by day and size. */
+pub const EVENTS_DIR: &str = "events";
+pub const EVENT_LOG_MAX_FILE_BYTES: u32 = 32 * 1024;
+pub const EVENT_LOG_MAX_FILES: usize = 60;
+pub const EVENT_QUEUE_CAPACITY: usize = 32;
+pub const EVENT_PAGE_DEFAULT_LIMIT: usize = 50;
+pub const EVENT_PAGE_MAX_LIMIT: usize = 200;
+
+/* Session expires when it isn't used for idle timeout or when max lifetime is reached. */
+pub const SESSION_IDLE_TIMEOUT_SECONDS: u64 = 30 * 60;
+pub const SESSION_MAX_LIFETIME_SECONDS: u64 = 12 * 60 * 60;
+/* Oldest session is removed when limit is reached. */
+pub const SESSIONS_MAX_COUNT: usize = 16;
+pub const USERS_MAX_COUNT: usize = 8;
+
+/* Login is delayed with exponential backoff after free attempts are used. Counters are forgotten after quiet period. */
+pub const LOGIN_CLIENT_FREE_ATTEMPTS: u32 = 3;
+pub const LOGIN_GLOBAL_FREE_ATTEMPTS: u32 = 20;
+pub const LOGIN_BACKOFF_BASE_SECONDS: u64 = 2;
+pub const LOGIN_LOCKOUT_MAX_SECONDS: u64 = 15 * 60;
+pub const LOGIN_FAILURES_RESET_SECONDS: u64 = 15 * 60;
+pub const LOGIN_TRACKED_CLIENTS_MAX: usize = 32;
+
+/* Minimum length of password policy can be raised by admin, but not below default. Maximum length isn't configurable. */
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+pub const PASSWORD_MAX_LENGTH: usize = 64;
+
+/* API keys of automation clients. Last used time is written to NVS at most once per interval to save flash. */
+pub const API_KEYS_MAX_COUNT: usize = 8;
+pub const API_KEY_LAST_USED_PERSIST_SECONDS: u64 = 60 * 60;
+
+/* Audit log isn't cleared by factory reset. Oldest files are rotated out. */
+pub const AUDIT_DIR: &str = "audit";
+pub const AUDIT_LOG_MAX_FILE_BYTES: u32 = 32 * 1024;
+pub const AUDIT_LOG_MAX_FILES: usize = 120;
+
+/* Live events are pushed over WebSocket. Every subscriber keeps socket open, so their count is limited. */
+pub const LIVE_EVENTS_URI: &str = "/api/v1/live";
+pub const LIVE_EVENTS_MAX_SUBSCRIBERS: usize = 3;
+pub const LIVE_EVENTS_BUFFER_CAPACITY: usize = 16;
+pub const LIVE_EVENTS_AUTH_MESSAGE_MAX_BYTES: usize = 512;
+/* Connections which don't send access token in time are closed, checked on every clock tick. */
+pub const LIVE_EVENTS_AUTH_TIMEOUT_MS: u64 = 5 * 1000;
+pub const LIVE_EVENTS_CLOCK_TICK_MS: u64 = 1000;
+/* Subscriptions whose access token expired or was revoked are closed on next check. */
+pub const LIVE_EVENTS_REAUTHENTICATION_INTERVAL_MS: u64 = 60 * 1000;
@@ -0,0 +1,107 @@
+mod reset_button;
+
+use crate::constant::INPUT_POLL_INTERVAL_MS;
+use crate::input_system::reset_button::ResetButton;
+use crate::schedule_system::error::ScheduleSystemError;
+use crate::schedule_system::input_config::{InputAction, InputConfig};
+use crate::schedule_system::ScheduleSystem;
+use esp_idf_svc::hal::gpio::AnyInputPin;
+use input::input::{Input, InputEvent};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/**
+ * Initialize configured inputs and start polling them together with reset button.
+ * Action of input is performed when input becomes active.
+ * Inputs which can't be initialized are skipped, so reset button is polled even with broken configuration.
+ */
+pub fn serve(schedule_system: Arc<ScheduleSystem>) {
+    let input_configs: Vec<InputConfig> = schedule_system
+        .get_input_configs()
+        .unwrap_or_else(|error| {
+            log::error!("Can't read input configuration, inputs are disabled: {error}");
+            vec![]
+        });
+
+    let mut inputs: Vec<(Input<'static>, InputAction)> = Vec::with_capacity(input_configs.len());
+
+    for input_config in input_configs {
+        /* configuration file can be edited manually, so validate it again */
+        if let Err(error) = schedule_system.validate_input_config(&input_config) {
+            log::warn!("Skipping input on GPIO {}: {error}", input_config.gpio);
+            continue;
+        }
+
+        let InputConfig { gpio, pull, active_level, debounce_millis, action } = input_config;
+
+        /* Validated GPIOs are not used by any other driver. */
+        let pin: AnyInputPin = unsafe { AnyInputPin::new(gpio) };
+
+        let input: Input = match Input::new(pin, pull, active_level, debounce_millis) {
+            Ok(input) => input,
+            Err(error) => {
+                log::warn!("Skipping input on GPIO {gpio}: {error}");
+                continue;
+            }
+        };
+
+        log::info!("Input on GPIO {gpio} initialized. Action - {action:?}.");
+        inputs.push((input, action));
+    }
+
+    let mut reset_button: Option<ResetButton> = schedule_system
+        .take_reset_button()
+        .map(ResetButton::new);
+
+    thread::spawn(move || loop {
+        for (input, action) in inputs.iter_mut() {
+            if let Some(InputEvent::Pressed) = input.poll() {
+                perform_action(&schedule_system, action);
+            }
+        }
+
+        if let Some(reset_button) = reset_button.as_mut() {
+            reset_button.poll(&schedule_system);
+        }
+
+        thread::sleep(Duration::from_millis(INPUT_POLL_INTERVAL_MS));
+    });
+}
+
+fn perform_action(schedule_system: &Arc<ScheduleSystem>, action: &InputAction) {
+    let result: Result<(), ScheduleSystemError> =
+        match action {
+            InputAction::RingOutput { output_index, pattern } => {
+                let schedule_system: Arc<ScheduleSystem> = Arc::clone(schedule_system);
+                let output_index: u8 = *output_index;
+                let pattern = pattern.clone();
+
+                /* ring in separate thread to keep polling other inputs */
+                thread::spawn(move || {
+                    if let Err(error) = schedule_system.ring_output(output_index, &pattern) {
+                        log::warn!("Can't ring output {output_index}: {error}");
+                    }
+                });
+
+                Ok(())
+            }
+            InputAction::StartEmergency { emergency } => schedule_system.start_emergency(emergency.clone()),
+            InputAction::StopEmergency => schedule_system.stop_emergency(),
+            InputAction::ToggleSilentMode => {
+                let enabled: bool = schedule_system.toggle_silent_mode();
+                log::info!("Silent mode {}.", if enabled { "enabled" } else { "disabled" });
+
+                Ok(())
+            }
+            InputAction::SwitchProfile { profile } => {
+                schedule_system
+                    .switch_profile(*profile)
+                    .map(|_| log::info!("Switched to schedule profile {profile}."))
+            }
+        };
+
+    if let Err(error) = result {
+        log::warn!("Can't perform input action {action:?}: {error}");
+    }
+}
@@ -0,0 +1,204 @@
+use crate::constant::RESTART_DELAY_SECONDS;
+use crate::schedule_system::audit::{AuditAction, AuditOutcome, AuditRecord};
+use crate::schedule_system::settings::Settings;
+use crate::schedule_system::ScheduleSystem;
+use crate::security::SecurityContext;
+use esp_idf_svc::hal::reset;
+use input::input::{Input, InputEvent};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ResetStage {
+    ShowDeviceInfo,
+    ResetPasswords,
+    FactoryReset,
+}
+
+impl ResetStage {
+    fn from_hold_time(held_for: Duration, settings: &Settings) -> Self {
+        match held_for.as_secs() {
+            seconds if seconds >= settings.reset_button_factory_hold_seconds => ResetStage::FactoryReset,
+            seconds if seconds >= settings.reset_button_passwords_hold_seconds => ResetStage::ResetPasswords,
+            _ => ResetStage::ShowDeviceInfo,
+        }
+    }
+
+    /**
+     * Feedback shown while button is held. Tells what happens on release and when next stage starts.
+     */
+    fn hold_message(&self, held_for: Duration, settings: &Settings) -> String {
+        let seconds: u64 = held_for.as_secs();
+
+        match self {
+            ResetStage::ShowDeviceInfo =>
+                format!("Reset in {}s\nRelease: info", settings.reset_button_passwords_hold_seconds - seconds),
+            ResetStage::ResetPasswords =>
+                format!("Wipe in {}s\nRelease: reset", settings.reset_button_factory_hold_seconds - seconds),
+            ResetStage::FactoryReset =>
+                String::from("Release to\nwipe all data"),
+        }
+    }
+}
+
+/* Actor of audit entries, because button isn't bound to any user. */
+const AUDIT_ACTOR: &str = "reset-button";
+
+/**
+ * Action of reset button depends on hold time:
+ * short press shows device info, 3s resets passwords, 10s wipes all alarms and settings.
+ * Hold times are taken from settings, values above are defaults.
+ * Action is performed when button is released.
+ */
+pub struct ResetButton {
+    input: Input<'static>,
+    displayed_message: Option<String>,
+}
+
+impl ResetButton {
+    pub fn new(input: Input<'static>) -> Self {
+        Self {
+            input,
+            displayed_message: None,
+        }
+    }
+
+    pub fn poll(&mut self, schedule_system: &ScheduleSystem) {
+        let event: Option<InputEvent> = self.input.poll();
+        let held_for: Option<Duration> = self.input.held_for();
+
+        /* settings are read only while button is used */
+        if event.is_none() && held_for.is_none() {
+            return;
+        }
+
+        let settings: Settings = schedule_system.get_settings().unwrap_or_default();
+
+        if let Some(InputEvent::Released(held_for)) = event {
+            self.displayed_message = None;
+            schedule_system.hide_message();
+
+            ResetButton::perform(ResetStage::from_hold_time(held_for, &settings), schedule_system, &settings);
+            return;
+        }
+
+        let Some(held_for) = held_for else {
+            return;
+        };
+
+        let message: String = ResetStage::from_hold_time(held_for, &settings).hold_message(held_for, &settings);
+
+        if self.displayed_message.as_ref() != Some(&message) {
+            /* message is replaced every second while button is held */
+            schedule_system.show_message(&message, Duration::from_secs(settings.reset_button_factory_hold_seconds));
+            self.displayed_message = Some(message);
+        }
+    }
+
+    fn perform(stage: ResetStage, schedule_system: &ScheduleSystem, settings: &Settings) {
+        log::info!("Reset button released. Stage - {stage:?}.");
+
+        match stage {
+            ResetStage::ShowDeviceInfo => ResetButton::show_device_info(schedule_system, settings),
+            ResetStage::ResetPasswords => {
+                ResetButton::reset_passwords(schedule_system);
+                ResetButton::restart(schedule_system, "Passwords\nreset");
+            }
+            ResetStage::FactoryReset => {
+                ResetButton::reset_passwords(schedule_system);
+
+                if let Err(error) = ResetButton::factory_reset(schedule_system) {
+                    log::error!("Factory reset failed: {error}");
+                    ResetButton::audit(schedule_system, AuditAction::FactoryReset, AuditOutcome::Failure, error);
+                    schedule_system.show_message("Wipe failed", Duration::from_secs(settings.device_info_display_seconds));
+                    return;
+                }
+
+                ResetButton::audit(schedule_system, AuditAction::FactoryReset, AuditOutcome::Success, String::new());
+                ResetButton::restart(schedule_system, "All data\nwiped");
+            }
+        }
+    }
+
+    /**
+     * Wipe schedule data and remove users, API keys and password policy.
+     */
+    fn factory_reset(schedule_system: &ScheduleSystem) -> Result<(), String> {
+        schedule_system
+            .factory_reset()
+            .map_err(|error| error.to_string())?;
+
+        SecurityContext::get()
+            .map_err(|error| error.to_string())?
+            .factory_reset()
+            .map_err(|error| error.to_string())
+    }
+
+    fn show_device_info(schedule_system: &ScheduleSystem, settings: &Settings) {
+        let ip: String = schedule_system
+            .get_access_point_ipv4()
+            .map_or(String::from("No IP"), |ip| ip.to_string());
+
+        let password: String = SecurityContext::get()
+            .and_then(|security_context| security_context.get_access_point_password())
+            .unwrap_or_default();
+
+        schedule_system.show_message(&format!("{ip}\n{password}"), Duration::from_secs(settings.device_info_display_seconds));
+    }
+
+    fn reset_passwords(schedule_system: &ScheduleSystem) {
+        log::warn!("Resetting passwords...");
+
+        let Ok(security_context) = SecurityContext::get() else {
+            log::error!("Can't get security context.");
+            ResetButton::audit(schedule_system, AuditAction::PasswordsReset, AuditOutcome::Failure, "Can't get security context.".to_string());
+            return;
+        };
+
+        let mut errors: Vec<String> = vec![];
+
+        if let Err(error) = security_context.reset_access_point_password() {
+            log::error!("Can't reset access point password: {error}");
+            errors.push(format!("Access point: {error}"));
+        }
+
+        if let Err(error) = security_context.reset_admin_password() {
+            log::error!("Can't reset admin password: {error}");
+            errors.push(format!("Admin: {error}"));
+        }
+
+        if let Err(error) = security_context.revoke_all_api_keys() {
+            log::error!("Can't revoke API keys: {error}");
+            errors.push(format!("API keys: {error}"));
+        }
+
+        if let Err(error) = security_context.revoke_all_access_tokens() {
+            log::error!("Can't revoke sessions: {error}");
+            errors.push(format!("Sessions: {error}"));
+        }
+
+        let outcome: AuditOutcome = if errors.is_empty() { AuditOutcome::Success } else { AuditOutcome::Failure };
+        ResetButton::audit(schedule_system, AuditAction::PasswordsReset, outcome, errors.join(" "));
+    }
+
+    fn audit(schedule_system: &ScheduleSystem, action: AuditAction, outcome: AuditOutcome, details: String) {
+        schedule_system.audit(AuditRecord {
+            actor: AUDIT_ACTOR.to_string(),
+            action,
+            source_ip: None,
+            outcome,
+            details,
+        });
+    }
+
+    fn restart(schedule_system: &ScheduleSystem, message: &str) -> ! {
+        schedule_system.show_message(message, Duration::from_secs(RESTART_DELAY_SECONDS * 2));
+        thread::sleep(Duration::from_secs(RESTART_DELAY_SECONDS));
+
+        schedule_system.show_message("Restarting...", Duration::from_secs(RESTART_DELAY_SECONDS));
+        thread::sleep(Duration::from_secs(1));
+
+        log::info!("Restarting...");
+        reset::restart()
+    }
+}
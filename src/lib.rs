@@ -0,0 +1,10 @@
+pub mod schedule_system;
+pub mod rest_interface;
+#[cfg(target_os = "espidf")]
+pub mod web_interface;
+pub mod synchronizer;
+pub mod model;
+pub mod constant;
+pub mod security;
+#[cfg(target_os = "espidf")]
+pub mod input_system;
@@ -1,18 +1,23 @@
-mod schedule_system;
-mod rest_interface;
-mod web_interface;
-mod synchronizer;
-mod model;
-mod constant;
-mod security;
-
-use crate::schedule_system::ScheduleSystem;
+#[cfg(target_os = "espidf")]
+use automatic_bell_system::schedule_system::settings::Settings;
+#[cfg(target_os = "espidf")]
+use automatic_bell_system::schedule_system::ScheduleSystem;
+#[cfg(target_os = "espidf")]
+use automatic_bell_system::{input_system, rest_interface, web_interface};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::prelude::Peripherals;
+#[cfg(target_os = "espidf")]
 use http_server::http_server::HttpServer;
+#[cfg(target_os = "espidf")]
+use http_server::https_redirect::HttpsRedirect;
+#[cfg(target_os = "espidf")]
 use std::sync::Arc;
+#[cfg(target_os = "espidf")]
 use std::thread;
+#[cfg(target_os = "espidf")]
 use std::time::Duration;
 
+#[cfg(target_os = "espidf")]
 fn main() {
     /* It is necessary to call this function once. Otherwise, some patches to the runtime */
     /* implemented by esp-idf-sys might not link properly. See https://github.com/esp-rs/esp-idf-template/issues/71 */
@@ -32,10 +37,25 @@ fn main() {
 
     let schedule_system: Arc<ScheduleSystem> = Arc::new(schedule_system);
 
+    schedule_system.start_event_log_writer().unwrap();
+    log::info!("Event log is ready.");
+
+    schedule_system.start_security_audit().unwrap();
+    log::info!("Security audit is ready.");
+
+    schedule_system.start_live_events().unwrap();
+    log::info!("Live events are ready.");
+
     schedule_system.enable_access_point().unwrap();
     log::info!("Access point enabled.");
 
-    let mut http_server: HttpServer = HttpServer::new().unwrap();
+    input_system::serve(Arc::clone(&schedule_system));
+    log::info!("Inputs are ready.");
+
+    let settings: Settings = schedule_system.get_settings().unwrap_or_default();
+
+    /* redirect is kept alive until restart */
+    let (mut http_server, _https_redirect): (HttpServer, Option<HttpsRedirect>) = start_http_server(&schedule_system, &settings);
 
     rest_interface::serve(&mut http_server, Arc::clone(&schedule_system)).unwrap();
     log::info!("Rest interface is ready.");
@@ -47,3 +67,38 @@ fn main() {
         thread::sleep(Duration::from_secs(10));
     }
 }
+
+/**
+ * Firmware runs on ESP32 only. Host build is used to run tests with in-memory devices.
+ */
+#[cfg(not(target_os = "espidf"))]
+fn main() {
+    eprintln!("Firmware runs on ESP32 only. Run tests with 'cargo test' on host.");
+}
+
+/**
+ * HTTPS server and optional redirect from plain HTTP when HTTPS is enabled.
+ * Plain HTTP server is used when certificate can't be loaded or generated, so device stays reachable.
+ */
+#[cfg(target_os = "espidf")]
+fn start_http_server<'a>(schedule_system: &ScheduleSystem, settings: &Settings) -> (HttpServer<'a>, Option<HttpsRedirect<'a>>) {
+    if !settings.https_enabled {
+        log::warn!("HTTPS disabled. Starting plain HTTP server...");
+        return (HttpServer::new().unwrap(), None);
+    }
+
+    match schedule_system.get_or_create_tls_certificate() {
+        Ok(tls_certificate) => {
+            log::info!("Starting HTTPS server with certificate '{}'...", tls_certificate.subject());
+            let http_server: HttpServer = HttpServer::new_https(&tls_certificate).unwrap();
+
+            let https_redirect: Option<HttpsRedirect> = settings.https_redirect_enabled.then(|| HttpsRedirect::new().unwrap());
+
+            (http_server, https_redirect)
+        }
+        Err(error) => {
+            log::error!("Can't get TLS certificate: {error}. Starting plain HTTP server...");
+            (HttpServer::new().unwrap(), None)
+        }
+    }
+}
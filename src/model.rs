@@ -1,3 +1,13 @@
+/* DTO modules are named after their resource, e.g. model::alarm::alarm */
+#![allow(clippy::module_inception)]
+
 pub mod auth;
 pub mod alarm;
 pub mod clock;
+pub mod input;
+pub mod emergency;
+pub mod event;
+pub mod audit;
+pub mod settings;
+pub mod tls;
+pub mod web_ui;
@@ -2,4 +2,6 @@ pub mod alarm;
 pub mod alarm_id;
 pub mod add_alarm;
 pub mod alarm_with_id;
+pub mod alarm_list;
 pub mod output_index;
+pub mod ring_pattern;
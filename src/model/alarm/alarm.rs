@@ -1,11 +1,12 @@
 use chrono::{Month, Weekday};
 use clock::alarm::{Alarm, AlarmMatcher};
 use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::hash::Hash;
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, JsonSchema)]
 pub enum WeekdayDTO {
     Monday,
     Tuesday,
@@ -46,7 +47,7 @@ impl From<Weekday> for WeekdayDTO {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, JsonSchema)]
 pub enum MonthDTO {
     January,
     February,
@@ -102,7 +103,7 @@ impl From<Month> for MonthDTO {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(tag = "tag")]
 pub enum AlarmMatcherDTO<T: Eq + Hash + Clone + Serialize> {
     Ignore,
@@ -152,7 +153,7 @@ where
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct AlarmDTO {
     pub year: AlarmMatcherDTO<u16>,
     pub month: AlarmMatcherDTO<MonthDTO>,
@@ -1,10 +1,11 @@
 use crate::schedule_system::alarm_id::AlarmId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use http_server::to_response_data::ToResponseData;
 
 impl ToResponseData for AlarmIdDTO {}
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AlarmIdDTO {
     pub output_index: u8,
     pub identifier: String
@@ -0,0 +1,90 @@
+use crate::model::alarm::alarm::{AlarmDTO, AlarmMatcherDTO};
+use crate::model::alarm::alarm_with_id::AlarmWithIdDTO;
+use http_server::media_type::MediaType;
+use http_server::to_response_data::{serialize, to_csv, ToResponseData};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::hash::Hash;
+
+/**
+ * Alarms of output. Sent as JSON array, CBOR array or CSV with one alarm per row.
+ */
+#[derive(Serialize, Debug, JsonSchema)]
+#[serde(transparent)]
+pub struct AlarmListDTO(pub Vec<AlarmWithIdDTO>);
+
+impl ToResponseData for AlarmListDTO {
+    fn media_types(&self) -> &'static [MediaType] {
+        &[MediaType::Json, MediaType::Cbor, MediaType::Csv]
+    }
+
+    fn to_response_data(&self, media_type: MediaType) -> Vec<u8> {
+        match media_type {
+            MediaType::Csv => {
+                let rows: Vec<AlarmRowDTO> = self.0.iter().map(Into::into).collect();
+                to_csv(&rows)
+            }
+            _ => serialize(self, media_type),
+        }
+    }
+}
+
+/**
+ * Flat alarm for CSV. Matcher is "*" when ignored, matched segments, e.g. "8 12", or "!" and excluded segments, e.g. "!Saturday Sunday".
+ */
+#[derive(Serialize)]
+struct AlarmRowDTO {
+    output_index: u8,
+    identifier: String,
+    year: String,
+    month: String,
+    month_day: String,
+    week_day: String,
+    hour: String,
+    minute: String,
+    second: String,
+    impulse_length_millis: u64,
+}
+
+impl From<&AlarmWithIdDTO> for AlarmRowDTO {
+    fn from(alarm_with_id: &AlarmWithIdDTO) -> Self {
+        let alarm: &AlarmDTO = &alarm_with_id.alarm;
+
+        Self {
+            output_index: alarm_with_id.id.output_index,
+            identifier: alarm_with_id.id.identifier.clone(),
+            year: matcher_to_csv(&alarm.year),
+            month: matcher_to_csv(&alarm.month),
+            month_day: matcher_to_csv(&alarm.month_day),
+            week_day: matcher_to_csv(&alarm.week_day),
+            hour: matcher_to_csv(&alarm.hour),
+            minute: matcher_to_csv(&alarm.minute),
+            second: matcher_to_csv(&alarm.second),
+            impulse_length_millis: alarm.impulse_length_millis,
+        }
+    }
+}
+
+/* segments are sorted, because they are stored in hash set */
+fn matcher_to_csv<T: Eq + Hash + Clone + Ord + Serialize>(matcher: &AlarmMatcherDTO<T>) -> String {
+    let (prefix, segments) = match matcher {
+        AlarmMatcherDTO::Ignore => return "*".to_string(),
+        AlarmMatcherDTO::Match { segments } => ("", segments),
+        AlarmMatcherDTO::DoNotMatch { segments } => ("!", segments),
+    };
+
+    let mut segments: Vec<&T> = segments.iter().collect();
+    segments.sort();
+
+    let segments: Vec<String> = segments
+        .into_iter()
+        .map(|segment| match serde_json::to_value(segment) {
+            Ok(Value::String(segment)) => segment,
+            Ok(segment) => segment.to_string(),
+            Err(_) => String::new(),
+        })
+        .collect();
+
+    format!("{prefix}{}", segments.join(" "))
+}
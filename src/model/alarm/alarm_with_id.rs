@@ -2,10 +2,11 @@ use crate::model::alarm::alarm::AlarmDTO;
 use crate::model::alarm::alarm_id::AlarmIdDTO;
 use crate::schedule_system::alarm_id::AlarmId;
 use clock::alarm::Alarm;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use http_server::to_response_data::ToResponseData;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct AlarmWithIdDTO {
     pub id: AlarmIdDTO,
     pub alarm: AlarmDTO,
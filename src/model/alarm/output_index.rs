@@ -1,8 +1,9 @@
 use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct OutputIndexDTO {
     pub output_index: u8
 }
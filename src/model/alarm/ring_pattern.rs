@@ -0,0 +1,33 @@
+use crate::schedule_system::ring_pattern::RingPattern;
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct RingPatternDTO {
+    pub impulse_length_millis: u64,
+    pub pause_length_millis: u64,
+    pub repeat: u8,
+}
+
+impl ToResponseData for RingPatternDTO {}
+
+impl From<RingPatternDTO> for RingPattern {
+    fn from(ring_pattern_dto: RingPatternDTO) -> Self {
+        Self {
+            impulse_length_millis: ring_pattern_dto.impulse_length_millis,
+            pause_length_millis: ring_pattern_dto.pause_length_millis,
+            repeat: ring_pattern_dto.repeat,
+        }
+    }
+}
+
+impl From<RingPattern> for RingPatternDTO {
+    fn from(ring_pattern: RingPattern) -> Self {
+        Self {
+            impulse_length_millis: ring_pattern.impulse_length_millis,
+            pause_length_millis: ring_pattern.pause_length_millis,
+            repeat: ring_pattern.repeat,
+        }
+    }
+}
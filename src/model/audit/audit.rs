@@ -0,0 +1,237 @@
+use crate::constant::{EVENT_PAGE_DEFAULT_LIMIT, EVENT_PAGE_MAX_LIMIT};
+use crate::schedule_system::audit::{AuditAction, AuditEntry, AuditFilter, AuditOutcome, AuditPage, AuditRecord, AuditVerification};
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+pub enum AuditActionDTO {
+    Login,
+    Logout,
+    SessionsRevoked,
+    PasswordChanged,
+    AccessPointPasswordChanged,
+    PasswordPolicyChanged,
+    SetupCompleted,
+    UserAdded,
+    UserUpdated,
+    UserRemoved,
+    ApiKeyCreated,
+    ApiKeyRemoved,
+    ApiKeyUsed,
+    TlsCertificateChanged,
+    PasswordsReset,
+    FactoryReset,
+    WebUiChanged,
+    UsersRestored,
+}
+
+impl From<AuditActionDTO> for AuditAction {
+    fn from(audit_action_dto: AuditActionDTO) -> Self {
+        match audit_action_dto {
+            AuditActionDTO::Login => AuditAction::Login,
+            AuditActionDTO::Logout => AuditAction::Logout,
+            AuditActionDTO::SessionsRevoked => AuditAction::SessionsRevoked,
+            AuditActionDTO::PasswordChanged => AuditAction::PasswordChanged,
+            AuditActionDTO::AccessPointPasswordChanged => AuditAction::AccessPointPasswordChanged,
+            AuditActionDTO::PasswordPolicyChanged => AuditAction::PasswordPolicyChanged,
+            AuditActionDTO::SetupCompleted => AuditAction::SetupCompleted,
+            AuditActionDTO::UserAdded => AuditAction::UserAdded,
+            AuditActionDTO::UserUpdated => AuditAction::UserUpdated,
+            AuditActionDTO::UserRemoved => AuditAction::UserRemoved,
+            AuditActionDTO::ApiKeyCreated => AuditAction::ApiKeyCreated,
+            AuditActionDTO::ApiKeyRemoved => AuditAction::ApiKeyRemoved,
+            AuditActionDTO::ApiKeyUsed => AuditAction::ApiKeyUsed,
+            AuditActionDTO::TlsCertificateChanged => AuditAction::TlsCertificateChanged,
+            AuditActionDTO::PasswordsReset => AuditAction::PasswordsReset,
+            AuditActionDTO::FactoryReset => AuditAction::FactoryReset,
+            AuditActionDTO::WebUiChanged => AuditAction::WebUiChanged,
+            AuditActionDTO::UsersRestored => AuditAction::UsersRestored,
+        }
+    }
+}
+
+impl From<AuditAction> for AuditActionDTO {
+    fn from(audit_action: AuditAction) -> Self {
+        match audit_action {
+            AuditAction::Login => AuditActionDTO::Login,
+            AuditAction::Logout => AuditActionDTO::Logout,
+            AuditAction::SessionsRevoked => AuditActionDTO::SessionsRevoked,
+            AuditAction::PasswordChanged => AuditActionDTO::PasswordChanged,
+            AuditAction::AccessPointPasswordChanged => AuditActionDTO::AccessPointPasswordChanged,
+            AuditAction::PasswordPolicyChanged => AuditActionDTO::PasswordPolicyChanged,
+            AuditAction::SetupCompleted => AuditActionDTO::SetupCompleted,
+            AuditAction::UserAdded => AuditActionDTO::UserAdded,
+            AuditAction::UserUpdated => AuditActionDTO::UserUpdated,
+            AuditAction::UserRemoved => AuditActionDTO::UserRemoved,
+            AuditAction::ApiKeyCreated => AuditActionDTO::ApiKeyCreated,
+            AuditAction::ApiKeyRemoved => AuditActionDTO::ApiKeyRemoved,
+            AuditAction::ApiKeyUsed => AuditActionDTO::ApiKeyUsed,
+            AuditAction::TlsCertificateChanged => AuditActionDTO::TlsCertificateChanged,
+            AuditAction::PasswordsReset => AuditActionDTO::PasswordsReset,
+            AuditAction::FactoryReset => AuditActionDTO::FactoryReset,
+            AuditAction::WebUiChanged => AuditActionDTO::WebUiChanged,
+            AuditAction::UsersRestored => AuditActionDTO::UsersRestored,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+pub enum AuditOutcomeDTO {
+    Success,
+    Failure,
+}
+
+impl From<AuditOutcomeDTO> for AuditOutcome {
+    fn from(audit_outcome_dto: AuditOutcomeDTO) -> Self {
+        match audit_outcome_dto {
+            AuditOutcomeDTO::Success => AuditOutcome::Success,
+            AuditOutcomeDTO::Failure => AuditOutcome::Failure,
+        }
+    }
+}
+
+impl From<AuditOutcome> for AuditOutcomeDTO {
+    fn from(audit_outcome: AuditOutcome) -> Self {
+        match audit_outcome {
+            AuditOutcome::Success => AuditOutcomeDTO::Success,
+            AuditOutcome::Failure => AuditOutcomeDTO::Failure,
+        }
+    }
+}
+
+/**
+ * Used by API and as line format of audit log on disk.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct AuditEntryDTO {
+    pub sequence: u64,
+    pub timestamp_millis: i64,
+    pub actor: String,
+    pub action: AuditActionDTO,
+    pub source_ip: Option<String>,
+    pub outcome: AuditOutcomeDTO,
+    pub details: String,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+impl ToResponseData for AuditEntryDTO {}
+
+/**
+ * Fails only for invalid IP, which means that line was modified.
+ */
+impl TryFrom<AuditEntryDTO> for AuditEntry {
+    type Error = String;
+
+    fn try_from(audit_entry_dto: AuditEntryDTO) -> Result<Self, Self::Error> {
+        let source_ip: Option<IpAddr> = audit_entry_dto.source_ip
+            .map(|source_ip| source_ip.parse().map_err(|_| format!("Invalid source IP '{source_ip}'.")))
+            .transpose()?;
+
+        Ok(Self {
+            sequence: audit_entry_dto.sequence,
+            timestamp_millis: audit_entry_dto.timestamp_millis,
+            record: AuditRecord {
+                actor: audit_entry_dto.actor,
+                action: audit_entry_dto.action.into(),
+                source_ip,
+                outcome: audit_entry_dto.outcome.into(),
+                details: audit_entry_dto.details,
+            },
+            previous_hash: audit_entry_dto.previous_hash,
+            hash: audit_entry_dto.hash,
+        })
+    }
+}
+
+impl From<AuditEntry> for AuditEntryDTO {
+    fn from(audit_entry: AuditEntry) -> Self {
+        Self {
+            sequence: audit_entry.sequence,
+            timestamp_millis: audit_entry.timestamp_millis,
+            actor: audit_entry.record.actor,
+            action: audit_entry.record.action.into(),
+            source_ip: audit_entry.record.source_ip.map(|source_ip| source_ip.to_string()),
+            outcome: audit_entry.record.outcome.into(),
+            details: audit_entry.record.details,
+            previous_hash: audit_entry.previous_hash,
+            hash: audit_entry.hash,
+        }
+    }
+}
+
+/**
+ * URL parameters of audit log query. All parameters are optional.
+ */
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
+pub struct AuditFilterDTO {
+    pub from_timestamp_millis: Option<i64>,
+    pub to_timestamp_millis: Option<i64>,
+    pub actor: Option<String>,
+    pub action: Option<AuditActionDTO>,
+    pub outcome: Option<AuditOutcomeDTO>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl From<AuditFilterDTO> for AuditFilter {
+    fn from(audit_filter_dto: AuditFilterDTO) -> Self {
+        Self {
+            from_timestamp_millis: audit_filter_dto.from_timestamp_millis,
+            to_timestamp_millis: audit_filter_dto.to_timestamp_millis,
+            actor: audit_filter_dto.actor,
+            action: audit_filter_dto.action.map(Into::into),
+            outcome: audit_filter_dto.outcome.map(Into::into),
+            offset: audit_filter_dto.offset.unwrap_or(0),
+            limit: audit_filter_dto.limit
+                .unwrap_or(EVENT_PAGE_DEFAULT_LIMIT)
+                .min(EVENT_PAGE_MAX_LIMIT),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct AuditPageDTO {
+    pub entries: Vec<AuditEntryDTO>,
+    pub has_more: bool,
+}
+
+impl ToResponseData for AuditPageDTO {}
+
+impl From<AuditPage> for AuditPageDTO {
+    fn from(audit_page: AuditPage) -> Self {
+        Self {
+            entries: audit_page.entries.into_iter().map(Into::into).collect(),
+            has_more: audit_page.has_more,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct AuditVerificationDTO {
+    pub valid: bool,
+    pub entries_count: u64,
+    pub first_sequence: Option<u64>,
+    pub last_sequence: Option<u64>,
+    pub broken_at_sequence: Option<u64>,
+    pub unreadable_lines_count: u64,
+    pub head_matches: bool,
+}
+
+impl ToResponseData for AuditVerificationDTO {}
+
+impl From<AuditVerification> for AuditVerificationDTO {
+    fn from(audit_verification: AuditVerification) -> Self {
+        Self {
+            valid: audit_verification.broken_at_sequence.is_none() && audit_verification.unreadable_lines_count == 0 && audit_verification.head_matches,
+            entries_count: audit_verification.entries_count,
+            first_sequence: audit_verification.first_sequence,
+            last_sequence: audit_verification.last_sequence,
+            broken_at_sequence: audit_verification.broken_at_sequence,
+            unreadable_lines_count: audit_verification.unreadable_lines_count,
+            head_matches: audit_verification.head_matches,
+        }
+    }
+}
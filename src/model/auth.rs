@@ -1,3 +1,7 @@
 pub mod login_credentials;
 pub mod api_credentials;
 pub mod access_point_credentials;
+pub mod user;
+pub mod setup;
+pub mod password_policy;
+pub mod api_key;
@@ -1,6 +1,9 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct AccessPointCredentials {
+    /* Password of logged in user, not of access point. */
+    pub current_password: String,
     pub password: String,
 }
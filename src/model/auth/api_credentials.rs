@@ -1,6 +1,11 @@
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+/**
+ * Password change of logged in user.
+ */
+#[derive(Deserialize, JsonSchema)]
 pub struct ApiCredentials {
+    pub current_password: String,
     pub password: String,
 }
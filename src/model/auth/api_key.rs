@@ -0,0 +1,137 @@
+use crate::security::api_key::ApiKey;
+use crate::security::user::Permission;
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+pub enum ApiKeyScopeDTO {
+    Read,
+    WriteAlarms,
+    Ring,
+    Admin,
+}
+
+impl From<ApiKeyScopeDTO> for Permission {
+    fn from(api_key_scope_dto: ApiKeyScopeDTO) -> Self {
+        match api_key_scope_dto {
+            ApiKeyScopeDTO::Read => Permission::Read,
+            ApiKeyScopeDTO::WriteAlarms => Permission::WriteAlarms,
+            ApiKeyScopeDTO::Ring => Permission::Ring,
+            ApiKeyScopeDTO::Admin => Permission::Admin,
+        }
+    }
+}
+
+impl From<Permission> for ApiKeyScopeDTO {
+    fn from(permission: Permission) -> Self {
+        match permission {
+            Permission::Read => ApiKeyScopeDTO::Read,
+            Permission::WriteAlarms => ApiKeyScopeDTO::WriteAlarms,
+            Permission::Ring => ApiKeyScopeDTO::Ring,
+            Permission::Admin => ApiKeyScopeDTO::Admin,
+        }
+    }
+}
+
+/**
+ * API key without secret hash returned by API.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct ApiKeyDTO {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<ApiKeyScopeDTO>,
+    pub created_at_millis: i64,
+    pub expires_at_millis: Option<i64>,
+    pub last_used_at_millis: Option<i64>,
+}
+
+impl ToResponseData for ApiKeyDTO {}
+
+impl From<ApiKey> for ApiKeyDTO {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            name: api_key.name,
+            owner: api_key.owner,
+            scopes: api_key.scopes.into_iter().map(Into::into).collect(),
+            created_at_millis: api_key.created_at_seconds as i64 * 1000,
+            expires_at_millis: api_key.expires_at_seconds.map(|seconds| seconds as i64 * 1000),
+            last_used_at_millis: api_key.last_used_at_seconds.map(|seconds| seconds as i64 * 1000),
+        }
+    }
+}
+
+/**
+ * Key never expires when expiration time isn't provided.
+ */
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct NewApiKeyDTO {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScopeDTO>,
+    pub expires_at_millis: Option<i64>,
+}
+
+/**
+ * Plaintext key is returned only on creation.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct CreatedApiKeyDTO {
+    pub key: String,
+    pub api_key: ApiKeyDTO,
+}
+
+impl ToResponseData for CreatedApiKeyDTO {}
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct ApiKeyIdDTO {
+    pub id: String,
+}
+
+/**
+ * API key as stored in NVS.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StoredApiKeyDTO {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<ApiKeyScopeDTO>,
+    pub secret_hash: String,
+    pub created_at_seconds: u64,
+    pub expires_at_seconds: Option<u64>,
+    pub last_used_at_seconds: Option<u64>,
+}
+
+impl From<StoredApiKeyDTO> for ApiKey {
+    fn from(stored_api_key_dto: StoredApiKeyDTO) -> Self {
+        Self {
+            id: stored_api_key_dto.id,
+            name: stored_api_key_dto.name,
+            owner: stored_api_key_dto.owner,
+            scopes: stored_api_key_dto.scopes.into_iter().map(Into::into).collect(),
+            secret_hash: stored_api_key_dto.secret_hash,
+            created_at_seconds: stored_api_key_dto.created_at_seconds,
+            expires_at_seconds: stored_api_key_dto.expires_at_seconds,
+            last_used_at_seconds: stored_api_key_dto.last_used_at_seconds,
+            audited_ips: vec![],
+        }
+    }
+}
+
+impl From<ApiKey> for StoredApiKeyDTO {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            name: api_key.name,
+            owner: api_key.owner,
+            scopes: api_key.scopes.into_iter().map(Into::into).collect(),
+            secret_hash: api_key.secret_hash,
+            created_at_seconds: api_key.created_at_seconds,
+            expires_at_seconds: api_key.expires_at_seconds,
+            last_used_at_seconds: api_key.last_used_at_seconds,
+        }
+    }
+}
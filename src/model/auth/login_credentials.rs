@@ -1,6 +1,15 @@
+use crate::security::user::ADMIN_USERNAME;
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct LoginCredentials {
+    /* Clients of single user firmware send only password. */
+    #[serde(default = "default_username")]
+    pub username: String,
     pub password: String,
 }
+
+fn default_username() -> String {
+    ADMIN_USERNAME.to_string()
+}
@@ -0,0 +1,42 @@
+use crate::security::password_policy::PasswordPolicy;
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/**
+ * Used by API and as stored format in NVS.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct PasswordPolicyDTO {
+    pub min_length: usize,
+    pub require_letter: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub reject_username: bool,
+}
+
+impl ToResponseData for PasswordPolicyDTO {}
+
+impl From<PasswordPolicyDTO> for PasswordPolicy {
+    fn from(password_policy_dto: PasswordPolicyDTO) -> Self {
+        Self {
+            min_length: password_policy_dto.min_length,
+            require_letter: password_policy_dto.require_letter,
+            require_digit: password_policy_dto.require_digit,
+            require_symbol: password_policy_dto.require_symbol,
+            reject_username: password_policy_dto.reject_username,
+        }
+    }
+}
+
+impl From<PasswordPolicy> for PasswordPolicyDTO {
+    fn from(password_policy: PasswordPolicy) -> Self {
+        Self {
+            min_length: password_policy.min_length,
+            require_letter: password_policy.require_letter,
+            require_digit: password_policy.require_digit,
+            require_symbol: password_policy.require_symbol,
+            reject_username: password_policy.reject_username,
+        }
+    }
+}
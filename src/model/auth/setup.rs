@@ -0,0 +1,19 @@
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct SetupDTO {
+    /* New password of logged in user. */
+    pub password: String,
+    pub access_point_password: String,
+    pub device_name: String,
+    pub timestamp_millis: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct SetupStatusDTO {
+    pub setup_required: bool,
+}
+
+impl ToResponseData for SetupStatusDTO {}
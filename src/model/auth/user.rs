@@ -0,0 +1,104 @@
+use crate::security::user::{Role, User};
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+pub enum RoleDTO {
+    Admin,
+    Operator,
+    Viewer,
+}
+
+impl From<RoleDTO> for Role {
+    fn from(role_dto: RoleDTO) -> Self {
+        match role_dto {
+            RoleDTO::Admin => Role::Admin,
+            RoleDTO::Operator => Role::Operator,
+            RoleDTO::Viewer => Role::Viewer,
+        }
+    }
+}
+
+impl From<Role> for RoleDTO {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Admin => RoleDTO::Admin,
+            Role::Operator => RoleDTO::Operator,
+            Role::Viewer => RoleDTO::Viewer,
+        }
+    }
+}
+
+/**
+ * User without password hash returned by API.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct UserDTO {
+    pub username: String,
+    pub role: RoleDTO,
+}
+
+impl ToResponseData for UserDTO {}
+
+impl From<User> for UserDTO {
+    fn from(user: User) -> Self {
+        Self {
+            username: user.username,
+            role: user.role.into(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct NewUserDTO {
+    pub username: String,
+    pub password: String,
+    pub role: RoleDTO,
+}
+
+/**
+ * Fields which are not provided stay unchanged.
+ */
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct UserUpdateDTO {
+    pub password: Option<String>,
+    pub role: Option<RoleDTO>,
+}
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct UsernameDTO {
+    pub username: String,
+}
+
+/**
+ * User as stored in NVS.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StoredUserDTO {
+    pub username: String,
+    pub role: RoleDTO,
+    pub password_hash: String,
+}
+
+impl From<StoredUserDTO> for User {
+    fn from(stored_user_dto: StoredUserDTO) -> Self {
+        Self {
+            username: stored_user_dto.username,
+            role: stored_user_dto.role.into(),
+            password_hash: stored_user_dto.password_hash,
+            /* deriving hash is slow, so it's checked by security context only when users are loaded */
+            has_default_password: false,
+        }
+    }
+}
+
+impl From<User> for StoredUserDTO {
+    fn from(user: User) -> Self {
+        Self {
+            username: user.username,
+            role: user.role.into(),
+            password_hash: user.password_hash,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::schedule_system::emergency::{Emergency, EmergencyKind, EmergencySignal};
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub enum EmergencyKindDTO {
+    Lockdown,
+    Evacuation,
+}
+
+impl From<EmergencyKindDTO> for EmergencyKind {
+    fn from(emergency_kind_dto: EmergencyKindDTO) -> Self {
+        match emergency_kind_dto {
+            EmergencyKindDTO::Lockdown => EmergencyKind::Lockdown,
+            EmergencyKindDTO::Evacuation => EmergencyKind::Evacuation,
+        }
+    }
+}
+
+impl From<EmergencyKind> for EmergencyKindDTO {
+    fn from(emergency_kind: EmergencyKind) -> Self {
+        match emergency_kind {
+            EmergencyKind::Lockdown => EmergencyKindDTO::Lockdown,
+            EmergencyKind::Evacuation => EmergencyKindDTO::Evacuation,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "tag")]
+pub enum EmergencySignalDTO {
+    Continuous,
+    Intermittent { on_millis: u64, off_millis: u64 },
+}
+
+impl From<EmergencySignalDTO> for EmergencySignal {
+    fn from(emergency_signal_dto: EmergencySignalDTO) -> Self {
+        match emergency_signal_dto {
+            EmergencySignalDTO::Continuous => EmergencySignal::Continuous,
+            EmergencySignalDTO::Intermittent { on_millis, off_millis } => EmergencySignal::Intermittent { on_millis, off_millis },
+        }
+    }
+}
+
+impl From<EmergencySignal> for EmergencySignalDTO {
+    fn from(emergency_signal: EmergencySignal) -> Self {
+        match emergency_signal {
+            EmergencySignal::Continuous => EmergencySignalDTO::Continuous,
+            EmergencySignal::Intermittent { on_millis, off_millis } => EmergencySignalDTO::Intermittent { on_millis, off_millis },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct EmergencyDTO {
+    pub kind: EmergencyKindDTO,
+    pub output_indices: Vec<u8>,
+    pub signal: EmergencySignalDTO,
+}
+
+impl ToResponseData for EmergencyDTO {}
+
+impl From<EmergencyDTO> for Emergency {
+    fn from(emergency_dto: EmergencyDTO) -> Self {
+        Self {
+            kind: emergency_dto.kind.into(),
+            output_indices: emergency_dto.output_indices,
+            signal: emergency_dto.signal.into(),
+        }
+    }
+}
+
+impl From<Emergency> for EmergencyDTO {
+    fn from(emergency: Emergency) -> Self {
+        Self {
+            kind: emergency.kind.into(),
+            output_indices: emergency.output_indices,
+            signal: emergency.signal.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct EmergencyStatusDTO {
+    pub emergency: Option<EmergencyDTO>,
+}
+
+impl ToResponseData for EmergencyStatusDTO {}
@@ -0,0 +1,140 @@
+use crate::constant::{EVENT_PAGE_DEFAULT_LIMIT, EVENT_PAGE_MAX_LIMIT};
+use crate::schedule_system::event::{Event, EventFilter, EventKind, EventPage};
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+pub enum EventKindDTO {
+    AlarmFired,
+    AlarmSkipped,
+    RingFailed,
+    ManualRing,
+    EmergencyStarted,
+    EmergencyStopped,
+    SilentModeChanged,
+    ProfileSwitched,
+    TimeChanged,
+    SettingsChanged,
+    AlarmsChanged,
+    InputsChanged,
+    Login,
+    LoginFailed,
+    LoginBlocked,
+}
+
+impl From<EventKindDTO> for EventKind {
+    fn from(event_kind_dto: EventKindDTO) -> Self {
+        match event_kind_dto {
+            EventKindDTO::AlarmFired => EventKind::AlarmFired,
+            EventKindDTO::AlarmSkipped => EventKind::AlarmSkipped,
+            EventKindDTO::RingFailed => EventKind::RingFailed,
+            EventKindDTO::ManualRing => EventKind::ManualRing,
+            EventKindDTO::EmergencyStarted => EventKind::EmergencyStarted,
+            EventKindDTO::EmergencyStopped => EventKind::EmergencyStopped,
+            EventKindDTO::SilentModeChanged => EventKind::SilentModeChanged,
+            EventKindDTO::ProfileSwitched => EventKind::ProfileSwitched,
+            EventKindDTO::TimeChanged => EventKind::TimeChanged,
+            EventKindDTO::SettingsChanged => EventKind::SettingsChanged,
+            EventKindDTO::AlarmsChanged => EventKind::AlarmsChanged,
+            EventKindDTO::InputsChanged => EventKind::InputsChanged,
+            EventKindDTO::Login => EventKind::Login,
+            EventKindDTO::LoginFailed => EventKind::LoginFailed,
+            EventKindDTO::LoginBlocked => EventKind::LoginBlocked,
+        }
+    }
+}
+
+impl From<EventKind> for EventKindDTO {
+    fn from(event_kind: EventKind) -> Self {
+        match event_kind {
+            EventKind::AlarmFired => EventKindDTO::AlarmFired,
+            EventKind::AlarmSkipped => EventKindDTO::AlarmSkipped,
+            EventKind::RingFailed => EventKindDTO::RingFailed,
+            EventKind::ManualRing => EventKindDTO::ManualRing,
+            EventKind::EmergencyStarted => EventKindDTO::EmergencyStarted,
+            EventKind::EmergencyStopped => EventKindDTO::EmergencyStopped,
+            EventKind::SilentModeChanged => EventKindDTO::SilentModeChanged,
+            EventKind::ProfileSwitched => EventKindDTO::ProfileSwitched,
+            EventKind::TimeChanged => EventKindDTO::TimeChanged,
+            EventKind::SettingsChanged => EventKindDTO::SettingsChanged,
+            EventKind::AlarmsChanged => EventKindDTO::AlarmsChanged,
+            EventKind::InputsChanged => EventKindDTO::InputsChanged,
+            EventKind::Login => EventKindDTO::Login,
+            EventKind::LoginFailed => EventKindDTO::LoginFailed,
+            EventKind::LoginBlocked => EventKindDTO::LoginBlocked,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct EventDTO {
+    pub timestamp_millis: i64,
+    pub kind: EventKindDTO,
+    pub details: String,
+}
+
+impl ToResponseData for EventDTO {}
+
+impl From<EventDTO> for Event {
+    fn from(event_dto: EventDTO) -> Self {
+        Self {
+            timestamp_millis: event_dto.timestamp_millis,
+            kind: event_dto.kind.into(),
+            details: event_dto.details,
+        }
+    }
+}
+
+impl From<Event> for EventDTO {
+    fn from(event: Event) -> Self {
+        Self {
+            timestamp_millis: event.timestamp_millis,
+            kind: event.kind.into(),
+            details: event.details,
+        }
+    }
+}
+
+/**
+ * URL parameters of events query. All parameters are optional.
+ */
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
+pub struct EventFilterDTO {
+    pub from_timestamp_millis: Option<i64>,
+    pub to_timestamp_millis: Option<i64>,
+    pub kind: Option<EventKindDTO>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl From<EventFilterDTO> for EventFilter {
+    fn from(event_filter_dto: EventFilterDTO) -> Self {
+        Self {
+            from_timestamp_millis: event_filter_dto.from_timestamp_millis,
+            to_timestamp_millis: event_filter_dto.to_timestamp_millis,
+            kind: event_filter_dto.kind.map(Into::into),
+            offset: event_filter_dto.offset.unwrap_or(0),
+            limit: event_filter_dto.limit
+                .unwrap_or(EVENT_PAGE_DEFAULT_LIMIT)
+                .min(EVENT_PAGE_MAX_LIMIT),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct EventPageDTO {
+    pub events: Vec<EventDTO>,
+    pub has_more: bool,
+}
+
+impl ToResponseData for EventPageDTO {}
+
+impl From<EventPage> for EventPageDTO {
+    fn from(event_page: EventPage) -> Self {
+        Self {
+            events: event_page.events.into_iter().map(Into::into).collect(),
+            has_more: event_page.has_more,
+        }
+    }
+}
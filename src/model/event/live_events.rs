@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * First message of WebSocket client. Browsers can't set headers of WebSocket request, so access token is sent in message.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LiveEventsAuthDTO {
+    pub access_token: String,
+}
@@ -0,0 +1,140 @@
+use crate::model::alarm::ring_pattern::RingPatternDTO;
+use crate::model::emergency::emergency::EmergencyDTO;
+use crate::schedule_system::input_config::{InputAction, InputConfig};
+use interface::gpio::{Level, Pull};
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub enum PullDTO {
+    Floating,
+    Up,
+    Down,
+    UpDown,
+}
+
+impl From<PullDTO> for Pull {
+    fn from(pull_dto: PullDTO) -> Self {
+        match pull_dto {
+            PullDTO::Floating => Pull::Floating,
+            PullDTO::Up => Pull::Up,
+            PullDTO::Down => Pull::Down,
+            PullDTO::UpDown => Pull::UpDown,
+        }
+    }
+}
+
+impl From<Pull> for PullDTO {
+    fn from(pull: Pull) -> Self {
+        match pull {
+            Pull::Floating => PullDTO::Floating,
+            Pull::Up => PullDTO::Up,
+            Pull::Down => PullDTO::Down,
+            Pull::UpDown => PullDTO::UpDown,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub enum LevelDTO {
+    Low,
+    High,
+}
+
+impl From<LevelDTO> for Level {
+    fn from(level_dto: LevelDTO) -> Self {
+        match level_dto {
+            LevelDTO::Low => Level::Low,
+            LevelDTO::High => Level::High,
+        }
+    }
+}
+
+impl From<Level> for LevelDTO {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Low => LevelDTO::Low,
+            Level::High => LevelDTO::High,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(tag = "tag")]
+pub enum InputActionDTO {
+    RingOutput { output_index: u8, pattern: RingPatternDTO },
+    StartEmergency { emergency: EmergencyDTO },
+    StopEmergency,
+    ToggleSilentMode,
+    SwitchProfile { profile: u8 },
+}
+
+impl From<InputActionDTO> for InputAction {
+    fn from(input_action_dto: InputActionDTO) -> Self {
+        match input_action_dto {
+            InputActionDTO::RingOutput { output_index, pattern } => InputAction::RingOutput {
+                output_index,
+                pattern: pattern.into(),
+            },
+            InputActionDTO::StartEmergency { emergency } => InputAction::StartEmergency {
+                emergency: emergency.into(),
+            },
+            InputActionDTO::StopEmergency => InputAction::StopEmergency,
+            InputActionDTO::ToggleSilentMode => InputAction::ToggleSilentMode,
+            InputActionDTO::SwitchProfile { profile } => InputAction::SwitchProfile { profile },
+        }
+    }
+}
+
+impl From<InputAction> for InputActionDTO {
+    fn from(input_action: InputAction) -> Self {
+        match input_action {
+            InputAction::RingOutput { output_index, pattern } => InputActionDTO::RingOutput {
+                output_index,
+                pattern: pattern.into(),
+            },
+            InputAction::StartEmergency { emergency } => InputActionDTO::StartEmergency {
+                emergency: emergency.into(),
+            },
+            InputAction::StopEmergency => InputActionDTO::StopEmergency,
+            InputAction::ToggleSilentMode => InputActionDTO::ToggleSilentMode,
+            InputAction::SwitchProfile { profile } => InputActionDTO::SwitchProfile { profile },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct InputConfigDTO {
+    pub gpio: i32,
+    pub pull: PullDTO,
+    pub active_level: LevelDTO,
+    pub debounce_millis: u64,
+    pub action: InputActionDTO,
+}
+
+impl ToResponseData for InputConfigDTO {}
+
+impl From<InputConfigDTO> for InputConfig {
+    fn from(input_config_dto: InputConfigDTO) -> Self {
+        Self {
+            gpio: input_config_dto.gpio,
+            pull: input_config_dto.pull.into(),
+            active_level: input_config_dto.active_level.into(),
+            debounce_millis: input_config_dto.debounce_millis,
+            action: input_config_dto.action.into(),
+        }
+    }
+}
+
+impl From<InputConfig> for InputConfigDTO {
+    fn from(input_config: InputConfig) -> Self {
+        Self {
+            gpio: input_config.gpio,
+            pull: input_config.pull.into(),
+            active_level: input_config.active_level.into(),
+            debounce_millis: input_config.debounce_millis,
+            action: input_config.action.into(),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::schedule_system::settings::{Settings, SettingsPatch};
+use crate::security::session::SessionStoreKind;
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/**
+ * Missing fields are filled with defaults, so settings stored by older firmware can be loaded.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(default)]
+pub struct SettingsDTO {
+    pub schema_version: u32,
+    pub access_point_ssid: String,
+    pub alarm_match_check_interval_ms: u64,
+    pub display_refresh_interval_ms: u64,
+    pub device_info_display_seconds: u64,
+    pub reset_button_passwords_hold_seconds: u64,
+    pub reset_button_factory_hold_seconds: u64,
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allowed_methods: Vec<String>,
+    pub cors_allow_credentials: bool,
+    pub max_request_body_bytes: u32,
+    pub https_enabled: bool,
+    pub https_redirect_enabled: bool,
+    pub session_store: SessionStoreKindDTO,
+}
+
+impl ToResponseData for SettingsDTO {}
+
+impl Default for SettingsDTO {
+    fn default() -> Self {
+        Settings::default().into()
+    }
+}
+
+impl From<SettingsDTO> for Settings {
+    fn from(settings_dto: SettingsDTO) -> Self {
+        Self {
+            schema_version: settings_dto.schema_version,
+            access_point_ssid: settings_dto.access_point_ssid,
+            alarm_match_check_interval_ms: settings_dto.alarm_match_check_interval_ms,
+            display_refresh_interval_ms: settings_dto.display_refresh_interval_ms,
+            device_info_display_seconds: settings_dto.device_info_display_seconds,
+            reset_button_passwords_hold_seconds: settings_dto.reset_button_passwords_hold_seconds,
+            reset_button_factory_hold_seconds: settings_dto.reset_button_factory_hold_seconds,
+            cors_allowed_origins: settings_dto.cors_allowed_origins,
+            cors_allowed_methods: settings_dto.cors_allowed_methods,
+            cors_allow_credentials: settings_dto.cors_allow_credentials,
+            max_request_body_bytes: settings_dto.max_request_body_bytes,
+            https_enabled: settings_dto.https_enabled,
+            https_redirect_enabled: settings_dto.https_redirect_enabled,
+            session_store: settings_dto.session_store.into(),
+        }
+    }
+}
+
+impl From<Settings> for SettingsDTO {
+    fn from(settings: Settings) -> Self {
+        Self {
+            schema_version: settings.schema_version,
+            access_point_ssid: settings.access_point_ssid,
+            alarm_match_check_interval_ms: settings.alarm_match_check_interval_ms,
+            display_refresh_interval_ms: settings.display_refresh_interval_ms,
+            device_info_display_seconds: settings.device_info_display_seconds,
+            reset_button_passwords_hold_seconds: settings.reset_button_passwords_hold_seconds,
+            reset_button_factory_hold_seconds: settings.reset_button_factory_hold_seconds,
+            cors_allowed_origins: settings.cors_allowed_origins,
+            cors_allowed_methods: settings.cors_allowed_methods,
+            cors_allow_credentials: settings.cors_allow_credentials,
+            max_request_body_bytes: settings.max_request_body_bytes,
+            https_enabled: settings.https_enabled,
+            https_redirect_enabled: settings.https_redirect_enabled,
+            session_store: settings.session_store.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, JsonSchema)]
+pub struct SettingsPatchDTO {
+    pub access_point_ssid: Option<String>,
+    pub alarm_match_check_interval_ms: Option<u64>,
+    pub display_refresh_interval_ms: Option<u64>,
+    pub device_info_display_seconds: Option<u64>,
+    pub reset_button_passwords_hold_seconds: Option<u64>,
+    pub reset_button_factory_hold_seconds: Option<u64>,
+    pub cors_allowed_origins: Option<Vec<String>>,
+    pub cors_allowed_methods: Option<Vec<String>>,
+    pub cors_allow_credentials: Option<bool>,
+    pub max_request_body_bytes: Option<u32>,
+    pub https_enabled: Option<bool>,
+    pub https_redirect_enabled: Option<bool>,
+    pub session_store: Option<SessionStoreKindDTO>,
+}
+
+impl From<SettingsPatchDTO> for SettingsPatch {
+    fn from(settings_patch_dto: SettingsPatchDTO) -> Self {
+        Self {
+            access_point_ssid: settings_patch_dto.access_point_ssid,
+            alarm_match_check_interval_ms: settings_patch_dto.alarm_match_check_interval_ms,
+            display_refresh_interval_ms: settings_patch_dto.display_refresh_interval_ms,
+            device_info_display_seconds: settings_patch_dto.device_info_display_seconds,
+            reset_button_passwords_hold_seconds: settings_patch_dto.reset_button_passwords_hold_seconds,
+            reset_button_factory_hold_seconds: settings_patch_dto.reset_button_factory_hold_seconds,
+            cors_allowed_origins: settings_patch_dto.cors_allowed_origins,
+            cors_allowed_methods: settings_patch_dto.cors_allowed_methods,
+            cors_allow_credentials: settings_patch_dto.cors_allow_credentials,
+            max_request_body_bytes: settings_patch_dto.max_request_body_bytes,
+            https_enabled: settings_patch_dto.https_enabled,
+            https_redirect_enabled: settings_patch_dto.https_redirect_enabled,
+            session_store: settings_patch_dto.session_store.map(Into::into),
+        }
+    }
+}
+
+/**
+ * Memory sessions support idle timeout and are lost on reboot. Signed tokens survive reboot.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+pub enum SessionStoreKindDTO {
+    Memory,
+    SignedToken,
+}
+
+impl From<SessionStoreKindDTO> for SessionStoreKind {
+    fn from(session_store_kind_dto: SessionStoreKindDTO) -> Self {
+        match session_store_kind_dto {
+            SessionStoreKindDTO::Memory => SessionStoreKind::Memory,
+            SessionStoreKindDTO::SignedToken => SessionStoreKind::SignedToken,
+        }
+    }
+}
+
+impl From<SessionStoreKind> for SessionStoreKindDTO {
+    fn from(session_store_kind: SessionStoreKind) -> Self {
+        match session_store_kind {
+            SessionStoreKind::Memory => SessionStoreKindDTO::Memory,
+            SessionStoreKind::SignedToken => SessionStoreKindDTO::SignedToken,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use http_server::tls::TlsCertificate;
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/**
+ * Uploaded certificate. Certificate PEM may contain intermediate certificates after server certificate.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct TlsCertificateDTO {
+    pub certificate_pem: String,
+    pub private_key_pem: String,
+}
+
+/**
+ * Stored certificate without private key.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct TlsCertificateInfoDTO {
+    pub subject: String,
+    pub issuer: String,
+    pub self_signed: bool,
+    pub not_before_millis: i64,
+    pub not_after_millis: i64,
+    pub certificate_pem: String,
+}
+
+impl ToResponseData for TlsCertificateInfoDTO {}
+
+impl From<&TlsCertificate> for TlsCertificateInfoDTO {
+    fn from(tls_certificate: &TlsCertificate) -> Self {
+        Self {
+            subject: tls_certificate.subject(),
+            issuer: tls_certificate.issuer(),
+            self_signed: tls_certificate.is_self_signed(),
+            not_before_millis: tls_certificate.not_before().as_millis() as i64,
+            not_after_millis: tls_certificate.not_after().as_millis() as i64,
+            certificate_pem: tls_certificate.certificate_pem().to_string(),
+        }
+    }
+}
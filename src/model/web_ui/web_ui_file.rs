@@ -0,0 +1,33 @@
+use crate::schedule_system::web_ui::WebUiFile;
+use http_server::to_response_data::ToResponseData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/**
+ * File of web interface. Names are FAT short names, so they are listed in upper case.
+ */
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+pub struct WebUiFileDTO {
+    /* relative to web interface directory, e.g. "GZ/APP.JS" */
+    pub path: String,
+    pub size: u32,
+}
+
+impl ToResponseData for WebUiFileDTO {}
+
+impl From<WebUiFile> for WebUiFileDTO {
+    fn from(web_ui_file: WebUiFile) -> Self {
+        Self {
+            path: web_ui_file.path,
+            size: web_ui_file.size,
+        }
+    }
+}
+
+/**
+ * URI example: /api/v1/web-ui/files?path=gz/app.js
+ */
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct WebUiFilePathDTO {
+    pub path: String,
+}
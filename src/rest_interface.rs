@@ -1,17 +1,143 @@
+mod api_key_controller;
 mod auth_controller;
+mod audit_controller;
 mod clock_controller;
 mod alarm_controller;
+mod input_controller;
+#[cfg(target_os = "espidf")]
+mod live_event_controller;
+mod openapi_controller;
+mod emergency_controller;
+mod event_controller;
+mod settings_controller;
+mod setup_controller;
+mod tls_controller;
+mod user_controller;
+mod web_ui_controller;
 mod security;
 
+use crate::constant::API_VERSIONS;
 use crate::schedule_system::ScheduleSystem;
-use esp_idf_svc::sys::EspError;
+use crate::security::session::Session;
+use crate::security::user::Permission;
+use crate::security::SecurityContext;
+use embedded_svc::http::server::{Connection, Request};
+use embedded_svc::http::Method;
+#[cfg(target_os = "espidf")]
+use interface::EspError;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+#[cfg(target_os = "espidf")]
 use http_server::http_server::HttpServer;
+use http_server::middleware::{Middleware, Next};
+use http_server::router::request_context::RequestContext;
+#[cfg(target_os = "espidf")]
+use http_server::router::EspRouter;
+use http_server::router::{unversioned_path, Api, ConnectionFamily, Route, ServerConnection};
+#[cfg(target_os = "espidf")]
 use std::sync::Arc;
 
+/* available before first-run setup is completed, in every API version. Password policy is read by setup wizard. */
+const SETUP_ALLOWED_ROUTES: [(Method, &str); 4] = [
+    (Method::Post, "/login"),
+    (Method::Get, "/setup"),
+    (Method::Post, "/setup"),
+    (Method::Get, "/password-policy"),
+];
+
+/* Router is registered for all methods of this URI, before wildcard of web interface. */
+#[cfg(target_os = "espidf")]
+const API_URI: &str = "/api/*";
+
+/**
+ * Request context of API handlers. Principal is session of access token or API key.
+ */
+pub type ApiContext<'r, 'c, C> = RequestContext<'r, 'c, C, ScheduleSystem, Session>;
+
+pub type ApiRoute<C> = Route<C, RestApi>;
+
+pub struct RestApi;
+
+impl Api for RestApi {
+    type State = ScheduleSystem;
+    type Principal = Session;
+    type Permission = Permission;
+
+    fn authenticate<C: ServerConnection>(request: &mut Request<C>, _schedule_system: &ScheduleSystem) -> Option<Session> {
+        security::authenticate_request(request).ok()
+    }
+
+    fn authorize(session: &Session, permission: Permission, _schedule_system: &ScheduleSystem) -> bool {
+        SecurityContext::get()
+            .is_ok_and(|security_context| security_context.authorize(session, permission).is_ok())
+    }
+
+    fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+        [
+            auth_controller::routes(),
+            clock_controller::routes(),
+            alarm_controller::routes(),
+            input_controller::routes(),
+            emergency_controller::routes(),
+            event_controller::routes(),
+            settings_controller::routes(),
+            user_controller::routes(),
+            api_key_controller::routes(),
+            audit_controller::routes(),
+            setup_controller::routes(),
+            tls_controller::routes(),
+            web_ui_controller::routes(),
+            openapi_controller::routes(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /* new version is added with routes of changed DTOs only, e.g. "/api/v2/clock" */
+    const VERSIONS: &'static [&'static str] = API_VERSIONS;
+}
+
+/**
+ * Register REST routes and live events. Access of every route is declared in its controller.
+ */
+#[cfg(target_os = "espidf")]
 pub fn serve(http_server: &mut HttpServer, schedule_system: Arc<ScheduleSystem>) -> Result<(), EspError> {
-    auth_controller::serve(http_server)?;
-    clock_controller::serve(http_server, Arc::clone(&schedule_system))?;
-    alarm_controller::serve(http_server, Arc::clone(&schedule_system))?;
+    http_server.add_middleware(SetupGuard);
+
+    live_event_controller::serve(http_server, Arc::clone(&schedule_system))?;
+
+    EspRouter::<RestApi>::new(schedule_system).mount(http_server, API_URI)
+}
+
+/**
+ * Rejects API requests until first-run setup is completed, except the ones needed for setup.
+ */
+pub struct SetupGuard;
+
+impl<C: Connection> Middleware<C> for SetupGuard {
+    fn handle(&self, request: Request<&mut C>, next: Next<'_, C>) -> RequestResult<(), C::Error> {
+        let path: &str = request
+            .uri()
+            .split_once('?')
+            .map_or(request.uri(), |(path, _)| path);
+
+        let method: Method = request.method();
+
+        let is_allowed: bool = unversioned_path(API_VERSIONS, path)
+            .is_some_and(|route_path| SETUP_ALLOWED_ROUTES.contains(&(method, route_path)));
+
+        if !path.starts_with("/api/") || is_allowed {
+            return next.run(request);
+        }
+
+        let setup_required: bool = SecurityContext::get()
+            .is_ok_and(|security_context| security_context.is_setup_required());
+
+        if setup_required {
+            return request.error(&ApiError::forbidden("setup_required", "Setup required. Change default passwords via setup endpoint."));
+        }
 
-    Ok(())
+        next.run(request)
+    }
 }
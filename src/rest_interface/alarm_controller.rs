@@ -1,131 +1,114 @@
-use crate::schedule_system::alarm_id::AlarmId;
-use crate::schedule_system::ScheduleSystem;
-use clock::alarm::Alarm;
-use esp_idf_svc::http::server::{EspHttpConnection, Request};
-use esp_idf_svc::http::Method;
-use esp_idf_svc::io::EspIOError;
-use esp_idf_svc::sys::EspError;
-use http_request::RequestResult;
-use http_server::http_request;
-use http_server::http_request::{IntoResponse, ReadData, ReadParameters, RequestError};
-use http_server::http_server::HttpServer;
-use std::sync::Arc;
 use crate::model::alarm::alarm::AlarmDTO;
 use crate::model::alarm::alarm_id::AlarmIdDTO;
+use crate::model::alarm::alarm_list::AlarmListDTO;
 use crate::model::alarm::alarm_with_id::AlarmWithIdDTO;
 use crate::model::alarm::output_index::OutputIndexDTO;
-use crate::rest_interface::security::authenticate_request;
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::alarm_id::AlarmId;
 use crate::schedule_system::to_alarms_with_id::ToAlarmsWithId;
+use crate::schedule_system::ScheduleSystem;
+use crate::security::user::Permission;
+use clock::alarm::Alarm;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
 
-pub fn serve(http_server: &mut HttpServer, schedule_system: Arc<ScheduleSystem>) -> Result<(), EspError> {
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
-    http_server.add_handler(
-        "/api/v1/alarm/output_indices", Method::Get,
-        move |request| get_alarm_output_indices(request, &schedule_system_clone)
-    )?;
-
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
-    http_server.add_handler(
-        "/api/v1/alarm", Method::Get,
-        move |request| get_alarm(request, &schedule_system_clone)
-    )?;
-
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
-    http_server.add_handler(
-        "/api/v1/alarms", Method::Get,
-        move |request| get_alarms_by_output_index(request, &schedule_system_clone)
-    )?;
-
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
-    http_server.add_handler(
-        "/api/v1/alarm", Method::Post,
-        move |request| add_alarm(request, &schedule_system_clone)
-    )?;
-
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
-    http_server.add_handler(
-        "/api/v1/alarm", Method::Delete,
-        move |request| delete_alarm(request, &schedule_system_clone)
-    )?;
-
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
-    http_server.add_handler(
-        "/api/v1/alarms", Method::Delete,
-        move |request| delete_alarms_by_output_index(request, &schedule_system_clone)
-    )?;
-
-    Ok(())
+/**
+ * Path parameters are named after fields of OutputIndexDTO and AlarmIdDTO.
+ */
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/outputs", Access::Permission(Permission::Read), get_alarm_output_indices)
+            .json_response::<Vec<usize>>(),
+        Route::get("/api/v1/outputs/{output_index}/alarms", Access::Permission(Permission::Read), get_alarms_by_output_index)
+            .path_params::<OutputIndexDTO>()
+            .table_response::<AlarmListDTO>(),
+        Route::post("/api/v1/outputs/{output_index}/alarms", Access::Permission(Permission::WriteAlarms), add_alarm)
+            .path_params::<OutputIndexDTO>()
+            .json_body::<AlarmDTO>()
+            .text_response(),
+        Route::delete("/api/v1/outputs/{output_index}/alarms", Access::Permission(Permission::WriteAlarms), delete_alarms_by_output_index)
+            .path_params::<OutputIndexDTO>()
+            .text_response(),
+        Route::get("/api/v1/outputs/{output_index}/alarms/{identifier}", Access::Permission(Permission::Read), get_alarm)
+            .path_params::<AlarmIdDTO>()
+            .json_response::<AlarmDTO>(),
+        Route::delete("/api/v1/outputs/{output_index}/alarms/{identifier}", Access::Permission(Permission::WriteAlarms), delete_alarm)
+            .path_params::<AlarmIdDTO>()
+            .text_response(),
+    ]
 }
 
-fn get_alarm_output_indices(request: Request<&mut EspHttpConnection>, schedule_system: &Arc<ScheduleSystem>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+fn get_alarm_output_indices<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    request.ok(&schedule_system.alarm_output_indices().clone())
+    context.ok(&schedule_system.alarm_output_indices().clone())
 }
 
-fn get_alarm(request: Request<&mut EspHttpConnection>, schedule_system: &Arc<ScheduleSystem>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+fn get_alarm<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    let alarm_id: AlarmId = request.parameters::<AlarmIdDTO>()?.into();
+    let alarm_id: AlarmId = context.path::<AlarmIdDTO>()?.into();
 
     let alarm: Alarm =
         schedule_system
             .get_alarm(&alarm_id)
-            .map_err(|error| RequestError::General(error.to_string()))?;
+            .map_err(ApiError::from)?;
 
     let alarm_dto: AlarmDTO = alarm.into();
 
-    request.ok(&alarm_dto)
+    context.ok(&alarm_dto)
 }
 
-fn get_alarms_by_output_index(request: Request<&mut EspHttpConnection>, schedule_system: &Arc<ScheduleSystem>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+fn get_alarms_by_output_index<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    let output_index: u8 = *request.parameters::<OutputIndexDTO>()?;
+    let output_index: u8 = *context.path::<OutputIndexDTO>()?;
 
     let alarms_with_id_dto: Vec<AlarmWithIdDTO> =
         schedule_system
             .get_alarms_by_output_index(output_index)
-            .map_err(|error| RequestError::General(error.to_string()))?
+            .map_err(ApiError::from)?
             .to_alarms_with_id();
 
-    request.ok(&alarms_with_id_dto)
+    context.ok(&AlarmListDTO(alarms_with_id_dto))
 }
 
-fn add_alarm(mut request: Request<&mut EspHttpConnection>, schedule_system: &Arc<ScheduleSystem>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+fn add_alarm<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    let output_index: u8 = *request.parameters::<OutputIndexDTO>()?;
+    let output_index: u8 = *context.path::<OutputIndexDTO>()?;
 
-    let alarm: Alarm = request.body::<AlarmDTO>()?.into();
+    let alarm: Alarm = context.json::<AlarmDTO>()?.into();
 
     schedule_system
         .add_alarm(output_index, alarm)
-        .map_err(|error| RequestError::General(error.to_string()))?;
+        .map_err(ApiError::from)?;
 
-    request.ok(&"Alarm added")
+    context.ok(&"Alarm added")
 }
 
-fn delete_alarm(request: Request<&mut EspHttpConnection>, schedule_system: &Arc<ScheduleSystem>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+fn delete_alarm<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    let alarm_id: AlarmId = request.parameters::<AlarmIdDTO>()?.into();
+    let alarm_id: AlarmId = context.path::<AlarmIdDTO>()?.into();
 
     schedule_system
         .remove_alarm(&alarm_id)
-        .map_err(|error| RequestError::General(error.to_string()))?;
+        .map_err(ApiError::from)?;
 
-    request.ok(&"Alarm removed")
+    context.ok(&"Alarm removed")
 }
 
-fn delete_alarms_by_output_index(request: Request<&mut EspHttpConnection>, schedule_system: &Arc<ScheduleSystem>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+fn delete_alarms_by_output_index<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    let output_index: u8 = *request.parameters::<OutputIndexDTO>()?;
+    let output_index: u8 = *context.path::<OutputIndexDTO>()?;
 
     schedule_system
         .remove_alarms_by_output_index(output_index)
-        .map_err(|error| RequestError::General(error.to_string()))?;
+        .map_err(ApiError::from)?;
 
-    request.ok(&"Alarms removed")
+    context.ok(&"Alarms removed")
 }
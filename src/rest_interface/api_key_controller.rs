@@ -0,0 +1,95 @@
+use crate::model::auth::api_key::{ApiKeyDTO, ApiKeyIdDTO, CreatedApiKeyDTO, NewApiKeyDTO};
+use crate::rest_interface::security::{audit_request, authenticated};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditAction, AuditOutcome};
+use crate::schedule_system::ScheduleSystem;
+use crate::security::api_key::ApiKey;
+use crate::security::error::SecurityError;
+use crate::security::session::Session;
+use crate::security::user::Permission;
+use crate::security::SecurityContext;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/api-keys", Access::Permission(Permission::Admin), get_api_keys)
+            .json_response::<Vec<ApiKeyDTO>>(),
+        Route::post("/api/v1/api-keys", Access::Permission(Permission::Admin), create_api_key)
+            .summary("Create API key, its plaintext key is returned only once")
+            .json_body::<NewApiKeyDTO>()
+            .json_response::<CreatedApiKeyDTO>(),
+        Route::delete("/api/v1/api-keys/{id}", Access::Permission(Permission::Admin), delete_api_key)
+            .path_params::<ApiKeyIdDTO>()
+            .text_response(),
+    ]
+}
+
+fn get_api_keys<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let api_keys: Vec<ApiKeyDTO> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .get_api_keys()
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    context.ok(&api_keys)
+}
+
+/**
+ * Key is owned by current user. Response contains plaintext key, which isn't returned anymore.
+ */
+fn create_api_key<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let NewApiKeyDTO { name, scopes, expires_at_millis } = context.json()?;
+
+    let expires_at_seconds: Option<u64> =
+        match expires_at_millis {
+            Some(expires_at_millis) if expires_at_millis < 0 =>
+                return context.error(&ApiError::bad_request("invalid_expiration", "Expiration time can't be negative.")),
+            expires_at_millis => expires_at_millis.map(|expires_at_millis| expires_at_millis as u64 / 1000),
+        };
+
+    let result: Result<(ApiKey, String), SecurityError> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .create_api_key(&session.username, &name, scopes.into_iter().map(Into::into).collect(), expires_at_seconds);
+
+    match result {
+        Ok((api_key, key)) => {
+            let details: String = format!("API key '{}' ({}) with scopes {:?}.", api_key.id, api_key.name, api_key.scopes);
+            audit_request(&mut context, &schedule_system, &session.username, AuditAction::ApiKeyCreated, AuditOutcome::Success, details);
+
+            context.ok(&CreatedApiKeyDTO { key, api_key: api_key.into() })
+        }
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
+
+/**
+ * URI example: /api/v1/api-keys/1a2b3c4d
+ */
+fn delete_api_key<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let ApiKeyIdDTO { id } = context.path()?;
+
+    let result: Result<(), SecurityError> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .delete_api_key(&id);
+
+    match result {
+        Ok(()) => {
+            audit_request(&mut context, &schedule_system, &session.username, AuditAction::ApiKeyRemoved, AuditOutcome::Success, format!("API key '{id}'."));
+            context.ok(&"API key removed.")
+        }
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
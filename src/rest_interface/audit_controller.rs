@@ -0,0 +1,83 @@
+use crate::model::audit::audit::{AuditFilterDTO, AuditPageDTO, AuditVerificationDTO};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditPage, AuditVerification};
+use crate::schedule_system::ScheduleSystem;
+use crate::security::user::Permission;
+use embedded_svc::io::Write;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/audit", Access::Permission(Permission::Admin), get_audit_entries)
+            .query_params::<AuditFilterDTO>()
+            .json_response::<AuditPageDTO>(),
+        Route::get("/api/v1/audit/verification", Access::Permission(Permission::Admin), verify_audit_log)
+            .json_response::<AuditVerificationDTO>(),
+        Route::get("/api/v1/audit/export", Access::Permission(Permission::Admin), export_audit_log)
+            .summary("Export audit log, one JSON entry per line")
+            .media_response("application/x-ndjson"),
+    ]
+}
+
+/**
+ * URI example: /api/v1/audit?actor=admin&action=Login&outcome=Failure&limit=50
+ */
+fn get_audit_entries<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    /* all parameters are optional */
+    let audit_filter: AuditFilterDTO = context.query()?;
+
+    let audit_page: AuditPage = schedule_system
+        .get_audit_entries(&audit_filter.into())
+        .map_err(ApiError::from)?;
+
+    context.ok(&AuditPageDTO::from(audit_page))
+}
+
+fn verify_audit_log<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let audit_verification: AuditVerification = schedule_system
+        .verify_audit_log()
+        .map_err(ApiError::from)?;
+
+    context.ok(&AuditVerificationDTO::from(audit_verification))
+}
+
+/**
+ * All retained entries from oldest to newest, one JSON entry per line. Hashes can be checked offline.
+ */
+fn export_audit_log<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let file_names: Vec<String> = schedule_system
+        .get_audit_log_file_names()
+        .map_err(ApiError::from)?;
+
+    let headers = &[
+        ("Content-Type", "application/x-ndjson"),
+        ("Content-Disposition", "attachment; filename=\"audit.ndjson\""),
+    ];
+
+    let mut response = context
+        .into_request()
+        .into_response(200, Some("OK"), headers)
+        .map_err(RequestError::Connection)?;
+
+    /* files are sent one by one, so whole log is never kept in memory */
+    for file_name in &file_names {
+        let content: Vec<u8> = schedule_system
+            .read_audit_log_file(file_name)
+            .map_err(ApiError::from)?;
+
+        response
+            .write_all(&content)
+            .map_err(RequestError::Connection)?;
+    }
+
+    response.flush().map_err(RequestError::Connection)
+}
@@ -1,84 +1,260 @@
+use crate::model::auth::access_point_credentials::AccessPointCredentials;
+use crate::model::auth::api_credentials::ApiCredentials;
 use crate::model::auth::login_credentials::LoginCredentials;
+use crate::model::auth::password_policy::PasswordPolicyDTO;
+use crate::rest_interface::security::{audit_request, authenticated, get_access_token};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditAction, AuditOutcome};
+use crate::schedule_system::event::EventKind;
+use crate::schedule_system::ScheduleSystem;
 use crate::security::error::SecurityError;
+use crate::security::login_guard::LoginLockout;
+use crate::security::session::Session;
+use crate::security::user::Permission;
 use crate::security::SecurityContext;
-use esp_idf_svc::http::server::{EspHttpConnection, Request};
-use esp_idf_svc::http::Method;
-use esp_idf_svc::io::EspIOError;
-use esp_idf_svc::sys::EspError;
-use http_server::http_request::{IntoResponse, ReadData, RequestError, RequestResult};
-use http_server::http_server::HttpServer;
-use crate::model::auth::access_point_credentials::AccessPointCredentials;
-use crate::model::auth::api_credentials::ApiCredentials;
-use crate::rest_interface::security::authenticate_request;
-
-pub fn serve(http_server: &mut HttpServer) -> Result<(), EspError> {
-    http_server.add_handler(
-        "/api/v1/login", Method::Post,
-        move |request| login(request)
-    )?;
-    http_server.add_handler(
-        "/api/v1/access-token-validity", Method::Post,
-        move |request| check_access_token_validity(request)
-    )?;
-    http_server.add_handler(
-        "/api/v1/user/password", Method::Patch,
-        move |request| change_user_password(request)
-    )?;
-    http_server.add_handler(
-        "/api/v1/access-point/password", Method::Patch,
-        move |request| change_access_point_password(request)
-    )?;
-
-    Ok(())
+use http_server::client_address::ClientAddress;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::post("/api/v1/login", Access::Public, login)
+            .summary("Get access token")
+            .json_body::<LoginCredentials>()
+            .text_response(),
+        Route::post("/api/v1/logout", Access::Authenticated, logout)
+            .text_response(),
+        Route::post("/api/v1/sessions/revoke-all", Access::Permission(Permission::Admin), revoke_all_sessions)
+            .text_response(),
+        Route::post("/api/v1/access-token-validity", Access::Authenticated, check_access_token_validity)
+            .text_response(),
+        Route::patch("/api/v1/user/password", Access::Authenticated, change_user_password)
+            .summary("Change password and get new access token")
+            .json_body::<ApiCredentials>()
+            .text_response(),
+        Route::patch("/api/v1/access-point/password", Access::Permission(Permission::Admin), change_access_point_password)
+            .json_body::<AccessPointCredentials>()
+            .text_response(),
+        Route::get("/api/v1/password-policy", Access::Authenticated, get_password_policy)
+            .json_response::<PasswordPolicyDTO>(),
+        Route::put("/api/v1/password-policy", Access::Permission(Permission::Admin), set_password_policy)
+            .json_body::<PasswordPolicyDTO>()
+            .text_response(),
+    ]
 }
 
-fn login(mut request: Request<&mut EspHttpConnection>) -> RequestResult<(), EspIOError> {
+fn login<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
     let security_context: &SecurityContext = SecurityContext::get().map_err(RequestError::EspError)?;
 
-    let LoginCredentials { password }: LoginCredentials = request.body()?;
+    /* clients with unknown address count only to global lockout */
+    let client_ip: Option<IpAddr> = context.client_ip();
+
+    let LoginCredentials { username, password }: LoginCredentials = context.json()?;
+
+    match security_context.get_access_token(&username, &password, client_ip) {
+        Ok(access_token) => {
+            schedule_system.log_event(EventKind::Login, format!("User '{username}' from {}.", client_name(client_ip)));
+            audit_request(&mut context, &schedule_system, &username, AuditAction::Login, AuditOutcome::Success, String::new());
 
-    match security_context.get_access_token("", &password) {
-        Ok(access_token) => request.ok(&access_token),
+            context.ok(&access_token)
+        }
         Err(error) =>
             match error {
-                SecurityError::EspError(error) => Err(RequestError::EspError(error)),
-                SecurityError::WrongCredentials => request.forbidden(&"Unable to get access token. Wrong password."),
-                SecurityError::ReadLockError => request.internal_server_error(&"Can't lock security context for reading token."),
-                SecurityError::WriteLockError => request.internal_server_error(&"Can't lock security context for writing token."),
+                SecurityError::WrongCredentials => {
+                    schedule_system.log_event(EventKind::LoginFailed, format!("User '{username}' from {}.", client_name(client_ip)));
+
+                    /* failure can start lockout */
+                    let lockout: Option<LoginLockout> = security_context.login_lockout(client_ip).ok().flatten();
+
+                    /* attempts during lockout aren't audited, their rate isn't limited */
+                    let details: String = lockout.map_or(String::new(), |lockout| format!("Lockout started: {lockout:?}."));
+                    audit_request(&mut context, &schedule_system, &username, AuditAction::Login, AuditOutcome::Failure, details);
+
+                    if let Some(lockout) = lockout {
+                        show_login_lockout(&schedule_system, client_ip, lockout);
+                    }
+
+                    context.error(&ApiError::unauthorized("wrong_credentials", "Unable to get access token. Wrong username or password."))
+                }
+                SecurityError::LoginLocked(lockout) => {
+                    schedule_system.log_event(EventKind::LoginBlocked, format!("User '{username}' from {}: {lockout:?}.", client_name(client_ip)));
+                    show_login_lockout(&schedule_system, client_ip, lockout);
+
+                    context.too_many_requests(&ApiError::from(error), lockout.remaining().as_secs() + 1)
+                }
+                error => Err(ApiError::from(error).into()),
             }
     }
 }
 
-fn check_access_token_validity(request: Request<&mut EspHttpConnection>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+/**
+ * Lock state is shown on display, so on-site admin can notice an attack.
+ */
+fn show_login_lockout(schedule_system: &ScheduleSystem, client_ip: Option<IpAddr>, lockout: LoginLockout) {
+    let text: String =
+        match lockout {
+            LoginLockout::Client(_) => format!("LOGIN LOCKED\n{}", client_name(client_ip)),
+            LoginLockout::Global(_) => String::from("LOGIN LOCKED\nAll clients"),
+        };
+
+    schedule_system.show_message(&text, lockout.remaining() + Duration::from_secs(1));
+}
+
+fn client_name(client_ip: Option<IpAddr>) -> String {
+    client_ip.map_or(String::from("unknown address"), |client_ip| client_ip.to_string())
+}
+
+/**
+ * Revoke access token of current request.
+ */
+fn logout<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let security_context: &SecurityContext = SecurityContext::get().map_err(RequestError::EspError)?;
+
+    security_context
+        .revoke_access_token(get_access_token(context.request())?)
+        .map_err(ApiError::from)?;
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::Logout, AuditOutcome::Success, String::new());
+
+    context.ok(&"Logged out.")
+}
+
+/**
+ * Revoke access tokens of all clients including current one.
+ */
+fn revoke_all_sessions<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
-    request.ok(&"Access token is valid.")
+    SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .revoke_all_access_tokens()
+        .map_err(ApiError::from)?;
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::SessionsRevoked, AuditOutcome::Success, String::new());
+
+    context.ok(&"All sessions revoked.")
+}
+
+fn check_access_token_validity<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    context.ok(&"Access token is valid.")
 }
 
-fn change_user_password(mut request: Request<&mut EspHttpConnection>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+/**
+ * Change password of current user. Other sessions of user are revoked, response contains new access token.
+ */
+fn change_user_password<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
     let security_context: &SecurityContext = SecurityContext::get().map_err(RequestError::EspError)?;
 
-    let ApiCredentials { password } = request.body()?;
+    let client_ip: Option<IpAddr> = context.client_ip();
 
-    security_context.set_api_password(password.as_str()).map_err(RequestError::EspError)?;
+    let ApiCredentials { current_password, password } = context.json()?;
 
-    request.ok(&"Api password changed.")
+    match security_context.change_user_password(&session.username, &current_password, &password, client_ip) {
+        Ok(access_token) => {
+            audit_request(&mut context, &schedule_system, &session.username, AuditAction::PasswordChanged, AuditOutcome::Success, String::new());
+            context.ok(&access_token)
+        }
+        Err(error) => {
+            audit_request(&mut context, &schedule_system, &session.username, AuditAction::PasswordChanged, AuditOutcome::Failure, error.to_string());
+            password_change_error_response(context, &schedule_system, client_ip, error)
+        }
+    }
 }
 
-fn change_access_point_password(mut request: Request<&mut EspHttpConnection>) -> RequestResult<(), EspIOError> {
-    authenticate_request(&request)?;
+/**
+ * New password is applied to running access point, connected clients have to reconnect.
+ */
+fn change_access_point_password<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
 
     let security_context: &SecurityContext = SecurityContext::get().map_err(RequestError::EspError)?;
 
-    let AccessPointCredentials { password } = request.body()?;
+    let client_ip: Option<IpAddr> = context.client_ip();
+
+    let AccessPointCredentials { current_password, password } = context.json()?;
+
+    if let Err(error) = security_context.change_access_point_password(&session.username, &current_password, &password, client_ip) {
+        audit_request(&mut context, &schedule_system, &session.username, AuditAction::AccessPointPasswordChanged, AuditOutcome::Failure, error.to_string());
+        return password_change_error_response(context, &schedule_system, client_ip, error);
+    }
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::AccessPointPasswordChanged, AuditOutcome::Success, String::new());
+
+    schedule_system
+        .set_access_point_password(&password)
+        .map_err(ApiError::from)?;
+
+    context.ok(&"Access point password changed.")
+}
+
+fn password_change_error_response<C: ConnectionFamily>(
+    context: ApiContext<C>,
+    schedule_system: &ScheduleSystem,
+    client_ip: Option<IpAddr>,
+    error: SecurityError,
+) -> RequestResult<(), C::Error> {
+    match error {
+        SecurityError::WrongCredentials => {
+            if let Ok(Some(lockout)) = SecurityContext::get().map_err(RequestError::EspError)?.login_lockout(client_ip) {
+                show_login_lockout(schedule_system, client_ip, lockout);
+            }
 
-    if password.len() < 8 {
-        return request.bad_request(&"Password should be minimum 8 characters.");
+            /* 401 would look like expired session to clients */
+            context.error(&ApiError::forbidden("wrong_password", "Current password is wrong."))
+        }
+        SecurityError::LoginLocked(lockout) => context.too_many_requests(&ApiError::from(error), lockout.remaining().as_secs() + 1),
+        error => Err(ApiError::from(error).into()),
     }
+}
+
+fn get_password_policy<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let password_policy: PasswordPolicyDTO = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .get_password_policy()
+        .map_err(ApiError::from)?
+        .into();
+
+    context.ok(&password_policy)
+}
 
-    security_context.set_access_point_password(password.as_str()).map_err(RequestError::EspError)?;
+/**
+ * Policy is checked on next password changes, existing passwords stay valid.
+ */
+fn set_password_policy<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
 
-    request.ok(&"Access point password changed.")
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let password_policy: PasswordPolicyDTO = context.json()?;
+    let details: String = format!("{password_policy:?}");
+
+    let result: Result<(), SecurityError> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .set_password_policy(password_policy.into());
+
+    if result.is_ok() {
+        audit_request(&mut context, &schedule_system, &session.username, AuditAction::PasswordPolicyChanged, AuditOutcome::Success, details);
+    }
+
+    match result {
+        Ok(()) => context.ok(&"Password policy changed."),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
 }
@@ -0,0 +1,53 @@
+use crate::model::emergency::emergency::{EmergencyDTO, EmergencyStatusDTO};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::security::user::Permission;
+use crate::schedule_system::ScheduleSystem;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/emergency", Access::Permission(Permission::Read), get_emergency)
+            .json_response::<EmergencyStatusDTO>(),
+        Route::post("/api/v1/emergency", Access::Permission(Permission::Ring), start_emergency)
+            .json_body::<EmergencyDTO>()
+            .text_response(),
+        Route::delete("/api/v1/emergency", Access::Permission(Permission::Ring), stop_emergency)
+            .text_response(),
+    ]
+}
+
+fn get_emergency<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let emergency: Option<EmergencyDTO> =
+        schedule_system
+            .get_emergency()
+            .map_err(ApiError::from)?
+            .map(Into::into);
+
+    context.ok(&EmergencyStatusDTO { emergency })
+}
+
+fn start_emergency<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let emergency: EmergencyDTO = context.json()?;
+
+    match schedule_system.start_emergency(emergency.into()) {
+        Ok(_) => context.ok(&"Emergency started"),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
+
+fn stop_emergency<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    schedule_system
+        .stop_emergency()
+        .map_err(ApiError::from)?;
+
+    context.ok(&"Emergency stopped")
+}
@@ -0,0 +1,33 @@
+use crate::model::event::event::{EventFilterDTO, EventPageDTO};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::security::user::Permission;
+use crate::schedule_system::event::EventPage;
+use crate::schedule_system::ScheduleSystem;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/events", Access::Permission(Permission::Read), get_events)
+            .query_params::<EventFilterDTO>()
+            .json_response::<EventPageDTO>(),
+    ]
+}
+
+/**
+ * URI example: /api/v1/events?from_timestamp_millis=1700000000000&kind=AlarmFired&offset=50&limit=50
+ */
+fn get_events<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    /* all parameters are optional */
+    let event_filter: EventFilterDTO = context.query()?;
+
+    let event_page: EventPage = schedule_system
+        .get_events(&event_filter.into())
+        .map_err(ApiError::from)?;
+
+    context.ok(&EventPageDTO::from(event_page))
+}
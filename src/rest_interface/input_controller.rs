@@ -0,0 +1,48 @@
+use crate::model::input::input_config::InputConfigDTO;
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::security::user::Permission;
+use crate::schedule_system::input_config::InputConfig;
+use crate::schedule_system::ScheduleSystem;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/inputs", Access::Permission(Permission::Read), get_inputs)
+            .json_response::<Vec<InputConfigDTO>>(),
+        Route::put("/api/v1/inputs", Access::Permission(Permission::Admin), set_inputs)
+            .json_body::<Vec<InputConfigDTO>>()
+            .text_response(),
+    ]
+}
+
+fn get_inputs<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let input_configs_dto: Vec<InputConfigDTO> =
+        schedule_system
+            .get_input_configs()
+            .map_err(ApiError::from)?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+    context.ok(&input_configs_dto)
+}
+
+fn set_inputs<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let input_configs: Vec<InputConfig> = context
+        .json::<Vec<InputConfigDTO>>()?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    match schedule_system.set_input_configs(input_configs) {
+        Ok(_) => context.ok(&"Inputs saved. Restart device to apply changes."),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
@@ -0,0 +1,112 @@
+use crate::constant::{LIVE_EVENTS_AUTH_MESSAGE_MAX_BYTES, LIVE_EVENTS_URI};
+use crate::model::event::live_events::LiveEventsAuthDTO;
+use crate::schedule_system::{LiveEventHub, ScheduleSystem};
+use crate::security::session::Session;
+use crate::security::user::Permission;
+use crate::security::SecurityContext;
+use esp_idf_svc::http::server::ws::{EspHttpWsConnection, EspHttpWsDetachedSender};
+use esp_idf_svc::sys::EspError;
+use esp_idf_svc::ws::FrameType;
+use http_server::api_error::ApiError;
+use http_server::client_address::ClientAddress;
+use http_server::http_server::HttpServer;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/**
+ * Live events are pushed as {"event": "...", "data": {...}} text messages:
+ * "clock" every second, "alarm_fired", "output_fault", "emergency" and "config_changed".
+ * Client sends {"access_token": "..."} as first message. Subscription is closed with error envelope when it is rejected
+ * or when the message doesn't come within LIVE_EVENTS_AUTH_TIMEOUT_MS.
+ * Must be registered before router, because URIs are matched in order of registration.
+ */
+pub fn serve(http_server: &mut HttpServer, schedule_system: Arc<ScheduleSystem>) -> Result<(), EspError> {
+    http_server.add_ws_handler(LIVE_EVENTS_URI, move |connection: &mut EspHttpWsConnection| {
+        let live_events: &LiveEventHub = schedule_system.live_events();
+
+        /* subscription waits for access token, connection is closed by live events thread when it doesn't come */
+        if connection.is_new() {
+            let sender: EspHttpWsDetachedSender = connection.create_detached_sender()?;
+            live_events.connect(connection.session(), sender);
+            return Ok(());
+        }
+
+        if connection.is_closed() {
+            live_events.unsubscribe(connection.session());
+            return Ok(());
+        }
+
+        let message: Result<Vec<u8>, ApiError> = receive_message(connection);
+
+        /* messages of subscribed client are ignored */
+        if live_events.is_subscribed(connection.session()) {
+            return Ok(());
+        }
+
+        if let Err(error) = message.and_then(|message| subscribe(connection, live_events, &message)) {
+            reject(connection, &error)?;
+        }
+
+        Ok(())
+    })
+}
+
+fn receive_message(connection: &mut EspHttpWsConnection) -> Result<Vec<u8>, ApiError> {
+    /* empty buffer returns only length of frame */
+    let (_, length): (FrameType, usize) = connection
+        .recv(&mut [])
+        .map_err(|_| ApiError::bad_request("invalid_message", "Can't receive message."))?;
+
+    if length > LIVE_EVENTS_AUTH_MESSAGE_MAX_BYTES {
+        return Err(ApiError::new(413, "message_too_large", format!("Message can have at most {LIVE_EVENTS_AUTH_MESSAGE_MAX_BYTES} bytes.")));
+    }
+
+    let mut message: Vec<u8> = vec![0; length];
+
+    connection
+        .recv(&mut message)
+        .map_err(|_| ApiError::bad_request("invalid_message", "Can't receive message."))?;
+
+    /* text frames are terminated by NUL */
+    while message.last() == Some(&0) {
+        message.pop();
+    }
+
+    Ok(message)
+}
+
+fn subscribe(connection: &mut EspHttpWsConnection, live_events: &LiveEventHub, message: &[u8]) -> Result<(), ApiError> {
+    let LiveEventsAuthDTO { access_token } = serde_json::from_slice(message)
+        .map_err(|_| ApiError::bad_request("invalid_auth_message", "First message must be {\"access_token\": \"...\"}."))?;
+
+    let security_context: &SecurityContext = SecurityContext::get()
+        .map_err(|_| ApiError::internal_server_error("security_unavailable", "Security context isn't available."))?;
+
+    if security_context.is_setup_required() {
+        return Err(ApiError::forbidden("setup_required", "Setup required. Change default passwords via /api/v1/setup."));
+    }
+
+    let client_ip: Option<IpAddr> = connection.client_ip();
+
+    let session: Session = security_context
+        .authenticate(&access_token, client_ip)
+        .map_err(ApiError::from)?;
+
+    security_context
+        .authorize(&session, Permission::Read)
+        .map_err(ApiError::from)?;
+
+    let sender: EspHttpWsDetachedSender = connection
+        .create_detached_sender()
+        .map_err(|_| ApiError::internal_server_error("subscription_failed", "Can't create sender of connection."))?;
+
+    live_events.subscribe(connection.session(), sender, access_token)
+}
+
+/* error is sent as the same envelope as REST errors, then connection is closed */
+fn reject(connection: &mut EspHttpWsConnection, error: &ApiError) -> Result<(), EspError> {
+    let envelope: String = serde_json::to_string(error).unwrap_or_default();
+
+    connection.send(FrameType::Text(false), envelope.as_bytes())?;
+    connection.send(FrameType::Close, &[])
+}
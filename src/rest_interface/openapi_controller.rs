@@ -0,0 +1,35 @@
+use crate::constant::{API_TITLE, API_VERSION};
+use crate::rest_interface::{ApiContext, ApiRoute, RestApi};
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::openapi::OpenApi;
+use http_server::router::{Access, ConnectionFamily, Route};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/* Routes don't change while firmware runs, so specification is built once. */
+static SPECIFICATION: OnceLock<Value> = OnceLock::new();
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/openapi.json", Access::Public, get_openapi)
+            .summary("OpenAPI specification of this API")
+            .json_response::<Value>(),
+    ]
+}
+
+/**
+ * Fails with 500 listing routes which were added without schema.
+ */
+fn get_openapi<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    if let Some(specification) = SPECIFICATION.get() {
+        return context.ok(specification);
+    }
+
+    let specification: Value = OpenApi::new(API_TITLE, API_VERSION)
+        .with_description("REST API of automatic bell system. Errors are sent as ApiError envelope.")
+        .document::<RestApi, C>()
+        .map_err(|error| ApiError::internal_server_error("undocumented_routes", error.to_string()))?;
+
+    context.ok(SPECIFICATION.get_or_init(|| specification))
+}
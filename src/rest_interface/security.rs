@@ -1,25 +1,66 @@
+use crate::rest_interface::ApiContext;
+use crate::schedule_system::audit::{AuditAction, AuditOutcome, AuditRecord};
+use crate::schedule_system::ScheduleSystem;
 use crate::security::error::SecurityError;
+use crate::security::session::Session;
 use crate::security::SecurityContext;
-use esp_idf_svc::http::server::{EspHttpConnection, Request};
-use esp_idf_svc::io::EspIOError;
+use embedded_svc::http::server::{Connection, Request};
+use http_server::api_error::ApiError;
+use http_server::client_address::ClientAddress;
 use http_server::http_request::{RequestError, RequestResult};
+use http_server::router::{ConnectionFamily, ServerConnection};
+use std::net::IpAddr;
 
-pub fn authenticate_request(request: &Request<&mut EspHttpConnection>) -> RequestResult<(), EspIOError> {
-    let access_token = request.header("Access-Token");
+pub fn authenticate_request<C: ServerConnection>(request: &mut Request<C>) -> RequestResult<Session, C::Error> {
+    let client_ip: Option<IpAddr> = request.client_ip();
+    let access_token: &str = get_access_token(request)?;
 
-    match access_token {
-        Some(access_token) => {
-            let is_valid_access_token: bool = SecurityContext::get()
-                .map_err(RequestError::EspError)?
-                .is_valid_access_token_token(access_token)
-                .map_err(|error: SecurityError| RequestError::Security(error.to_string()))?;
+    SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .authenticate(access_token, client_ip)
+        .map_err(|error: SecurityError| ApiError::from(error).into())
+}
+
+/**
+ * Session authenticated by router. Fails only for public routes when request has no valid access token.
+ */
+pub fn authenticated<C: ConnectionFamily>(context: &ApiContext<C>) -> RequestResult<Session, C::Error> {
+    context
+        .principal()
+        .cloned()
+        .ok_or(ApiError::from(SecurityError::InvalidAccessToken).into())
+}
+
+/**
+ * Access token or API key is taken from "Authorization: Bearer" header or from "Access-Token" header.
+ */
+pub fn get_access_token<C: Connection>(request: &Request<C>) -> RequestResult<&str, C::Error> {
+    let bearer_token: Option<&str> = request
+        .header("Authorization")
+        .and_then(|authorization| authorization.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    bearer_token
+        .or_else(|| request.header("Access-Token"))
+        .ok_or(ApiError::unauthorized("missing_access_token", "Missing Authorization or Access-Token header.").into())
+}
 
-            if !is_valid_access_token {
-                Err(RequestError::Security("Invalid access token!".to_string()))
-            } else {
-                Ok(())
-            }
-        },
-        None => Err(RequestError::Security("Missing Access-Token header.".to_string()))
-    }
+/**
+ * Record security relevant request in audit log. Source IP is taken from request.
+ */
+pub fn audit_request(
+    request: &mut impl ClientAddress,
+    schedule_system: &ScheduleSystem,
+    actor: &str,
+    action: AuditAction,
+    outcome: AuditOutcome,
+    details: String,
+) {
+    schedule_system.audit(AuditRecord {
+        actor: actor.to_string(),
+        action,
+        source_ip: request.client_ip(),
+        outcome,
+        details,
+    });
 }
@@ -0,0 +1,43 @@
+use crate::model::settings::settings::{SettingsDTO, SettingsPatchDTO};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::security::user::Permission;
+use crate::schedule_system::ScheduleSystem;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/settings", Access::Permission(Permission::Read), get_settings)
+            .json_response::<SettingsDTO>(),
+        Route::patch("/api/v1/settings", Access::Permission(Permission::Admin), update_settings)
+            .json_body::<SettingsPatchDTO>()
+            .json_response::<SettingsDTO>(),
+    ]
+}
+
+fn get_settings<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let settings: SettingsDTO = schedule_system
+        .get_settings()
+        .map_err(ApiError::from)?
+        .into();
+
+    context.ok(&settings)
+}
+
+/**
+ * Only provided fields are changed. Response contains all settings after update.
+ */
+fn update_settings<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let settings_patch: SettingsPatchDTO = context.json()?;
+
+    match schedule_system.update_settings(settings_patch.into()) {
+        Ok(settings) => context.ok(&SettingsDTO::from(settings)),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
@@ -0,0 +1,74 @@
+use crate::model::auth::setup::{SetupDTO, SetupStatusDTO};
+use crate::rest_interface::security::{audit_request, authenticated};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditAction, AuditOutcome};
+use crate::schedule_system::error::ScheduleSystemError;
+use crate::schedule_system::setup::Setup;
+use crate::schedule_system::ScheduleSystem;
+use crate::security::session::Session;
+use crate::security::user::Permission;
+use crate::security::SecurityContext;
+use chrono::{DateTime, Utc};
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/setup", Access::Public, get_setup_status)
+            .json_response::<SetupStatusDTO>(),
+        Route::post("/api/v1/setup", Access::Permission(Permission::Admin), complete_setup)
+            .json_body::<SetupDTO>()
+            .json_response::<SetupStatusDTO>(),
+    ]
+}
+
+/**
+ * Public, so web interface knows whether to show setup wizard before login.
+ */
+fn get_setup_status<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let setup_required: bool = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .is_setup_required();
+
+    context.ok(&SetupStatusDTO { setup_required })
+}
+
+/**
+ * Password of logged in admin is changed together with access point password, device name and time.
+ * Sessions of admin are revoked, so client logs in again with new password.
+ */
+fn complete_setup<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let setup_dto: SetupDTO = context.json()?;
+
+    let datetime: DateTime<Utc> =
+        match DateTime::<Utc>::from_timestamp_millis(setup_dto.timestamp_millis) {
+            Some(datetime) => datetime,
+            None => return context.error(&ApiError::bad_request("invalid_timestamp", format!("Can't convert timestamp {} to datetime.", setup_dto.timestamp_millis)))
+        };
+
+    let setup: Setup = Setup {
+        username: session.username.clone(),
+        user_password: setup_dto.password,
+        access_point_password: setup_dto.access_point_password,
+        device_name: setup_dto.device_name,
+        datetime,
+    };
+
+    let result: Result<(), ScheduleSystemError> = schedule_system.complete_setup(setup);
+
+    match &result {
+        Ok(()) => audit_request(&mut context, &schedule_system, &session.username, AuditAction::SetupCompleted, AuditOutcome::Success, String::new()),
+        Err(error) => audit_request(&mut context, &schedule_system, &session.username, AuditAction::SetupCompleted, AuditOutcome::Failure, error.to_string()),
+    }
+
+    match result {
+        Ok(()) => context.ok(&SetupStatusDTO { setup_required: false }),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
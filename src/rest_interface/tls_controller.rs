@@ -0,0 +1,81 @@
+use crate::model::tls::tls_certificate::{TlsCertificateDTO, TlsCertificateInfoDTO};
+use crate::rest_interface::security::{audit_request, authenticated};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditAction, AuditOutcome};
+use crate::schedule_system::ScheduleSystem;
+use crate::security::session::Session;
+use crate::security::user::Permission;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use http_server::tls::TlsCertificate;
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/tls-certificate", Access::Permission(Permission::Admin), get_tls_certificate)
+            .json_response::<TlsCertificateInfoDTO>(),
+        Route::put("/api/v1/tls-certificate", Access::Permission(Permission::Admin), set_tls_certificate)
+            .json_body::<TlsCertificateDTO>()
+            .json_response::<TlsCertificateInfoDTO>(),
+        Route::delete("/api/v1/tls-certificate", Access::Permission(Permission::Admin), reset_tls_certificate)
+            .summary("Replace certificate with new self-signed one")
+            .json_response::<TlsCertificateInfoDTO>(),
+    ]
+}
+
+fn get_tls_certificate<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    match schedule_system.get_tls_certificate() {
+        Ok(Some(tls_certificate)) => context.ok(&TlsCertificateInfoDTO::from(&tls_certificate)),
+        Ok(None) => context.error(&ApiError::not_found("tls_certificate_not_found", "TLS certificate not found.")),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
+
+/**
+ * Certificate is used after restart. Private key should match the first certificate of the chain.
+ */
+fn set_tls_certificate<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let TlsCertificateDTO { certificate_pem, private_key_pem } = context.json()?;
+
+    let tls_certificate: TlsCertificate =
+        match TlsCertificate::new(certificate_pem, private_key_pem) {
+            Ok(tls_certificate) => tls_certificate,
+            Err(error) => {
+                audit_request(&mut context, &schedule_system, &session.username, AuditAction::TlsCertificateChanged, AuditOutcome::Failure, error.to_string());
+                return context.error(&ApiError::from(error));
+            }
+        };
+
+    schedule_system
+        .set_tls_certificate(&tls_certificate)
+        .map_err(ApiError::from)?;
+
+    let details: String = format!("Certificate '{}' issued by '{}'.", tls_certificate.subject(), tls_certificate.issuer());
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::TlsCertificateChanged, AuditOutcome::Success, details);
+
+    context.ok(&TlsCertificateInfoDTO::from(&tls_certificate))
+}
+
+/**
+ * Replace stored certificate with new self-signed one. It is used after restart.
+ */
+fn reset_tls_certificate<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let tls_certificate: TlsCertificate = schedule_system
+        .reset_tls_certificate()
+        .map_err(ApiError::from)?;
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::TlsCertificateChanged, AuditOutcome::Success, "Self-signed certificate generated.".to_string());
+
+    context.ok(&TlsCertificateInfoDTO::from(&tls_certificate))
+}
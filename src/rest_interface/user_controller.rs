@@ -0,0 +1,120 @@
+use crate::model::auth::user::{NewUserDTO, UserDTO, UserUpdateDTO, UsernameDTO};
+use crate::rest_interface::security::{audit_request, authenticated};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditAction, AuditOutcome};
+use crate::schedule_system::ScheduleSystem;
+use crate::security::session::Session;
+use crate::security::error::SecurityError;
+use crate::security::user::Permission;
+use crate::security::SecurityContext;
+use http_server::client_address::ClientAddress;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use std::sync::Arc;
+
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/users", Access::Permission(Permission::Admin), get_users)
+            .json_response::<Vec<UserDTO>>(),
+        Route::post("/api/v1/users", Access::Permission(Permission::Admin), add_user)
+            .json_body::<NewUserDTO>()
+            .text_response(),
+        Route::patch("/api/v1/users/{username}", Access::Permission(Permission::Admin), update_user)
+            .path_params::<UsernameDTO>()
+            .json_body::<UserUpdateDTO>()
+            .text_response(),
+        Route::delete("/api/v1/users/{username}", Access::Permission(Permission::Admin), delete_user)
+            .path_params::<UsernameDTO>()
+            .text_response(),
+    ]
+}
+
+fn get_users<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let users: Vec<UserDTO> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .get_users()
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    context.ok(&users)
+}
+
+fn add_user<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let NewUserDTO { username, password, role } = context.json()?;
+
+    let result: Result<(), SecurityError> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .add_user(&username, &password, role.into());
+
+    audit_user_change(&mut context, &schedule_system, &session, AuditAction::UserAdded, &result, format!("User '{username}' with role {role:?}."));
+
+    user_response(context, result, "User added.")
+}
+
+/**
+ * URI example: /api/v1/users/operator
+ */
+fn update_user<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let UsernameDTO { username } = context.path()?;
+    let UserUpdateDTO { password, role } = context.json()?;
+
+    let result: Result<(), SecurityError> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .update_user(&username, password.as_deref(), role.map(Into::into));
+
+    let details: String = format!("User '{username}', password changed: {}, role: {role:?}.", password.is_some());
+    audit_user_change(&mut context, &schedule_system, &session, AuditAction::UserUpdated, &result, details);
+
+    user_response(context, result, "User updated.")
+}
+
+/**
+ * URI example: /api/v1/users/operator
+ */
+fn delete_user<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let UsernameDTO { username } = context.path()?;
+
+    let result: Result<(), SecurityError> = SecurityContext::get()
+        .map_err(RequestError::EspError)?
+        .delete_user(&username);
+
+    audit_user_change(&mut context, &schedule_system, &session, AuditAction::UserRemoved, &result, format!("User '{username}'."));
+
+    user_response(context, result, "User removed.")
+}
+
+fn audit_user_change(
+    request: &mut impl ClientAddress,
+    schedule_system: &ScheduleSystem,
+    session: &Session,
+    action: AuditAction,
+    result: &Result<(), SecurityError>,
+    details: String,
+) {
+    match result {
+        Ok(()) => audit_request(request, schedule_system, &session.username, action, AuditOutcome::Success, details),
+        Err(error) => audit_request(request, schedule_system, &session.username, action, AuditOutcome::Failure, format!("{details} {error}")),
+    }
+}
+
+fn user_response<C: ConnectionFamily>(context: ApiContext<C>, result: Result<(), SecurityError>, message: &str) -> RequestResult<(), C::Error> {
+    match result {
+        Ok(_) => context.ok(&message),
+        Err(error) => Err(ApiError::from(error).into()),
+    }
+}
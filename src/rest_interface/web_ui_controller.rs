@@ -0,0 +1,221 @@
+use crate::constant::WEB_UI_MAX_UPLOAD_BYTES;
+use crate::model::web_ui::web_ui_file::{WebUiFileDTO, WebUiFilePathDTO};
+use crate::rest_interface::security::{audit_request, authenticated};
+use crate::rest_interface::{ApiContext, ApiRoute};
+use crate::schedule_system::audit::{AuditAction, AuditOutcome};
+use crate::schedule_system::error::ScheduleSystemError;
+use crate::schedule_system::web_ui::WebUiWriter;
+use crate::schedule_system::ScheduleSystem;
+use crate::security::session::Session;
+use crate::security::user::Permission;
+use http_server::api_error::ApiError;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::router::{Access, ConnectionFamily, Route};
+use http_server::tar::{TarEntry, TarError, TarReader};
+use std::sync::Arc;
+
+/**
+ * Web interface is uploaded into staging slot, either as tar archive or file by file, and served after activation.
+ * Active web interface is left intact when upload fails.
+ */
+pub fn routes<C: ConnectionFamily>() -> Vec<ApiRoute<C>> {
+    vec![
+        Route::get("/api/v1/web-ui/files", Access::Permission(Permission::Admin), get_web_ui_files)
+            .json_response::<Vec<WebUiFileDTO>>(),
+        Route::delete("/api/v1/web-ui/files", Access::Permission(Permission::Admin), delete_web_ui_file)
+            .query_params::<WebUiFilePathDTO>()
+            .text_response(),
+        Route::put("/api/v1/web-ui/archive", Access::Permission(Permission::Admin), upload_web_ui_archive)
+            .summary("Replace web interface with content of tar archive")
+            .media_body("application/x-tar")
+            .json_response::<Vec<WebUiFileDTO>>(),
+        Route::get("/api/v1/web-ui/staging/files", Access::Permission(Permission::Admin), get_staging_files)
+            .json_response::<Vec<WebUiFileDTO>>(),
+        Route::put("/api/v1/web-ui/staging/files", Access::Permission(Permission::Admin), upload_staging_file)
+            .summary("Upload file into staging slot")
+            .query_params::<WebUiFilePathDTO>()
+            .media_body("application/octet-stream")
+            .json_response::<WebUiFileDTO>(),
+        Route::delete("/api/v1/web-ui/staging", Access::Permission(Permission::Admin), clear_staging)
+            .summary("Delete files of staging slot")
+            .text_response(),
+        Route::post("/api/v1/web-ui/staging/activate", Access::Permission(Permission::Admin), activate_staging)
+            .summary("Serve web interface from staging slot")
+            .json_response::<Vec<WebUiFileDTO>>(),
+    ]
+}
+
+fn get_web_ui_files<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    list_files(context, false)
+}
+
+fn get_staging_files<C: ConnectionFamily>(context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    list_files(context, true)
+}
+
+/**
+ * File is deleted from active web interface. URI example: /api/v1/web-ui/files?path=gz/app.js
+ */
+fn delete_web_ui_file<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let WebUiFilePathDTO { path } = context.query()?;
+
+    schedule_system
+        .delete_web_ui_file(&path)
+        .map_err(ApiError::from)?;
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Success, format!("File '{path}' deleted."));
+
+    context.ok(&"File deleted.")
+}
+
+/**
+ * Archive is extracted into emptied staging slot, which is activated when whole archive is written.
+ * Archive should have index.htm in its root, precompressed copies go to "gz" subdirectories.
+ */
+fn upload_web_ui_archive<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    schedule_system
+        .clear_web_ui_staging()
+        .map_err(ApiError::from)?;
+
+    let mut writer: WebUiWriter = WebUiWriter::new(&schedule_system);
+    let mut tar_reader: TarReader = TarReader::new();
+    let mut files_count: usize = 0;
+
+    let mut on_entry = |entry: TarEntry| -> Result<(), ScheduleSystemError> {
+        match entry {
+            TarEntry::Directory { path } => writer.make_dir(path),
+            TarEntry::File { path, .. } => {
+                files_count += 1;
+                writer.create_file(path)
+            }
+            TarEntry::Data(data) => writer.write(data),
+            TarEntry::FileEnd => writer.close_file(),
+        }
+    };
+
+    let result: RequestResult<usize, C::Error> = context
+        .stream_body(WEB_UI_MAX_UPLOAD_BYTES, |chunk| {
+            tar_reader
+                .feed(chunk, &mut on_entry)
+                .map_err(|error| archive_error(error).into())
+        })
+        .and_then(|size| {
+            tar_reader
+                .finish()
+                .map_err(|error| archive_error(error).into())
+                .map(|_| size)
+        })
+        .and_then(|size| {
+            schedule_system
+                .activate_web_ui_staging()
+                .map_err(|error| ApiError::from(error).into())
+                .map(|_| size)
+        });
+
+    match result {
+        Ok(size) => {
+            let details: String = format!("Archive with {files_count} files ({size} bytes) activated.");
+            audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Success, details);
+
+            list_files(context, false)
+        }
+        Err(error) => {
+            let details: String = format!("Archive upload failed: {error}");
+            audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Failure, details);
+
+            context.error(&error.to_api_error())
+        }
+    }
+}
+
+/**
+ * Body is stored as file of staging slot. URI example: /api/v1/web-ui/staging/files?path=gz/app.js
+ */
+fn upload_staging_file<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let WebUiFilePathDTO { path } = context.query()?;
+
+    let mut writer: WebUiWriter = WebUiWriter::new(&schedule_system);
+
+    writer
+        .create_file(&path)
+        .map_err(ApiError::from)?;
+
+    let size: usize = context.stream_body(WEB_UI_MAX_UPLOAD_BYTES, |chunk| {
+        writer
+            .write(chunk)
+            .map_err(|error| ApiError::from(error).into())
+    })?;
+
+    writer
+        .close_file()
+        .map_err(ApiError::from)?;
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Success, format!("File '{path}' ({size} bytes) uploaded to staging."));
+
+    context.ok(&WebUiFileDTO { path, size: size as u32 })
+}
+
+fn clear_staging<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    schedule_system
+        .clear_web_ui_staging()
+        .map_err(ApiError::from)?;
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Success, "Staging cleared.".to_string());
+
+    context.ok(&"Staging cleared.")
+}
+
+/**
+ * Staging slot needs index.htm to be activated. Previous web interface is deleted and its slot becomes staging.
+ */
+fn activate_staging<C: ConnectionFamily>(mut context: ApiContext<C>) -> RequestResult<(), C::Error> {
+    let session: Session = authenticated(&context)?;
+
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    if let Err(error) = schedule_system.activate_web_ui_staging() {
+        audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Failure, error.to_string());
+        return context.error(&ApiError::from(error));
+    }
+
+    audit_request(&mut context, &schedule_system, &session.username, AuditAction::WebUiChanged, AuditOutcome::Success, "Staging activated.".to_string());
+
+    list_files(context, false)
+}
+
+fn list_files<C: ConnectionFamily>(context: ApiContext<C>, staging: bool) -> RequestResult<(), C::Error> {
+    let schedule_system: Arc<ScheduleSystem> = context.state();
+
+    let files: Vec<WebUiFileDTO> = schedule_system
+        .list_web_ui_files(staging)
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(WebUiFileDTO::from)
+        .collect();
+
+    context.ok(&files)
+}
+
+/* malformed archive is caused by request, failure of writing it is reported like other disk errors */
+fn archive_error(error: TarError<ScheduleSystemError>) -> ApiError {
+    match error {
+        TarError::Sink(error) => ApiError::from(error),
+        error => ApiError::bad_request("invalid_archive", error.to_string()),
+    }
+}
@@ -1,51 +1,167 @@
 pub mod alarm_id;
+pub mod audit;
 pub mod to_alarms_with_id;
-mod error;
-
-use crate::constant::{ACCESS_POINT_SSID, ALARMS_DIR, ALARM_MATCH_CHECK_INTERVAL_MS, RESET_BUTTON_PRESS_TIME_SECONDS, SYSTEM_DIR, WEB_UI_DIR};
+pub mod ring_pattern;
+pub mod input_config;
+pub mod emergency;
+pub mod display_message;
+pub mod settings;
+pub mod setup;
+pub mod journal;
+pub mod event;
+pub mod error;
+pub mod web_ui;
+
+use crate::constant::{ACCESS_POINT_SSID, ALARMS_DIR, ALARM_MATCH_CHECK_INTERVAL_MS, DISPLAY_REFRESH_INTERVAL_MS, EMERGENCY_FILE, EMERGENCY_SIGNAL_TICK_MS, AUDIT_DIR, AUDIT_LOG_MAX_FILES, AUDIT_LOG_MAX_FILE_BYTES, EVENTS_DIR, EVENT_LOG_MAX_FILES, EVENT_LOG_MAX_FILE_BYTES, EVENT_QUEUE_CAPACITY, FILE_READ_CHUNK_BYTES, FREE_INPUT_GPIOS, INPUTS_FILE, NO_PULL_INPUT_GPIOS, LIVE_EVENTS_AUTH_TIMEOUT_MS, LIVE_EVENTS_BUFFER_CAPACITY, LIVE_EVENTS_CLOCK_TICK_MS, LIVE_EVENTS_MAX_SUBSCRIBERS, LIVE_EVENTS_REAUTHENTICATION_INTERVAL_MS, PROFILE_FILE, SETTINGS_FILE, SETTINGS_SCHEMA_VERSION, SCHEDULE_PROFILES_COUNT, SYSTEM_DIR, TLS_CERTIFICATE_FILE, TLS_DIR, TLS_PRIVATE_KEY_FILE, WEB_UI_ACTIVE_SLOT_FILE, WEB_UI_DIR, WEB_UI_INDEX_FILE, WEB_UI_SLOT_DIRS};
+#[cfg(target_os = "espidf")]
+use crate::constant::RESET_BUTTON_DEBOUNCE_MS;
 use crate::model::alarm::alarm_with_id::AlarmWithIdDTO;
+use crate::model::audit::audit::AuditEntryDTO;
+use crate::model::clock::clock::ClockDTO;
+use crate::model::emergency::emergency::{EmergencyDTO, EmergencyStatusDTO};
+use crate::model::event::event::EventDTO;
+use crate::model::input::input_config::InputConfigDTO;
+use crate::model::settings::settings::SettingsDTO;
 use crate::schedule_system::alarm_id::AlarmId;
+use crate::schedule_system::audit::{AuditChain, AuditChainHead, AuditEntry, AuditFilter, AuditPage, AuditRecord, AuditVerification};
+use crate::schedule_system::display_message::DisplayMessage;
+use crate::schedule_system::emergency::{Emergency, EmergencySignal};
 use crate::schedule_system::error::ScheduleSystemError;
+use crate::schedule_system::event::{Event, EventFilter, EventKind, EventPage};
+use crate::schedule_system::input_config::{InputAction, InputConfig};
+use crate::schedule_system::journal::Journal;
+use crate::schedule_system::ring_pattern::RingPattern;
+use crate::schedule_system::settings::{Settings, SettingsPatch};
+use crate::schedule_system::setup::Setup;
+use crate::schedule_system::web_ui::{parse_web_ui_path, WebUiFile};
+use crate::security::user::Permission;
 use crate::security::SecurityContext;
+#[cfg(not(target_os = "espidf"))]
+use crate::synchronizer::MockOutputPin;
 use crate::synchronizer::{BoxedMutex, BoxedRwLock, IntoBoxedMutex, IntoBoxedRwLock, IntoMutexOutputPin, MutexOutputPin};
 use access_point::access_point::AccessPoint;
 use chrono::{DateTime, Utc};
 use clock::alarm::Alarm;
-use clock::clock::Clock;
+use clock::clock::{Clock, Rtc};
+#[cfg(not(target_os = "espidf"))]
+use clock::mock_rtc::MockRtc;
 use disk::disk::Disk;
 use display::display::Display;
-use esp_idf_svc::hal::gpio::{AnyOutputPin, PinDriver, Pull};
+use embedded_sdmmc::DirEntry;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::hal::gpio::AnyOutputPin;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::i2c::{I2cConfig, I2cDriver};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::peripherals::Peripherals;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::spi::config::DriverConfig;
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::spi::SpiDriver;
+#[cfg(target_os = "espidf")]
+use esp_idf_svc::http::server::ws::EspHttpWsDetachedSender;
+use http_server::cors::{set_cors_policy, CorsPolicy};
+use http_server::event_hub::EventHub;
+#[cfg(not(target_os = "espidf"))]
+use http_server::mock_connection::MockSender;
+use http_server::request_body::set_max_body_size;
+use http_server::tls::TlsCertificate;
+#[cfg(target_os = "espidf")]
+use input::input::Input;
 use interface::clock::{ReadClock, WriteClock};
 use interface::disk::path::directory_path::DirectoryPath;
 use interface::disk::path::file_path::FilePath;
 use interface::disk::{ReadDisk, WriteDisk};
+use interface::gpio::{Level, Pull};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
+#[cfg(target_os = "espidf")]
 use shared_bus::BusManagerStd;
-use std::collections::HashMap;
-use std::process::exit;
+use std::collections::{HashMap, HashSet};
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
 use std::thread;
-use std::time::Duration;
-use esp_idf_svc::systime::EspSystemTime;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use clock::system_time;
 
 type ScheduleSystemResult<Ok> = Result<Ok, ScheduleSystemError>;
 type AlarmOutputs<'a> = Vec<MutexOutputPin<'a>>;
 
+static EVENT_JOURNAL: Journal = Journal::new(EVENTS_DIR, EVENT_LOG_MAX_FILE_BYTES, EVENT_LOG_MAX_FILES);
+static AUDIT_JOURNAL: Journal = Journal::new(AUDIT_DIR, AUDIT_LOG_MAX_FILE_BYTES, AUDIT_LOG_MAX_FILES);
+
+fn alarms_dir_name(profile: u8) -> String {
+    match profile {
+        0 => ALARMS_DIR.to_string(),
+        profile => format!("{ALARMS_DIR}{profile}"),
+    }
+}
+
+
+/**
+ * Subscribers of live events. Principal is access token, which is checked again periodically.
+ */
+#[cfg(target_os = "espidf")]
+pub type LiveEventHub = EventHub<EspHttpWsDetachedSender, String>;
+#[cfg(not(target_os = "espidf"))]
+pub type LiveEventHub = EventHub<MockSender, String>;
+
+/**
+ * Devices used by schedule system: peripherals of ESP32 on device, in-memory ones on host.
+ */
+struct Devices {
+    access_point: AccessPoint<'static>,
+    rtc: Rtc<'static>,
+    disk: Disk<'static>,
+    display: Display<'static>,
+    alarm_output_pins: Vec<MutexOutputPin<'static>>,
+    #[cfg(target_os = "espidf")]
+    reset_button: Input<'static>,
+}
 
 /* Wrap fields into box to prevent stack overflowing.*/
 pub struct ScheduleSystem {
     access_point: BoxedMutex<AccessPoint<'static>>,
-    /* Clock is RwLock, because it requires immutable reference for reading time. */
+    /* Clock is RwLock, because it requires immutable reference for reading time.
+       Clock is always locked before disk, so alarms and their files can be changed together. */
     clock: BoxedRwLock<Clock<AlarmId>>,
     disk: BoxedMutex<Disk<'static>>,
+    /* Output pins are shared with clock alarm callback. */
+    alarm_outputs: Arc<AlarmOutputs<'static>>,
     alarm_output_indices: Vec<usize>,
+    /* Alarms are skipped while silent mode is enabled. */
+    silent_mode: Arc<AtomicBool>,
+    /* Changed only while clock is locked for writing, so alarms are never written to profile being switched away from. */
+    active_profile: AtomicU8,
+    /* Regular alarms are suppressed while emergency is active. */
+    emergency: Arc<RwLock<Option<Emergency>>>,
+    /* Incremented on every emergency change to stop signal thread of previous emergency. */
+    emergency_generation: Arc<AtomicU32>,
+    /* Signal thread is joined before next one starts, so stopped thread can't change outputs of the new one. */
+    emergency_signal_thread: Mutex<Option<JoinHandle<()>>>,
+    display_message: Arc<RwLock<Option<DisplayMessage>>>,
+    /* Shared with display thread, so refresh interval can be changed at runtime. */
+    display_refresh_interval_ms: Arc<AtomicU64>,
+    settings: RwLock<Settings>,
+    /* Reset button is taken by input system for polling. */
+    #[cfg(target_os = "espidf")]
+    reset_button: Mutex<Option<Input<'static>>>,
+    /* Events are written to disk by separate thread, because alarm callback has no access to disk. */
+    event_sender: SyncSender<Event>,
+    event_receiver: Mutex<Option<Receiver<Event>>>,
+    /* Events are pushed to WebSocket clients by event log writer and live events thread. */
+    live_events: Arc<LiveEventHub>,
+    /* Audit entries are written synchronously, so chain is continued in order and no entry is dropped. */
+    audit_chain: Mutex<AuditChain>,
+    /* Slot directory of uploaded web interface, None until the first upload is activated. */
+    web_ui_slot: RwLock<Option<&'static str>>,
 }
 
 impl ScheduleSystem {
+    #[cfg(target_os = "espidf")]
     pub fn new(peripherals: Peripherals) -> Result<Self, ScheduleSystemError> {
         /* Init I2c bus */
         let i2c = peripherals.i2c0;
@@ -73,62 +189,13 @@ impl ScheduleSystem {
         /* Init SPI driver */
 
         /* Init reset button */
-        let mut reset_button = PinDriver::input(peripherals.pins.gpio13)
-            .map_err(ScheduleSystemError::EspError)?;
-        reset_button.set_pull(Pull::Up)
+        let reset_button: Input = Input::new(peripherals.pins.gpio13.into(), Pull::Up, Level::Low, RESET_BUTTON_DEBOUNCE_MS)
             .map_err(ScheduleSystemError::EspError)?;
-
-        thread::spawn(move || {
-            return;
-            let mut press_time: Option<u64> = None;
-
-            loop {
-                thread::sleep(Duration::from_secs(1));
-
-                /* fix time when button pressed */
-                if press_time.is_none() && reset_button.is_low() {
-                    press_time = Some(EspSystemTime.now().as_secs())
-                }
-
-                /* drop press time when button released */
-                if reset_button.is_high() {
-                    press_time = None;
-                }
-
-                let Some(press_time_secs) = press_time else {
-                    continue;
-                };
-
-                let seconds_passed: u64 = EspSystemTime.now().as_secs() - press_time_secs;
-                if seconds_passed != RESET_BUTTON_PRESS_TIME_SECONDS {
-                    continue;
-                }
-
-                press_time = None;
-
-                log::info!("Resetting...");
-                let Ok(security_context) = SecurityContext::get() else {
-                    return;
-                };
-                log::info!("Got security context.");
-
-                let _ = security_context.reset_access_point_password();
-                let _ = security_context.reset_api_password();
-
-                log::info!("Reset.");
-                log::info!("Rebooting...");
-                exit(0);
-            }
-        });
         /* Init reset button */
 
         /* display */
         let display: Display = Display::new(i2c_bus_manager.acquire_i2c())
             .map_err(ScheduleSystemError::DisplayError)?;
-        let mut display: Box<Display> = Box::new(display);
-
-        let _ = display.write_text("Booting...");
-        log::info!("Display initialized.");
 
         let alarm_output_pins: Vec<MutexOutputPin> = vec![
             Into::<AnyOutputPin>::into(peripherals.pins.gpio14)
@@ -138,159 +205,1772 @@ impl ScheduleSystem {
                 .try_into_mutex_output_pin()
                 .map_err(ScheduleSystemError::EspError)?,
         ];
-        let output_pins_count: usize = alarm_output_pins.len();
+
+        /* access point */
+        let access_point_password: String = ScheduleSystem::access_point_password()?;
+        let access_point: AccessPoint = AccessPoint::new(peripherals.modem, ACCESS_POINT_SSID, access_point_password.as_str())
+            .map_err(ScheduleSystemError::EspError)?;
+        log::info!("Access point initialized.");
+
+        /* disk */
+        let disk: Disk = Disk::new(spi_driver, cs)
+            .map_err(ScheduleSystemError::EspError)?;
+        log::info!("Disk initialized.");
+
+        ScheduleSystem::with_devices(Devices {
+            access_point,
+            rtc: i2c_bus_manager.acquire_i2c(),
+            disk,
+            display,
+            alarm_output_pins,
+            reset_button,
+        })
+    }
+
+    /**
+     * Schedule system of host build. Devices are kept in memory, so it starts with blank disk, RTC with time of host
+     * and two outputs, like the device has.
+     */
+    #[cfg(not(target_os = "espidf"))]
+    pub fn new_mock() -> Result<Self, ScheduleSystemError> {
+        let access_point_password: String = ScheduleSystem::access_point_password()?;
+        let access_point: AccessPoint = AccessPoint::new(ACCESS_POINT_SSID, access_point_password.as_str())
+            .map_err(ScheduleSystemError::EspError)?;
+
+        let alarm_output_pins: Vec<MutexOutputPin> = vec![
+            MockOutputPin::new()
+                .try_into_mutex_output_pin()
+                .map_err(ScheduleSystemError::EspError)?,
+            MockOutputPin::new()
+                .try_into_mutex_output_pin()
+                .map_err(ScheduleSystemError::EspError)?,
+        ];
+
+        ScheduleSystem::with_devices(Devices {
+            access_point,
+            rtc: MockRtc::default(),
+            disk: Disk::new_in_memory(),
+            display: Display::new().map_err(ScheduleSystemError::DisplayError)?,
+            alarm_output_pins,
+        })
+    }
+
+    fn access_point_password() -> Result<String, ScheduleSystemError> {
+        SecurityContext::get()
+            .map_err(ScheduleSystemError::EspError)?
+            .get_access_point_password()
+            .map_err(ScheduleSystemError::EspError)
+    }
+
+    fn with_devices(devices: Devices) -> Result<Self, ScheduleSystemError> {
+        let mut display: Box<Display> = Box::new(devices.display);
+
+        let _ = display.write_text("Booting...");
+        log::info!("Display initialized.");
+
+        let output_pins_count: usize = devices.alarm_output_pins.len();
         let alarm_output_indices: Vec<usize> = (0..output_pins_count).collect();
 
+        let alarm_outputs: Arc<AlarmOutputs> = Arc::new(devices.alarm_output_pins);
+        let silent_mode: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let emergency: Arc<RwLock<Option<Emergency>>> = Arc::new(RwLock::new(None));
+        let (event_sender, event_receiver): (SyncSender<Event>, Receiver<Event>) = mpsc::sync_channel(EVENT_QUEUE_CAPACITY);
+
         log::info!("Alarm outputs initialized. Total count is {output_pins_count}.");
 
         /* clock */
+        let alarm_outputs_clone: Arc<AlarmOutputs> = Arc::clone(&alarm_outputs);
+        let silent_mode_clone: Arc<AtomicBool> = Arc::clone(&silent_mode);
+        let emergency_clone: Arc<RwLock<Option<Emergency>>> = Arc::clone(&emergency);
+        let event_sender_clone: SyncSender<Event> = event_sender.clone();
         let clock: BoxedRwLock<Clock<AlarmId>> = Clock::new(
-            i2c_bus_manager.acquire_i2c(),
+            devices.rtc,
             |_| log::info!("Synchronizing..."),
-            move |alarm_id: &AlarmId, alarm: &Alarm, date_time| ScheduleSystem::on_alarm(alarm_id, alarm, date_time, &alarm_output_pins),
+            move |alarm_id: &AlarmId, alarm: &Alarm, date_time| ScheduleSystem::on_alarm(alarm_id, alarm, date_time, &alarm_outputs_clone, &silent_mode_clone, &emergency_clone, &event_sender_clone),
             ALARM_MATCH_CHECK_INTERVAL_MS
         )
         .map_err(ScheduleSystemError::ClockError)?
         .into_boxed_rwlock();
         log::info!("Clock initialized.");
 
-        /* access point */
-        let security_context: &SecurityContext = SecurityContext::get()
-            .map_err(ScheduleSystemError::EspError)?;
-        let access_point_password: String = security_context
-            .get_access_point_password()
-            .map_err(ScheduleSystemError::EspError)?;
-        log::info!("Access point password - '{access_point_password}'.");
-
-        let access_point: BoxedMutex<AccessPoint> = AccessPoint::new(peripherals.modem, ACCESS_POINT_SSID, access_point_password.as_str())
-            .map_err(ScheduleSystemError::EspError)?
-            .into_boxed_mutex();
-        log::info!("Access point initialized.");
-
-        /* disk */
-        let disk: BoxedMutex<Disk> = Disk::new(spi_driver, cs)
-            .map_err(ScheduleSystemError::EspError)?
-            .into_boxed_mutex();
-        log::info!("Disk initialized.");
+        let access_point: BoxedMutex<AccessPoint> = devices.access_point.into_boxed_mutex();
+        let disk: BoxedMutex<Disk> = devices.disk.into_boxed_mutex();
 
         let this: Self = Self {
             access_point,
             clock,
             disk,
-            alarm_output_indices
+            alarm_outputs,
+            alarm_output_indices,
+            silent_mode,
+            active_profile: AtomicU8::new(0),
+            emergency,
+            emergency_generation: Arc::new(AtomicU32::new(0)),
+            emergency_signal_thread: Mutex::new(None),
+            display_message: Arc::new(RwLock::new(None)),
+            display_refresh_interval_ms: Arc::new(AtomicU64::new(DISPLAY_REFRESH_INTERVAL_MS)),
+            settings: RwLock::new(Settings::default()),
+            #[cfg(target_os = "espidf")]
+            reset_button: Mutex::new(Some(devices.reset_button)),
+            event_sender,
+            event_receiver: Mutex::new(Some(event_receiver)),
+            live_events: Arc::new(EventHub::new(LIVE_EVENTS_MAX_SUBSCRIBERS, LIVE_EVENTS_BUFFER_CAPACITY)),
+            audit_chain: Mutex::new(AuditChain::default()),
+            web_ui_slot: RwLock::new(None),
+        };
+
+        this.init_filesystem(output_pins_count)?;
+        log::info!("File system initialized.");
+
+        this.restore_audit_chain_from_disk()?;
+        log::info!("Audit log is ready.");
+
+        this.read_settings_from_disk()?;
+        log::info!("Settings are loaded.");
+
+        this.read_active_profile_from_disk()?;
+        log::info!("Active schedule profile is {}.", this.active_profile());
+
+        {
+            let mut clock = this
+                .clock
+                .write()
+                .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+            this.synchronize_alarms_from_disk(&mut clock)?;
+        }
+        log::info!("Alarms are synchronized from disk.");
+
+        this.restore_emergency_from_disk()?;
+
+        this.read_web_ui_slot_from_disk()?;
+
+        let display_message_clone: Arc<RwLock<Option<DisplayMessage>>> = Arc::clone(&this.display_message);
+        let emergency_clone: Arc<RwLock<Option<Emergency>>> = Arc::clone(&this.emergency);
+        let display_refresh_interval_ms_clone: Arc<AtomicU64> = Arc::clone(&this.display_refresh_interval_ms);
+        thread::spawn(move || {
+            let mut displayed_text: String = String::new();
+
+            loop {
+                let text: String = ScheduleSystem::display_text(&display_message_clone, &emergency_clone);
+
+                /* redraw only when text changed */
+                if text != displayed_text {
+                    let _ = display.write_text(text.as_str());
+                    displayed_text = text;
+                }
+
+                thread::sleep(Duration::from_millis(display_refresh_interval_ms_clone.load(Ordering::SeqCst)));
+            }
+        });
+
+        Ok(this)
+    }
+
+    /**
+     * Message has the highest priority, then emergency banner, then current time.
+     */
+    fn display_text(display_message: &RwLock<Option<DisplayMessage>>, emergency: &RwLock<Option<Emergency>>) -> String {
+        if let Ok(display_message) = display_message.read() {
+            if let Some(display_message) = display_message.as_ref() {
+                if display_message.until > Instant::now() {
+                    return display_message.text.clone();
+                }
+            }
+        }
+
+        let emergency_banner: Option<&str> = emergency
+            .read()
+            .ok()
+            .and_then(|emergency| emergency.as_ref().map(|emergency| emergency.kind.banner()));
+
+        if let Some(emergency_banner) = emergency_banner {
+            return format!("EMERGENCY\n{emergency_banner}");
+        }
+
+        let seconds: u64 = system_time::now().as_secs();
+
+        DateTime::from_timestamp(seconds as i64, 0)
+            .map(|datetime| datetime
+                .naive_utc()
+                .format("%d/%m/%Y\n%H:%M:%S")
+                .to_string()
+            )
+            .unwrap_or_default()
+    }
+
+
+    fn on_alarm(alarm_id: &AlarmId,
+                alarm: &Alarm,
+                _date_time: &DateTime<Utc>,
+                alarm_output_pins: &AlarmOutputs,
+                silent_mode: &AtomicBool,
+                emergency: &RwLock<Option<Emergency>>,
+                event_sender: &SyncSender<Event>) {
+        let output_index: usize = alarm_id.output_index as usize;
+        let identifier: &str = alarm_id.identifier.as_str();
+
+        if silent_mode.load(Ordering::SeqCst) {
+            ScheduleSystem::send_event(event_sender, EventKind::AlarmSkipped, format!("Output {output_index}, alarm {identifier}: silent mode."));
+            return;
+        }
+
+        /* emergency has priority over regular alarms */
+        if emergency.read().map_or(true, |emergency| emergency.is_some()) {
+            ScheduleSystem::send_event(event_sender, EventKind::AlarmSkipped, format!("Output {output_index}, alarm {identifier}: emergency is active."));
+            return;
+        }
+
+        let Some(output_pin) = alarm_output_pins.get(output_index) else {
+            log::warn!("Alarm output index {output_index} out of bounds. Skipping alarm...");
+            ScheduleSystem::send_event(event_sender, EventKind::RingFailed, format!("Output {output_index}, alarm {identifier}: output doesn't exist."));
+            return;
+        };
+
+        let Ok(mut output_pin_driver) = output_pin.try_lock() else {
+            log::warn!("Can't lock output GPIO pin {output_index}. Skipping alarm...");
+            ScheduleSystem::send_event(event_sender, EventKind::RingFailed, format!("Output {output_index}, alarm {identifier}: output is busy."));
+            return;
         };
 
-        this.init_filesystem(output_pins_count)?;
-        log::info!("File system initialized.");
-        
-        this.synchronize_alarms_from_disk()?;
-        log::info!("Alarms are synchronized from disk.");
+        ScheduleSystem::send_event(event_sender, EventKind::AlarmFired, format!("Output {output_index}, alarm {identifier}."));
+
+        // log::info!(
+        //     "Alarming: Output - {}, Id - {}, time - {}, impulse length - {}ms.",
+        //     alarm_id.output_index, alarm_id.identifier, date_time, alarm.impulse_length_millis
+        // );
+
+        let _ = output_pin_driver.set_high();
+        thread::sleep(Duration::from_millis(alarm.impulse_length_millis));
+
+        // log::info!(
+        //     "Stoping alarm: Output - {}, Id - {}, time - {}.",
+        //     alarm_id.output_index, alarm_id.identifier, date_time
+        // );
+        let _ = output_pin_driver.set_low();
+    }
+}
+
+impl ScheduleSystem {
+    pub fn alarm_output_indices(&self) -> &Vec<usize> {
+        &self.alarm_output_indices
+    }
+
+    #[cfg(target_os = "espidf")]
+    pub fn take_reset_button(&self) -> Option<Input<'static>> {
+        self.reset_button
+            .lock()
+            .ok()
+            .and_then(|mut reset_button| reset_button.take())
+    }
+
+    /**
+     * Remove alarms of all profiles, inputs configuration and emergency state.
+     * Users, API keys and password policy are reset by security context.
+     */
+    pub fn factory_reset(&self) -> ScheduleSystemResult<()> {
+        self.stop_emergency()?;
+        self.set_silent_mode(false);
+
+        self.clock
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .clear_all_alarms()
+            .map_err(ScheduleSystemError::ClockError)?;
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        for profile in 0..SCHEDULE_PROFILES_COUNT {
+            let alarms_dir: String = alarms_dir_name(profile);
+
+            for output_index in &self.alarm_output_indices {
+                let path: DirectoryPath = [
+                    SYSTEM_DIR,
+                    alarms_dir.as_str(),
+                    output_index.to_string().as_str()
+                ].as_slice().into();
+
+                disk.clear_dir(&path)
+                    .map_err(ScheduleSystemError::DiskError)?;
+            }
+        }
+
+        for file_name in [INPUTS_FILE, PROFILE_FILE, EMERGENCY_FILE, SETTINGS_FILE] {
+            let path: FilePath = ([SYSTEM_DIR].as_slice(), file_name).into();
+
+            match disk.delete_file(&path) {
+                Ok(_) | Err(embedded_sdmmc::Error::NotFound) => {}
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            }
+        }
+
+        /* new self-signed certificate is generated on next boot */
+        for file_name in [TLS_CERTIFICATE_FILE, TLS_PRIVATE_KEY_FILE] {
+            let path: FilePath = ([SYSTEM_DIR, TLS_DIR].as_slice(), file_name).into();
+
+            match disk.delete_file(&path) {
+                Ok(_) | Err(embedded_sdmmc::Error::NotFound) => {}
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            }
+        }
+
+        self.active_profile.store(0, Ordering::SeqCst);
+
+        /* release disk, because applying settings doesn't need it */
+        drop(disk);
+
+        self.apply_settings(Settings::default())
+    }
+}
+
+/* display */
+impl ScheduleSystem {
+    /**
+     * Show text on display instead of time for provided duration.
+     */
+    pub fn show_message(&self, text: &str, duration: Duration) {
+        if let Ok(mut display_message) = self.display_message.write() {
+            *display_message = Some(DisplayMessage {
+                text: text.to_string(),
+                until: Instant::now() + duration,
+            });
+        }
+    }
+
+    pub fn hide_message(&self) {
+        if let Ok(mut display_message) = self.display_message.write() {
+            *display_message = None;
+        }
+    }
+}
+
+/* outputs */
+impl ScheduleSystem {
+    /**
+     * Ring output by pattern. Blocks current thread until pattern is finished.
+     * Output can't be rung while emergency is active, pattern is cut short when emergency starts.
+     */
+    pub fn ring_output(&self, output_index: u8, pattern: &RingPattern) -> ScheduleSystemResult<()> {
+        match self.ring_output_pattern(output_index, pattern) {
+            Ok(_) => {
+                self.log_event(EventKind::ManualRing, format!("Output {output_index}, {} impulses.", pattern.repeat));
+                Ok(())
+            }
+            Err(error) => {
+                self.log_event(EventKind::RingFailed, format!("Output {output_index}: {error}"));
+                Err(error)
+            }
+        }
+    }
+
+    fn ring_output_pattern(&self, output_index: u8, pattern: &RingPattern) -> ScheduleSystemResult<()> {
+        let output_pin: &MutexOutputPin = self.alarm_outputs
+            .get(output_index as usize)
+            .ok_or(ScheduleSystemError::OutputIndexOutOfBounds(output_index))?;
+
+        let mut output_pin_driver = output_pin
+            .try_lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        for impulse in 0..pattern.repeat {
+            if impulse > 0 {
+                thread::sleep(Duration::from_millis(pattern.pause_length_millis));
+            }
+
+            /* emergency has priority over manual ringing */
+            if self.is_emergency_active() {
+                return Err(ScheduleSystemError::EmergencyActive);
+            }
+
+            output_pin_driver.set_high().map_err(ScheduleSystemError::EspError)?;
+            thread::sleep(Duration::from_millis(pattern.impulse_length_millis));
+            output_pin_driver.set_low().map_err(ScheduleSystemError::EspError)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_silent_mode(&self) -> bool {
+        self.silent_mode.load(Ordering::SeqCst)
+    }
+
+    pub fn set_silent_mode(&self, enabled: bool) {
+        if self.silent_mode.swap(enabled, Ordering::SeqCst) != enabled {
+            self.log_event(EventKind::SilentModeChanged, format!("Silent mode enabled: {enabled}."));
+        }
+    }
+
+    /**
+     * Returns new state of silent mode.
+     */
+    pub fn toggle_silent_mode(&self) -> bool {
+        let enabled: bool = !self.silent_mode.fetch_xor(true, Ordering::SeqCst);
+        self.log_event(EventKind::SilentModeChanged, format!("Silent mode enabled: {enabled}."));
+
+        enabled
+    }
+}
+
+/* schedule profiles */
+impl ScheduleSystem {
+    pub fn active_profile(&self) -> u8 {
+        self.active_profile.load(Ordering::SeqCst)
+    }
+
+    /**
+     * Replace alarms of clock with alarms of provided profile.
+     */
+    pub fn switch_profile(&self, profile: u8) -> ScheduleSystemResult<()> {
+        if profile >= SCHEDULE_PROFILES_COUNT {
+            return Err(ScheduleSystemError::ProfileOutOfBounds(profile));
+        }
+
+        let mut clock = self
+            .clock
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        clock
+            .clear_all_alarms()
+            .map_err(ScheduleSystemError::ClockError)?;
+
+        self.active_profile.store(profile, Ordering::SeqCst);
+        self.write_active_profile_to_disk(profile)?;
+
+        self.synchronize_alarms_from_disk(&mut clock)?;
+        drop(clock);
+
+        self.log_event(EventKind::ProfileSwitched, format!("Profile {profile}."));
+
+        Ok(())
+    }
+}
+
+/* emergency */
+impl ScheduleSystem {
+    pub fn get_emergency(&self) -> ScheduleSystemResult<Option<Emergency>> {
+        let emergency: Option<Emergency> = self.emergency
+            .read()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .clone();
+
+        Ok(emergency)
+    }
+
+    /**
+     * Start emergency signal on selected outputs. Regular alarms are suppressed until emergency is stopped.
+     * Active emergency can be replaced only by emergency with the same or higher priority.
+     */
+    pub fn start_emergency(&self, emergency: Emergency) -> ScheduleSystemResult<()> {
+        self.validate_emergency(&emergency)?;
+
+        let mut active_emergency = self.emergency
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        if let Some(active_emergency) = active_emergency.as_ref() {
+            if emergency.kind.priority() < active_emergency.kind.priority() {
+                return Err(ScheduleSystemError::EmergencyPriorityTooLow);
+            }
+        }
+
+        self.write_emergency_to_disk(Some(&emergency))?;
+        *active_emergency = Some(emergency.clone());
+
+        log::warn!("Emergency {:?} started on outputs {:?}.", emergency.kind, emergency.output_indices);
+        self.log_event(EventKind::EmergencyStarted, format!("{:?} on outputs {:?}.", emergency.kind, emergency.output_indices));
+
+        self.start_emergency_signal(emergency);
+
+        Ok(())
+    }
+
+    pub fn stop_emergency(&self) -> ScheduleSystemResult<()> {
+        let mut active_emergency = self.emergency
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let Some(emergency) = active_emergency.take() else {
+            return Ok(());
+        };
+
+        self.stop_emergency_signal();
+
+        self.write_emergency_to_disk(None)?;
+
+        log::warn!("Emergency {:?} stopped.", emergency.kind);
+        self.log_event(EventKind::EmergencyStopped, format!("{:?}.", emergency.kind));
+
+        Ok(())
+    }
+
+    fn validate_emergency(&self, emergency: &Emergency) -> ScheduleSystemResult<()> {
+        if emergency.output_indices.is_empty() {
+            return Err(ScheduleSystemError::InvalidEmergency("No outputs selected.".to_string()));
+        }
+
+        for output_index in &emergency.output_indices {
+            if !self.alarm_output_indices.contains(&(*output_index as usize)) {
+                return Err(ScheduleSystemError::OutputIndexOutOfBounds(*output_index));
+            }
+        }
+
+        if let EmergencySignal::Intermittent { on_millis: 0, .. } = emergency.signal {
+            return Err(ScheduleSystemError::InvalidEmergency("Signal on time should be positive.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Emergency being changed counts as active. Lock is not awaited, because caller can hold output which signal
+     * thread of replaced emergency waits for.
+     */
+    fn is_emergency_active(&self) -> bool {
+        self.emergency
+            .try_read()
+            .map_or(true, |emergency| emergency.is_some())
+    }
+
+    /**
+     * Signal thread stops on next tick. It is joined, so outputs are low when this function returns.
+     */
+    fn stop_emergency_signal(&self) {
+        self.emergency_generation.fetch_add(1, Ordering::SeqCst);
+
+        let signal_thread: Option<JoinHandle<()>> = self.emergency_signal_thread
+            .lock()
+            .ok()
+            .and_then(|mut signal_thread| signal_thread.take());
+
+        if let Some(signal_thread) = signal_thread {
+            if signal_thread.join().is_err() {
+                log::error!("Emergency signal thread panicked.");
+            }
+        }
+    }
+
+    fn start_emergency_signal(&self, emergency: Emergency) {
+        /* signal of replaced emergency sets outputs low when it stops, so it has to stop before new signal starts */
+        self.stop_emergency_signal();
+
+        let generation: u32 = self.emergency_generation.load(Ordering::SeqCst);
+        let emergency_generation: Arc<AtomicU32> = Arc::clone(&self.emergency_generation);
+        let alarm_outputs: Arc<AlarmOutputs> = Arc::clone(&self.alarm_outputs);
+
+        let signal_thread: JoinHandle<()> = thread::spawn(move || {
+            let started_at: Instant = Instant::now();
+
+            let set_outputs_level = |level: Level| {
+                for output_index in &emergency.output_indices {
+                    if let Some(output_pin) = alarm_outputs.get(*output_index as usize) {
+                        if let Ok(mut output_pin_driver) = output_pin.lock() {
+                            let _ = output_pin_driver.set_level(level);
+                        }
+                    }
+                }
+            };
+
+            /* run until emergency is stopped or replaced by other one */
+            while emergency_generation.load(Ordering::SeqCst) == generation {
+                let level: Level =
+                    match emergency.signal {
+                        EmergencySignal::Continuous => Level::High,
+                        EmergencySignal::Intermittent { on_millis, off_millis } => {
+                            let elapsed_millis: u64 = started_at.elapsed().as_millis() as u64;
+
+                            if elapsed_millis % (on_millis + off_millis) < on_millis {
+                                Level::High
+                            } else {
+                                Level::Low
+                            }
+                        }
+                    };
+
+                set_outputs_level(level);
+
+                thread::sleep(Duration::from_millis(EMERGENCY_SIGNAL_TICK_MS));
+            }
+
+            set_outputs_level(Level::Low);
+        });
+
+        if let Ok(mut emergency_signal_thread) = self.emergency_signal_thread.lock() {
+            *emergency_signal_thread = Some(signal_thread);
+        }
+    }
+}
+
+/* inputs */
+impl ScheduleSystem {
+    pub fn get_input_configs(&self) -> ScheduleSystemResult<Vec<InputConfig>> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), INPUTS_FILE).into();
+
+        let content: Vec<u8> =
+            match self.disk.lock().map_err(|_| ScheduleSystemError::MutexLockError)?.read_from_file(&path) {
+                Ok(content) => content,
+                /* no inputs configured yet */
+                Err(embedded_sdmmc::Error::NotFound) => return Ok(vec![]),
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            };
+
+        let input_configs: Vec<InputConfigDTO> = serde_json::from_slice(&content)
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        Ok(input_configs.into_iter().map(Into::into).collect())
+    }
+
+    /**
+     * Inputs are applied on next boot, because GPIOs can't be released while input thread is running.
+     */
+    pub fn set_input_configs(&self, input_configs: Vec<InputConfig>) -> ScheduleSystemResult<()> {
+        let mut gpios: HashSet<i32> = HashSet::new();
+
+        for input_config in &input_configs {
+            self.validate_input_config(input_config)?;
+
+            if !gpios.insert(input_config.gpio) {
+                return Err(ScheduleSystemError::InvalidInputConfig(format!("GPIO {} is used more than once.", input_config.gpio)));
+            }
+        }
+
+        let input_configs: Vec<InputConfigDTO> = input_configs.into_iter().map(Into::into).collect();
+        let input_configs_str: String = serde_json::to_string(&input_configs)
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), INPUTS_FILE).into();
+
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .write_to_file(&path, input_configs_str.as_bytes())
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        self.log_event(EventKind::InputsChanged, format!("{} inputs configured.", input_configs.len()));
+
+        Ok(())
+    }
+
+    pub fn validate_input_config(&self, input_config: &InputConfig) -> ScheduleSystemResult<()> {
+        if !FREE_INPUT_GPIOS.contains(&input_config.gpio) {
+            return Err(ScheduleSystemError::InvalidInputConfig(format!("GPIO {} can't be used as input.", input_config.gpio)));
+        }
+
+        if NO_PULL_INPUT_GPIOS.contains(&input_config.gpio) && !matches!(input_config.pull, Pull::Floating) {
+            return Err(ScheduleSystemError::InvalidInputConfig(format!("GPIO {} has no internal pull resistor, use floating pull with external resistor.", input_config.gpio)));
+        }
+
+        match &input_config.action {
+            InputAction::RingOutput { output_index, .. } => {
+                if !self.alarm_output_indices.contains(&(*output_index as usize)) {
+                    return Err(ScheduleSystemError::OutputIndexOutOfBounds(*output_index));
+                }
+            }
+            InputAction::SwitchProfile { profile } => {
+                if *profile >= SCHEDULE_PROFILES_COUNT {
+                    return Err(ScheduleSystemError::ProfileOutOfBounds(*profile));
+                }
+            }
+            InputAction::StartEmergency { emergency } => self.validate_emergency(emergency)?,
+            InputAction::StopEmergency | InputAction::ToggleSilentMode => {}
+        }
+
+        Ok(())
+    }
+}
+
+/* settings */
+impl ScheduleSystem {
+    pub fn get_settings(&self) -> ScheduleSystemResult<Settings> {
+        let settings: Settings = self.settings
+            .read()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .clone();
+
+        Ok(settings)
+    }
+
+    /**
+     * Validate, apply and persist changed settings. Returns updated settings.
+     */
+    pub fn update_settings(&self, settings_patch: SettingsPatch) -> ScheduleSystemResult<Settings> {
+        /* settings stay locked until applied, so concurrent updates can't revert each other */
+        let mut current_settings = self.settings
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let settings: Settings = settings_patch.apply_to(&current_settings);
+
+        settings
+            .validate()
+            .map_err(ScheduleSystemError::InvalidSettings)?;
+
+        let previous_settings: Settings = current_settings.clone();
+
+        /* settings are saved only when subsystems accept them, so device doesn't load rejected settings on next start */
+        let result: ScheduleSystemResult<()> = self
+            .apply_settings_locked(&mut current_settings, settings.clone())
+            .and_then(|_| self.write_settings_to_disk(&settings));
+
+        if let Err(error) = result {
+            /* subsystems applied before failure return to previous settings, which are the saved ones */
+            if let Err(revert_error) = self.apply_settings_locked(&mut current_settings, previous_settings) {
+                log::error!("Can't revert settings: {revert_error:?}");
+            }
+
+            return Err(error);
+        }
+
+        drop(current_settings);
+
+        self.log_event(EventKind::SettingsChanged, format!("{settings:?}"));
+
+        Ok(settings)
+    }
+
+    /**
+     * Notify subsystems about changed settings and store them as current ones.
+     */
+    fn apply_settings(&self, settings: Settings) -> ScheduleSystemResult<()> {
+        let mut current_settings = self.settings
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        self.apply_settings_locked(&mut current_settings, settings)
+    }
+
+    fn apply_settings_locked(&self, current_settings: &mut Settings, settings: Settings) -> ScheduleSystemResult<()> {
+        let mut access_point = self.access_point
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        if access_point.get_ssid() != settings.access_point_ssid {
+            access_point
+                .set_ssid(&settings.access_point_ssid)
+                .map_err(ScheduleSystemError::EspError)?;
+            log::info!("Access point SSID changed to '{}'.", settings.access_point_ssid);
+        }
+
+        self.clock
+            .read()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .set_alarm_match_check_interval(settings.alarm_match_check_interval_ms);
+
+        self.display_refresh_interval_ms.store(settings.display_refresh_interval_ms, Ordering::SeqCst);
+
+        set_cors_policy(CorsPolicy {
+            allowed_origins: settings.cors_allowed_origins.clone(),
+            allowed_methods: settings.cors_allowed_methods.clone(),
+            allow_credentials: settings.cors_allow_credentials,
+        });
+
+        set_max_body_size(settings.max_request_body_bytes as usize);
+
+        SecurityContext::get()
+            .map_err(ScheduleSystemError::EspError)?
+            .set_session_store(settings.session_store)
+            .map_err(ScheduleSystemError::SecurityError)?;
+
+        *current_settings = settings;
+
+        Ok(())
+    }
+}
+
+/* first-run setup */
+impl ScheduleSystem {
+    /**
+     * Set passwords, device name and time together. Already applied changes are reverted when a step fails.
+     */
+    pub fn complete_setup(&self, setup: Setup) -> ScheduleSystemResult<()> {
+        let security_context: &SecurityContext = SecurityContext::get()
+            .map_err(ScheduleSystemError::EspError)?;
+
+        if !security_context.is_setup_required() {
+            return Err(ScheduleSystemError::SetupAlreadyCompleted);
+        }
+
+        let previous_settings: Settings = self.get_settings()?;
+
+        let settings: Settings = SettingsPatch {
+            access_point_ssid: Some(setup.device_name),
+            ..SettingsPatch::default()
+        }.apply_to(&previous_settings);
+
+        settings
+            .validate()
+            .map_err(ScheduleSystemError::InvalidSetup)?;
+
+        let previous_datetime: DateTime<Utc> = self.get_time()?;
+        let started_at: Instant = Instant::now();
+
+        self.set_time(setup.datetime)?;
+
+        let result: ScheduleSystemResult<()> = self
+            .write_settings_to_disk(&settings)
+            .and_then(|_| self.apply_settings(settings))
+            .and_then(|_| security_context
+                .complete_setup(&setup.username, &setup.user_password, &setup.access_point_password)
+                .map_err(ScheduleSystemError::SecurityError)
+            );
+
+        if let Err(error) = result {
+            log::warn!("Setup failed, reverting changes: {error}");
+
+            let _ = self.write_settings_to_disk(&previous_settings);
+            let _ = self.apply_settings(previous_settings);
+            let _ = self.set_time(previous_datetime + started_at.elapsed());
+
+            return Err(error);
+        }
+
+        /* password is stored already, so it's applied at least after restart */
+        if let Err(error) = self.set_access_point_password(&setup.access_point_password) {
+            log::error!("Can't apply access point password: {error}");
+        }
+
+        log::info!("First-run setup completed.");
+
+        Ok(())
+    }
+}
+
+/* events */
+impl ScheduleSystem {
+    pub fn log_event(&self, kind: EventKind, details: String) {
+        ScheduleSystem::send_event(&self.event_sender, kind, details);
+    }
+
+    /**
+     * Event is dropped when writer thread can't keep up, so callers are never blocked by disk.
+     */
+    fn send_event(event_sender: &SyncSender<Event>, kind: EventKind, details: String) {
+        match event_sender.try_send(Event::now(kind, details)) {
+            Ok(_) => {}
+            Err(TrySendError::Full(event)) => log::warn!("Event queue is full. Dropping event {:?}.", event.kind),
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+
+    /**
+     * Start thread which appends queued events to event journal. Can be started only once.
+     */
+    pub fn start_event_log_writer(self: &Arc<Self>) -> ScheduleSystemResult<()> {
+        let event_receiver: Receiver<Event> = self.event_receiver
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .take()
+            .ok_or(ScheduleSystemError::EventLogWriterAlreadyStarted)?;
+
+        let this: Arc<Self> = Arc::clone(self);
+
+        thread::Builder::new()
+            .stack_size(8 * 1024)
+            .spawn(move || {
+                for event in event_receiver {
+                    this.publish_live_event(&event);
+
+                    if let Err(error) = this.write_event_to_disk(event) {
+                        log::error!("Can't write event: {error}");
+                    }
+                }
+            })
+            .map_err(ScheduleSystemError::ThreadSpawnError)?;
+
+        Ok(())
+    }
+
+    /**
+     * Events matching filter from newest to oldest.
+     */
+    pub fn get_events(&self, filter: &EventFilter) -> ScheduleSystemResult<EventPage> {
+        let from_day: Option<String> = filter.from_timestamp_millis.and_then(ScheduleSystem::event_day);
+        let to_day: Option<String> = filter.to_timestamp_millis.and_then(ScheduleSystem::event_day);
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let file_names: Vec<String> = EVENT_JOURNAL
+            .file_names(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        let mut events: Vec<Event> = vec![];
+        let mut skipped: usize = 0;
+
+        for file_name in file_names.iter().rev() {
+            let day: &str = Journal::file_day(file_name);
+
+            if to_day.as_ref().is_some_and(|to_day| day > to_day.as_str()) {
+                continue;
+            }
+
+            if from_day.as_ref().is_some_and(|from_day| day < from_day.as_str()) {
+                break;
+            }
+
+            let content: Vec<u8> = EVENT_JOURNAL
+                .read_file(&mut disk, file_name)
+                .map_err(ScheduleSystemError::DiskError)?;
+
+            for line in String::from_utf8_lossy(&content).lines().rev() {
+                let Ok(event) = serde_json::from_str::<EventDTO>(line) else {
+                    continue;
+                };
+                let event: Event = event.into();
+
+                if !filter.matches(&event) {
+                    continue;
+                }
+
+                if skipped < filter.offset {
+                    skipped += 1;
+                    continue;
+                }
+
+                /* one extra event tells whether there are more pages */
+                if events.len() == filter.limit {
+                    return Ok(EventPage { events, has_more: true });
+                }
+
+                events.push(event);
+            }
+        }
+
+        Ok(EventPage { events, has_more: false })
+    }
+
+    fn write_event_to_disk(&self, event: Event) -> ScheduleSystemResult<()> {
+        let day: String = ScheduleSystem::event_day(event.timestamp_millis).unwrap_or_default();
+
+        let event: EventDTO = event.into();
+        let event_str: String = serde_json::to_string(&event)
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        EVENT_JOURNAL
+            .append(&mut disk, &day, &event_str)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    fn event_day(timestamp_millis: i64) -> Option<String> {
+        DateTime::from_timestamp_millis(timestamp_millis)
+            .map(|datetime| datetime.format("%Y%m%d").to_string())
+    }
+}
+
+/* live events */
+impl ScheduleSystem {
+    pub fn live_events(&self) -> &LiveEventHub {
+        &self.live_events
+    }
+
+    /**
+     * Start thread which sends live events to subscribers and publishes clock tick every second.
+     * Subscriptions are closed when their access token isn't valid anymore
+     * and connections are closed when they don't send access token in time.
+     */
+    pub fn start_live_events(self: &Arc<Self>) -> ScheduleSystemResult<()> {
+        let this: Arc<Self> = Arc::clone(self);
+
+        thread::Builder::new()
+            .stack_size(8 * 1024)
+            .spawn(move || {
+                let clock_tick: Duration = Duration::from_millis(LIVE_EVENTS_CLOCK_TICK_MS);
+                let reauthentication_interval: Duration = Duration::from_millis(LIVE_EVENTS_REAUTHENTICATION_INTERVAL_MS);
+                let authentication_timeout: Duration = Duration::from_millis(LIVE_EVENTS_AUTH_TIMEOUT_MS);
+
+                let mut next_clock_tick: Instant = Instant::now();
+                let mut next_reauthentication: Instant = Instant::now() + reauthentication_interval;
+
+                loop {
+                    this.live_events.wait(next_clock_tick.saturating_duration_since(Instant::now()));
+
+                    let now: Instant = Instant::now();
+
+                    if now >= next_clock_tick {
+                        let timestamp_millis: i64 = system_time::now().as_millis() as i64;
+                        this.live_events.publish("clock", &ClockDTO { timestamp_millis });
+                        next_clock_tick = now + clock_tick;
+                    }
+
+                    if now >= next_reauthentication {
+                        this.live_events.retain(|access_token: &String| ScheduleSystem::is_live_events_access_valid(access_token));
+                        next_reauthentication = now + reauthentication_interval;
+                    }
+
+                    this.live_events.close_unauthenticated(authentication_timeout);
+
+                    this.live_events.deliver();
+                }
+            })
+            .map_err(ScheduleSystemError::ThreadSpawnError)?;
+
+        Ok(())
+    }
+
+    /**
+     * Subscriber needs read permission, the same as for polling state via REST interface.
+     * Address of subscriber isn't known here, it was audited when subscriber authenticated.
+     */
+    fn is_live_events_access_valid(access_token: &str) -> bool {
+        SecurityContext::get().is_ok_and(|security_context| {
+            security_context
+                .authenticate(access_token, None)
+                .and_then(|session| security_context.authorize(&session, Permission::Read))
+                .is_ok()
+        })
+    }
+
+    /**
+     * Logged event is pushed to subscribers when it changes state shown by web interface.
+     * Login events aren't pushed, because they are visible only in event log.
+     */
+    fn publish_live_event(&self, event: &Event) {
+        match event.kind {
+            EventKind::AlarmFired => self.live_events.publish("alarm_fired", &EventDTO::from(event.clone())),
+            EventKind::RingFailed => self.live_events.publish("output_fault", &EventDTO::from(event.clone())),
+            EventKind::EmergencyStarted | EventKind::EmergencyStopped => {
+                let emergency: Option<EmergencyDTO> = self
+                    .get_emergency()
+                    .ok()
+                    .flatten()
+                    .map(Into::into);
+
+                self.live_events.publish("emergency", &EmergencyStatusDTO { emergency });
+            }
+            EventKind::SilentModeChanged
+            | EventKind::ProfileSwitched
+            | EventKind::TimeChanged
+            | EventKind::SettingsChanged
+            | EventKind::AlarmsChanged
+            | EventKind::InputsChanged => self.live_events.publish("config_changed", &EventDTO::from(event.clone())),
+            EventKind::AlarmSkipped
+            | EventKind::ManualRing
+            | EventKind::Login
+            | EventKind::LoginFailed
+            | EventKind::LoginBlocked => {}
+        }
+    }
+}
+
+/* audit */
+impl ScheduleSystem {
+    /**
+     * Append entry to audit log. Failure is only logged, because audited action is already done.
+     */
+    pub fn audit(&self, record: AuditRecord) {
+        if let Err(error) = self.write_audit_entry(record) {
+            log::error!("Can't write audit entry: {error}");
+        }
+    }
+
+    /**
+     * Let security context audit events which happen inside of it. Can be called once schedule system is shared.
+     */
+    pub fn start_security_audit(self: &Arc<Self>) -> ScheduleSystemResult<()> {
+        let this: Arc<Self> = Arc::clone(self);
+
+        SecurityContext::get()
+            .map_err(ScheduleSystemError::EspError)?
+            .set_audit_listener(move |record| this.audit(record));
+
+        Ok(())
+    }
+
+    fn write_audit_entry(&self, record: AuditRecord) -> ScheduleSystemResult<()> {
+        let mut audit_chain = self.audit_chain
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let timestamp_millis: i64 = system_time::now().as_millis() as i64;
+        let entry: AuditEntry = AuditEntry::new(audit_chain.next_sequence, timestamp_millis, record, audit_chain.last_hash.clone());
+
+        let day: String = ScheduleSystem::event_day(timestamp_millis)
+            .unwrap_or_default()
+            .max(audit_chain.last_day.clone());
+        let audit_chain_head: AuditChainHead = AuditChainHead::of(&entry);
+        let next_audit_chain: AuditChain = AuditChain::continue_from(&audit_chain_head, &day);
+
+        let entry_str: String = serde_json::to_string(&AuditEntryDTO::from(entry))
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        AUDIT_JOURNAL
+            .append(&mut disk, &day, &entry_str)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        /* chain moves only when entry is stored */
+        *audit_chain = next_audit_chain;
+
+        /* entry is already stored, so failure only makes verification report mismatched head */
+        if let Err(error) = ScheduleSystem::store_audit_chain_head(&audit_chain_head) {
+            log::error!("Can't store head of audit chain: {error}");
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Audit entries matching filter from newest to oldest.
+     */
+    pub fn get_audit_entries(&self, filter: &AuditFilter) -> ScheduleSystemResult<AuditPage> {
+        let from_day: Option<String> = filter.from_timestamp_millis.and_then(ScheduleSystem::event_day);
+        let to_day: Option<String> = filter.to_timestamp_millis.and_then(ScheduleSystem::event_day);
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let file_names: Vec<String> = AUDIT_JOURNAL
+            .file_names(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        let mut entries: Vec<AuditEntry> = vec![];
+        let mut skipped: usize = 0;
+
+        for file_name in file_names.iter().rev() {
+            let day: &str = Journal::file_day(file_name);
+
+            if to_day.as_ref().is_some_and(|to_day| day > to_day.as_str()) {
+                continue;
+            }
+
+            if from_day.as_ref().is_some_and(|from_day| day < from_day.as_str()) {
+                break;
+            }
+
+            let content: Vec<u8> = AUDIT_JOURNAL
+                .read_file(&mut disk, file_name)
+                .map_err(ScheduleSystemError::DiskError)?;
+
+            for line in String::from_utf8_lossy(&content).lines().rev() {
+                let Some(entry) = ScheduleSystem::parse_audit_entry(line) else {
+                    continue;
+                };
+
+                if !filter.matches(&entry) {
+                    continue;
+                }
+
+                if skipped < filter.offset {
+                    skipped += 1;
+                    continue;
+                }
+
+                if entries.len() == filter.limit {
+                    return Ok(AuditPage { entries, has_more: true });
+                }
+
+                entries.push(entry);
+            }
+        }
+
+        Ok(AuditPage { entries, has_more: false })
+    }
+
+    /**
+     * Check hashes and sequence of all retained entries and compare newest entry with stored head.
+     * Files are read one by one to keep memory usage low.
+     */
+    pub fn verify_audit_log(&self) -> ScheduleSystemResult<AuditVerification> {
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let file_names: Vec<String> = AUDIT_JOURNAL
+            .file_names(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        let mut verification: AuditVerification = AuditVerification {
+            entries_count: 0,
+            first_sequence: None,
+            last_sequence: None,
+            broken_at_sequence: None,
+            unreadable_lines_count: 0,
+            head_matches: true,
+        };
+        let mut previous_entry: Option<AuditEntry> = None;
+
+        for file_name in &file_names {
+            let content: Vec<u8> = AUDIT_JOURNAL
+                .read_file(&mut disk, file_name)
+                .map_err(ScheduleSystemError::DiskError)?;
+
+            for line in String::from_utf8_lossy(&content).lines() {
+                let Some(entry) = ScheduleSystem::parse_audit_entry(line) else {
+                    verification.unreadable_lines_count += 1;
+                    continue;
+                };
+
+                let is_linked: bool = previous_entry.as_ref().map_or(true, |previous_entry| {
+                    entry.sequence == previous_entry.sequence + 1 && entry.previous_hash == previous_entry.hash
+                });
+
+                if verification.broken_at_sequence.is_none() && (!is_linked || entry.compute_hash() != entry.hash) {
+                    verification.broken_at_sequence = Some(entry.sequence);
+                }
+
+                verification.entries_count += 1;
+                verification.first_sequence.get_or_insert(entry.sequence);
+                verification.last_sequence = Some(entry.sequence);
+
+                previous_entry = Some(entry);
+            }
+        }
+
+        if let Some(stored_head) = ScheduleSystem::read_audit_chain_head()? {
+            verification.head_matches = previous_entry
+                .as_ref()
+                .is_some_and(|previous_entry| AuditChainHead::of(previous_entry) == stored_head);
+        }
+
+        Ok(verification)
+    }
+
+    /**
+     * Names of audit log files from oldest to newest. Used for export file by file.
+     */
+    pub fn get_audit_log_file_names(&self) -> ScheduleSystemResult<Vec<String>> {
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        AUDIT_JOURNAL
+            .file_names(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    pub fn read_audit_log_file(&self, file_name: &str) -> ScheduleSystemResult<Vec<u8>> {
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        AUDIT_JOURNAL
+            .read_file(&mut disk, file_name)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    fn read_audit_chain_head() -> ScheduleSystemResult<Option<AuditChainHead>> {
+        SecurityContext::get()
+            .map_err(ScheduleSystemError::EspError)?
+            .get_audit_chain_head()
+            .map_err(ScheduleSystemError::SecurityError)
+    }
+
+    fn store_audit_chain_head(audit_chain_head: &AuditChainHead) -> ScheduleSystemResult<()> {
+        SecurityContext::get()
+            .map_err(ScheduleSystemError::EspError)?
+            .set_audit_chain_head(audit_chain_head)
+            .map_err(ScheduleSystemError::SecurityError)
+    }
+
+    /**
+     * Continue chain from last readable entry of newest file. When it isn't the stored head, chain continues
+     * from stored head, so removed or rewritten entries stay detectable by verification.
+     */
+    fn restore_audit_chain_from_disk(&self) -> ScheduleSystemResult<()> {
+        let stored_head: Option<AuditChainHead> = ScheduleSystem::read_audit_chain_head().unwrap_or_else(|error| {
+            log::error!("Can't read head of audit chain: {error}");
+            None
+        });
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let file_names: Vec<String> = AUDIT_JOURNAL
+            .file_names(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        for file_name in file_names.iter().rev() {
+            let content: Vec<u8> = AUDIT_JOURNAL
+                .read_file(&mut disk, file_name)
+                .map_err(ScheduleSystemError::DiskError)?;
+
+            let last_entry: Option<AuditEntry> = String::from_utf8_lossy(&content)
+                .lines()
+                .rev()
+                .find_map(ScheduleSystem::parse_audit_entry);
+
+            if let Some(last_entry) = last_entry {
+                let head: AuditChainHead = AuditChainHead::of(&last_entry);
+
+                let head: AuditChainHead = match stored_head {
+                    Some(stored_head) if stored_head != head => {
+                        log::error!("Newest audit entry {} doesn't match stored head {}.", head.sequence, stored_head.sequence);
+                        stored_head
+                    }
+                    _ => head,
+                };
+
+                *self.audit_chain
+                    .lock()
+                    .map_err(|_| ScheduleSystemError::MutexLockError)? = AuditChain::continue_from(&head, Journal::file_day(file_name));
+
+                return Ok(());
+            }
+        }
+
+        if let Some(stored_head) = stored_head {
+            log::error!("Audit log is empty, but stored head is entry {}.", stored_head.sequence);
+
+            *self.audit_chain
+                .lock()
+                .map_err(|_| ScheduleSystemError::MutexLockError)? = AuditChain::continue_from(&stored_head, "");
+        }
+
+        Ok(())
+    }
+
+    fn parse_audit_entry(line: &str) -> Option<AuditEntry> {
+        serde_json::from_str::<AuditEntryDTO>(line)
+            .ok()
+            .and_then(|entry| entry.try_into().ok())
+    }
+}
+
+/* access point */
+impl ScheduleSystem {
+    pub fn enable_access_point(&self) -> ScheduleSystemResult<()> {
+        self.access_point
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .start()
+            .map_err(ScheduleSystemError::EspError)?;
+
+        Ok(())
+    }
+
+    pub fn get_access_point_ipv4(&self) -> ScheduleSystemResult<Ipv4Addr> {
+        self.access_point
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .get_ipv4()
+            .map_err(ScheduleSystemError::EspError)
+    }
+
+    /**
+     * Apply password to running access point. Password should be already stored by security context.
+     */
+    pub fn set_access_point_password(&self, password: &str) -> ScheduleSystemResult<()> {
+        self.access_point
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .set_password(password)
+            .map_err(ScheduleSystemError::EspError)?;
+
+        log::info!("Access point password changed.");
+
+        Ok(())
+    }
+
+    pub fn disable_access_point(&self) -> ScheduleSystemResult<()> {
+        self.access_point
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .stop()
+            .map_err(ScheduleSystemError::EspError)?;
+
+        Ok(())
+    }
+}
+
+/* TLS certificate */
+impl ScheduleSystem {
+    /**
+     * Stored certificate or new self-signed one when nothing valid is stored. Used on boot to start HTTPS server.
+     */
+    pub fn get_or_create_tls_certificate(&self) -> ScheduleSystemResult<TlsCertificate> {
+        match self.get_tls_certificate() {
+            Ok(Some(tls_certificate)) => return Ok(tls_certificate),
+            Ok(None) => log::info!("TLS certificate not found. Generating self-signed certificate..."),
+            Err(error) => log::warn!("Can't read TLS certificate: {error}. Generating self-signed certificate..."),
+        }
+
+        self.reset_tls_certificate()
+    }
+
+    /**
+     * Stored certificate. It is used by HTTPS server after restart.
+     */
+    pub fn get_tls_certificate(&self) -> ScheduleSystemResult<Option<TlsCertificate>> {
+        let mut disk = self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let mut read_pem = |file_name: &str| -> ScheduleSystemResult<Option<String>> {
+            match disk.read_from_file(&([SYSTEM_DIR, TLS_DIR].as_slice(), file_name).into()) {
+                Ok(content) => Ok(Some(String::from_utf8_lossy(&content).to_string())),
+                Err(embedded_sdmmc::Error::NotFound) => Ok(None),
+                Err(error) => Err(ScheduleSystemError::DiskError(error)),
+            }
+        };
+
+        let (Some(certificate_pem), Some(private_key_pem)) = (read_pem(TLS_CERTIFICATE_FILE)?, read_pem(TLS_PRIVATE_KEY_FILE)?) else {
+            return Ok(None);
+        };
+
+        TlsCertificate::new(certificate_pem, private_key_pem)
+            .map(Some)
+            .map_err(ScheduleSystemError::TlsError)
+    }
+
+    /**
+     * Certificate is used by HTTPS server after restart.
+     */
+    pub fn set_tls_certificate(&self, tls_certificate: &TlsCertificate) -> ScheduleSystemResult<()> {
+        let mut disk = self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let files: [(&str, &str); 2] = [
+            (TLS_PRIVATE_KEY_FILE, tls_certificate.private_key_pem()),
+            (TLS_CERTIFICATE_FILE, tls_certificate.certificate_pem()),
+        ];
+
+        /* mismatching pair left by failed write is detected on boot and replaced by self-signed certificate */
+        for (file_name, pem) in files {
+            disk.write_to_file(&([SYSTEM_DIR, TLS_DIR].as_slice(), file_name).into(), pem.as_bytes())
+                .map_err(ScheduleSystemError::DiskError)?;
+        }
+
+        log::info!("TLS certificate '{}' stored.", tls_certificate.subject());
+
+        Ok(())
+    }
+
+    /**
+     * Replace stored certificate with new self-signed one issued for access point SSID and IP address.
+     */
+    pub fn reset_tls_certificate(&self) -> ScheduleSystemResult<TlsCertificate> {
+        let common_name: String = self.get_settings()?.access_point_ssid;
+        let ip_addresses: Vec<Ipv4Addr> = self.get_access_point_ipv4().into_iter().collect();
+
+        let tls_certificate: TlsCertificate = TlsCertificate::generate_self_signed(&common_name, &ip_addresses)
+            .map_err(ScheduleSystemError::TlsError)?;
+
+        self.set_tls_certificate(&tls_certificate)?;
+
+        Ok(tls_certificate)
+    }
+}
+
+/* disk */
+impl ScheduleSystem {
+    pub fn read_from_file(&self, path: &FilePath) -> ScheduleSystemResult<Vec<u8>> {
+        println!("Locking disk for read...");
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .read_from_file(path)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    pub fn get_file_info(&self, path: &FilePath) -> ScheduleSystemResult<DirEntry> {
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .file_info(path)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    /**
+     * Disk is locked until whole range is read, so file can't be changed while it's being sent.
+     */
+    pub fn read_from_file_range<OnRead: FnMut(&[u8]) -> Result<(), ()>>(&self, path: &FilePath, offset: u32, length: u32, on_read: OnRead) -> ScheduleSystemResult<()> {
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .read_from_file_range(path, offset, length, FILE_READ_CHUNK_BYTES, on_read)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    pub fn write_to_file(&self, path: &FilePath, data_buffer: &mut [u8]) -> ScheduleSystemResult<()> {
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .write_to_file(path, data_buffer)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    pub fn append_to_file(&self, path: &FilePath, data_buffer: &[u8]) -> ScheduleSystemResult<()> {
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .append_to_file(path, data_buffer)
+            .map_err(ScheduleSystemError::DiskError)
+    }
+}
+
+/* web interface */
+impl ScheduleSystem {
+    /**
+     * Directory which web interface is served from, i.e. active slot or WEB_UI_DIR before first activation.
+     */
+    pub fn web_ui_dir(&self) -> Vec<&'static str> {
+        let mut directories: Vec<&'static str> = vec![SYSTEM_DIR, WEB_UI_DIR];
+        directories.extend(self.web_ui_slot());
+
+        directories
+    }
+
+    /**
+     * Files of active web interface or of staging slot, which is activated by activate_web_ui_staging().
+     */
+    pub fn list_web_ui_files(&self, staging: bool) -> ScheduleSystemResult<Vec<WebUiFile>> {
+        let root: Vec<&str> = if staging { self.web_ui_staging_dir() } else { self.web_ui_dir() };
+
+        /* slots and pointer file are not part of web interface served from WEB_UI_DIR */
+        let is_legacy_root: bool = !staging && self.web_ui_slot().is_none();
+        let is_hidden = |relative: &[String], name: &str| {
+            is_legacy_root
+                && relative.is_empty()
+                && (WEB_UI_SLOT_DIRS.iter().any(|slot| slot.eq_ignore_ascii_case(name)) || WEB_UI_ACTIVE_SLOT_FILE.eq_ignore_ascii_case(name))
+        };
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let mut files: Vec<WebUiFile> = vec![];
+        let mut pending_dirs: Vec<Vec<String>> = vec![vec![]];
+
+        while let Some(relative) = pending_dirs.pop() {
+            let mut directories: Vec<&str> = root.clone();
+            directories.extend(relative.iter().map(String::as_str));
+
+            let path: DirectoryPath = directories.as_slice().into();
+
+            let dir_names: Vec<String> =
+                match disk.list_dir(&path) {
+                    Ok(dir_names) => dir_names,
+                    /* staging slot is created by first upload */
+                    Err(embedded_sdmmc::Error::NotFound) if relative.is_empty() => return Ok(vec![]),
+                    Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+                };
 
-        thread::spawn(move || loop {
-            let seconds: u64 = EspSystemTime.now().as_secs();
-            let Some(datetime) = DateTime::from_timestamp(seconds as i64, 0) else {
-                continue;
-            };
+            for dir_name in dir_names {
+                if !is_hidden(&relative, &dir_name) {
+                    let mut dir: Vec<String> = relative.clone();
+                    dir.push(dir_name);
+                    pending_dirs.push(dir);
+                }
+            }
 
-            let datetime: String = datetime
-                .naive_utc()
-                .format("%d/%m/%Y\n%H:%M:%S")
-                .to_string();
+            for filename in disk.list_files(&path).map_err(ScheduleSystemError::DiskError)? {
+                if is_hidden(&relative, &filename) {
+                    continue;
+                }
 
-            let _ = display.write_text(datetime.as_str());
+                let size: u32 = disk
+                    .file_info(&(directories.as_slice(), filename.as_str()).into())
+                    .map_err(ScheduleSystemError::DiskError)?
+                    .size;
 
-            /* update time every second */
-            thread::sleep(Duration::from_secs(1));
-        });
+                let mut segments: Vec<String> = relative.clone();
+                segments.push(filename);
 
-        Ok(this)
-    }
+                files.push(WebUiFile { path: segments.join("/"), size });
+            }
+        }
 
+        files.sort_by(|a, b| a.path.cmp(&b.path));
 
-    fn on_alarm(alarm_id: &AlarmId, alarm: &Alarm, date_time: &DateTime<Utc>, alarm_output_pins: &AlarmOutputs) {
-        let output_index: usize = alarm_id.output_index as usize;
+        Ok(files)
+    }
 
-        let Some(output_pin) = alarm_output_pins.get(output_index) else {
-            log::warn!("Alarm output index {output_index} out of bounds. Skipping alarm...");
-            return;
-        };
+    /**
+     * Deleted file is missing from active web interface immediately.
+     */
+    pub fn delete_web_ui_file(&self, path: &str) -> ScheduleSystemResult<()> {
+        let segments: Vec<&str> = parse_web_ui_path(path)?;
 
-        let Ok(mut output_pin_driver) = output_pin.try_lock() else {
-            log::warn!("Can't lock output GPIO pin {output_index}. Skipping alarm...");
-            return;
-        };
+        let mut directories: Vec<&str> = self.web_ui_dir();
+        directories.extend_from_slice(&segments[..segments.len() - 1]);
 
-        // log::info!(
-        //     "Alarming: Output - {}, Id - {}, time - {}, impulse length - {}ms.",
-        //     alarm_id.output_index, alarm_id.identifier, date_time, alarm.impulse_length_millis
-        // );
+        let file_path: FilePath = (directories.as_slice(), segments[segments.len() - 1]).into();
 
-        let _ = output_pin_driver.set_high();
-        thread::sleep(Duration::from_millis(alarm.impulse_length_millis));
+        let result = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .delete_file(&file_path);
 
-        // log::info!(
-        //     "Stoping alarm: Output - {}, Id - {}, time - {}.",
-        //     alarm_id.output_index, alarm_id.identifier, date_time
-        // );
-        let _ = output_pin_driver.set_low();
+        match result {
+            Ok(()) => Ok(()),
+            Err(embedded_sdmmc::Error::NotFound) => Err(ScheduleSystemError::WebUiFileNotFound(path.to_string())),
+            Err(embedded_sdmmc::Error::DeleteDirAsFile) => Err(ScheduleSystemError::InvalidWebUiFile(format!("'{path}' is directory."))),
+            Err(error) => Err(ScheduleSystemError::DiskError(error)),
+        }
     }
-}
 
-impl ScheduleSystem {
-    pub fn alarm_output_indices(&self) -> &Vec<usize> {
-        &self.alarm_output_indices
+    /**
+     * Files of staging slot are deleted. Empty directories are left, because FAT driver can't delete directories.
+     */
+    pub fn clear_web_ui_staging(&self) -> ScheduleSystemResult<()> {
+        self.clear_web_ui_dir(&self.web_ui_staging_dir())
     }
-}
 
-/* access point */
-impl ScheduleSystem {
-    pub fn enable_access_point(&self) -> ScheduleSystemResult<()> {
-        self.access_point
+    pub fn make_web_ui_staging_dir(&self, path: &str) -> ScheduleSystemResult<()> {
+        let mut directories: Vec<&str> = self.web_ui_staging_dir();
+        directories.extend(parse_web_ui_path(path)?);
+
+        self.disk
             .lock()
             .map_err(|_| ScheduleSystemError::MutexLockError)?
-            .start()
-            .map_err(ScheduleSystemError::EspError)?;
+            .make_dir(&directories.as_slice().into())
+            .map_err(|error| web_ui_file_error(path, error))
+    }
 
-        Ok(())
+    /**
+     * Empty file in staging slot, which uploaded data is appended to. Existing file is truncated.
+     */
+    pub fn create_web_ui_staging_file(&self, path: &str) -> ScheduleSystemResult<FilePath> {
+        let segments: Vec<&str> = parse_web_ui_path(path)?;
+
+        let mut directories: Vec<&str> = self.web_ui_staging_dir();
+        directories.extend_from_slice(&segments[..segments.len() - 1]);
+
+        let file_path: FilePath = (directories.as_slice(), segments[segments.len() - 1]).into();
+
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        disk.make_dir(&directories.as_slice().into())
+            .map_err(|error| web_ui_file_error(path, error))?;
+
+        disk.write_to_file(&file_path, &[])
+            .map_err(|error| web_ui_file_error(path, error))?;
+
+        Ok(file_path)
     }
 
-    pub fn disable_access_point(&self) -> ScheduleSystemResult<()> {
-        self.access_point
+    /**
+     * Staging slot becomes active by rewriting pointer file, so web interface is never served half-written.
+     * Previously active slot is cleared and becomes staging slot of next upload.
+     */
+    pub fn activate_web_ui_staging(&self) -> ScheduleSystemResult<()> {
+        let previous_dir: Vec<&str> = self.web_ui_dir();
+        let staging_dir: Vec<&str> = self.web_ui_staging_dir();
+        let staging_slot: &'static str = staging_dir[staging_dir.len() - 1];
+
+        let mut disk = self
+            .disk
             .lock()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
-            .stop()
-            .map_err(ScheduleSystemError::EspError)?;
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        match disk.file_info(&(staging_dir.as_slice(), WEB_UI_INDEX_FILE).into()) {
+            Ok(_) => {}
+            Err(embedded_sdmmc::Error::NotFound) => {
+                return Err(ScheduleSystemError::InvalidWebUiFile(format!("Staged web interface has no {WEB_UI_INDEX_FILE}.")));
+            }
+            Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+        }
+
+        let path: FilePath = ([SYSTEM_DIR, WEB_UI_DIR].as_slice(), WEB_UI_ACTIVE_SLOT_FILE).into();
+
+        disk.write_to_file(&path, staging_slot.as_bytes())
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        drop(disk);
+
+        *self
+            .web_ui_slot
+            .write()
+            .map_err(|_| ScheduleSystemError::MutexLockError)? = Some(staging_slot);
+
+        /* files served from WEB_UI_DIR before first activation are kept, because slots are inside of it */
+        if previous_dir.len() > 2 {
+            if let Err(error) = self.clear_web_ui_dir(&previous_dir) {
+                log::warn!("Can't clear previous web interface: {error}");
+            }
+        }
 
         Ok(())
     }
-}
 
-/* disk */
-impl ScheduleSystem {
-    pub fn read_from_file(&self, path: &FilePath) -> ScheduleSystemResult<Vec<u8>> {
-        println!("Locking disk for read...");
-        self.disk
-            .lock()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
-            .read_from_file(path)
-            .map_err(ScheduleSystemError::DiskError)
+    fn web_ui_slot(&self) -> Option<&'static str> {
+        self.web_ui_slot
+            .read()
+            .ok()
+            .and_then(|slot| *slot)
     }
 
-    pub fn read_from_file_bytes<OnRead: FnMut(&[u8], usize) -> Result<(), ()>>(&self, path: &FilePath, bytes: usize, on_read: OnRead) -> ScheduleSystemResult<()> {
-        println!("Locking disk for read...");
-        self.disk
-            .lock()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
-            .read_from_file_bytes(path, bytes, on_read)
-            .map_err(ScheduleSystemError::DiskError)
+    /* slot which isn't active */
+    fn web_ui_staging_dir(&self) -> Vec<&'static str> {
+        let active_slot: Option<&'static str> = self.web_ui_slot();
+
+        let staging_slot: &'static str = WEB_UI_SLOT_DIRS
+            .into_iter()
+            .find(|slot| Some(*slot) != active_slot)
+            .unwrap_or(WEB_UI_SLOT_DIRS[0]);
+
+        vec![SYSTEM_DIR, WEB_UI_DIR, staging_slot]
     }
 
-    pub fn write_to_file(&self, path: &FilePath, data_buffer: &mut [u8]) -> ScheduleSystemResult<()> {
-        self.disk
+    fn clear_web_ui_dir(&self, root: &[&str]) -> ScheduleSystemResult<()> {
+        let mut disk = self
+            .disk
             .lock()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
-            .write_to_file(path, data_buffer)
-            .map_err(ScheduleSystemError::DiskError)
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let mut pending_dirs: Vec<Vec<String>> = vec![root.iter().map(ToString::to_string).collect()];
+
+        while let Some(directories) = pending_dirs.pop() {
+            let path: DirectoryPath = directories
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<&str>>()
+                .as_slice()
+                .into();
+
+            let dir_names: Vec<String> =
+                match disk.list_dir(&path) {
+                    Ok(dir_names) => dir_names,
+                    Err(embedded_sdmmc::Error::NotFound) => return Ok(()),
+                    Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+                };
+
+            disk.clear_dir(&path)
+                .map_err(ScheduleSystemError::DiskError)?;
+
+            for dir_name in dir_names {
+                let mut dir: Vec<String> = directories.clone();
+                dir.push(dir_name);
+                pending_dirs.push(dir);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* name which isn't valid FAT short name is caused by request */
+fn web_ui_file_error(path: &str, error: embedded_sdmmc::Error<embedded_sdmmc::sdcard::Error>) -> ScheduleSystemError {
+    match error {
+        embedded_sdmmc::Error::FilenameError(_) => ScheduleSystemError::InvalidWebUiFile(format!("'{path}' isn't valid 8.3 file name.")),
+        error => ScheduleSystemError::DiskError(error),
     }
 }
 
@@ -305,11 +1985,25 @@ impl ScheduleSystem {
     }
 
     pub fn set_time(&self, datetime: DateTime<Utc>) -> ScheduleSystemResult<()> {
-        self.clock
+        let mut clock = self
+            .clock
             .write()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        let previous_datetime: Option<DateTime<Utc>> = clock.get_datetime().ok();
+
+        clock
             .set_datetime(datetime)
-            .map_err(ScheduleSystemError::ClockError)
+            .map_err(ScheduleSystemError::ClockError)?;
+
+        let details: String =
+            match previous_datetime {
+                Some(previous_datetime) => format!("From {previous_datetime} to {datetime}."),
+                None => format!("To {datetime}."),
+            };
+        self.log_event(EventKind::TimeChanged, details);
+
+        Ok(())
     }
 
 
@@ -349,6 +2043,12 @@ impl ScheduleSystem {
     }
 
     pub fn add_alarm(&self, output_index: u8, alarm: Alarm) -> ScheduleSystemResult<()> {
+        /* alarms directory exists only for existing outputs */
+        if !self.alarm_output_indices.contains(&(output_index as usize)) {
+            return Err(ScheduleSystemError::OutputIndexOutOfBounds(output_index));
+        }
+
+        /* kept locked until file is written, so profile can't be switched meanwhile */
         let mut clock = self
             .clock
             .write()
@@ -380,28 +2080,41 @@ impl ScheduleSystem {
             .add_alarm(alarm_id.clone(), alarm.clone())
             .map_err(ScheduleSystemError::ClockError)?;
 
+        self.log_event(EventKind::AlarmsChanged, format!("Added alarm {} on output {output_index}.", alarm_id.identifier));
+
         self.write_alarm_to_disk(alarm_id, alarm)?;
 
         Ok(())
     }
 
     pub fn remove_alarm(&self, alarm_id: &AlarmId) -> ScheduleSystemResult<()> {
-        self.clock
+        /* kept locked until file is removed, so profile can't be switched meanwhile */
+        let mut clock = self
+            .clock
             .write()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        clock
             .remove_alarm(alarm_id)
             .map_err(ScheduleSystemError::ClockError)?;
 
-        self.remove_alarm_from_disk_by_id(&alarm_id)
+        self.log_event(EventKind::AlarmsChanged, format!("Removed alarm {} on output {}.", alarm_id.identifier, alarm_id.output_index));
+
+        self.remove_alarm_from_disk_by_id(alarm_id)
     }
 
     pub fn remove_alarms_by_output_index(&self, output_index: u8) -> ScheduleSystemResult<()> {
-        self.clock
+        let mut clock = self
+            .clock
             .write()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .map_err(|_| ScheduleSystemError::MutexLockError)?;
+
+        clock
             .remove_alarm_if(|alarm_id: &AlarmId| alarm_id.output_index == output_index)
             .map_err(ScheduleSystemError::ClockError)?;
 
+        self.log_event(EventKind::AlarmsChanged, format!("Removed alarms on output {output_index}."));
+
         self.remove_alarm_from_disk_by_output_index(output_index)
     }
 }
@@ -421,46 +2134,208 @@ impl ScheduleSystem {
             .map_err(ScheduleSystemError::DiskError)?;
         log::info!("Created dir '{path}'.");
 
-        let path: DirectoryPath = [SYSTEM_DIR, ALARMS_DIR].as_slice().into();
+        EVENT_JOURNAL
+            .init(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        AUDIT_JOURNAL
+            .init(&mut disk)
+            .map_err(ScheduleSystemError::DiskError)?;
+
+        let path: DirectoryPath = [SYSTEM_DIR, TLS_DIR].as_slice().into();
 
         disk.make_dir(&path)
             .map_err(ScheduleSystemError::DiskError)?;
-        log::info!("Created dir '{path}'.");
 
-        for output_index in 0..outputs_count {
-            let path: DirectoryPath = [
-                SYSTEM_DIR,
-                ALARMS_DIR,
-                output_index.to_string().as_str()
-            ].as_slice().into();
+        for profile in 0..SCHEDULE_PROFILES_COUNT {
+            let alarms_dir: String = alarms_dir_name(profile);
+            let path: DirectoryPath = [SYSTEM_DIR, alarms_dir.as_str()].as_slice().into();
 
             disk.make_dir(&path)
                 .map_err(ScheduleSystemError::DiskError)?;
+            log::info!("Created dir '{path}'.");
+
+            for output_index in 0..outputs_count {
+                let path: DirectoryPath = [
+                    SYSTEM_DIR,
+                    alarms_dir.as_str(),
+                    output_index.to_string().as_str()
+                ].as_slice().into();
+
+                disk.make_dir(&path)
+                    .map_err(ScheduleSystemError::DiskError)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Settings stored by older firmware are completed with defaults and stored again with current schema version.
+     * Invalid settings are replaced with defaults.
+     */
+    fn read_settings_from_disk(&self) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), SETTINGS_FILE).into();
+
+        let content: Vec<u8> =
+            match self.disk.lock().map_err(|_| ScheduleSystemError::MutexLockError)?.read_from_file(&path) {
+                Ok(content) => content,
+                /* defaults are used until settings are changed */
+                Err(embedded_sdmmc::Error::NotFound) => return self.apply_settings(Settings::default()),
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            };
+
+        let mut settings: Settings =
+            match serde_json::from_slice::<SettingsDTO>(&content) {
+                Ok(settings) => settings.into(),
+                Err(error) => {
+                    log::warn!("Can't parse settings: {error}. Using defaults...");
+                    Settings::default()
+                }
+            };
+
+        if let Err(error) = settings.validate() {
+            log::warn!("Stored settings are invalid: {error} Using defaults...");
+            settings = Settings::default();
+        }
+
+        if settings.schema_version != SETTINGS_SCHEMA_VERSION {
+            log::info!("Migrating settings from schema version {} to {SETTINGS_SCHEMA_VERSION}.", settings.schema_version);
+            settings.schema_version = SETTINGS_SCHEMA_VERSION;
+            self.write_settings_to_disk(&settings)?;
+        }
+
+        self.apply_settings(settings)
+    }
+
+    fn write_settings_to_disk(&self, settings: &Settings) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), SETTINGS_FILE).into();
+
+        let settings: SettingsDTO = settings.clone().into();
+        let settings_str: String = serde_json::to_string(&settings)
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .write_to_file(&path, settings_str.as_bytes())
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    fn read_active_profile_from_disk(&self) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), PROFILE_FILE).into();
+
+        let content: Vec<u8> =
+            match self.disk.lock().map_err(|_| ScheduleSystemError::MutexLockError)?.read_from_file(&path) {
+                Ok(content) => content,
+                /* default profile is used until other one is selected */
+                Err(embedded_sdmmc::Error::NotFound) => return Ok(()),
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            };
+
+        let profile: u8 = String::from_utf8_lossy(&content)
+            .trim()
+            .parse()
+            .unwrap_or(0);
+
+        if profile < SCHEDULE_PROFILES_COUNT {
+            self.active_profile.store(profile, Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    fn read_web_ui_slot_from_disk(&self) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR, WEB_UI_DIR].as_slice(), WEB_UI_ACTIVE_SLOT_FILE).into();
+
+        let content: Vec<u8> =
+            match self.disk.lock().map_err(|_| ScheduleSystemError::MutexLockError)?.read_from_file(&path) {
+                Ok(content) => content,
+                /* web interface wasn't uploaded yet */
+                Err(embedded_sdmmc::Error::NotFound) => return Ok(()),
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            };
+
+        let content: String = String::from_utf8_lossy(&content).trim().to_string();
+
+        match WEB_UI_SLOT_DIRS.into_iter().find(|slot| *slot == content) {
+            Some(slot) => {
+                *self.web_ui_slot.write().map_err(|_| ScheduleSystemError::MutexLockError)? = Some(slot);
+            }
+            None => log::warn!("Unknown web interface slot '{content}', files of {WEB_UI_DIR} are served."),
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Resume emergency which was active before reboot.
+     */
+    fn restore_emergency_from_disk(&self) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), EMERGENCY_FILE).into();
+
+        let content: Vec<u8> =
+            match self.disk.lock().map_err(|_| ScheduleSystemError::MutexLockError)?.read_from_file(&path) {
+                Ok(content) => content,
+                Err(embedded_sdmmc::Error::NotFound) => return Ok(()),
+                Err(error) => return Err(ScheduleSystemError::DiskError(error)),
+            };
+
+        let emergency: Option<EmergencyDTO> = serde_json::from_slice(&content)
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        if let Some(emergency) = emergency {
+            log::warn!("Restoring emergency after reboot.");
+            self.start_emergency(emergency.into())?;
         }
 
         Ok(())
     }
 
+    fn write_emergency_to_disk(&self, emergency: Option<&Emergency>) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), EMERGENCY_FILE).into();
+
+        let emergency: Option<EmergencyDTO> = emergency.cloned().map(Into::into);
+        let emergency_str: String = serde_json::to_string(&emergency)
+            .map_err(ScheduleSystemError::SerdeError)?;
+
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .write_to_file(&path, emergency_str.as_bytes())
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
+    fn write_active_profile_to_disk(&self, profile: u8) -> ScheduleSystemResult<()> {
+        let path: FilePath = ([SYSTEM_DIR].as_slice(), PROFILE_FILE).into();
+
+        self.disk
+            .lock()
+            .map_err(|_| ScheduleSystemError::MutexLockError)?
+            .write_to_file(&path, profile.to_string().as_bytes())
+            .map_err(ScheduleSystemError::DiskError)
+    }
+
     /**
-     * Read all alarms from disk and add to clock.
+     * Read all alarms of active profile from disk and add to clock. Caller holds clock lock, so disk is locked after it.
      */
-    fn synchronize_alarms_from_disk(&self) -> ScheduleSystemResult<()> {
-        fn get_output_dir_names(disk: &mut Disk) -> ScheduleSystemResult<Vec<String>> {
+    fn synchronize_alarms_from_disk(&self, clock: &mut Clock<AlarmId>) -> ScheduleSystemResult<()> {
+        fn get_output_dir_names(disk: &mut Disk, alarms_dir: &str) -> ScheduleSystemResult<Vec<String>> {
             let path: DirectoryPath =
                 [
                     SYSTEM_DIR,
-                    ALARMS_DIR,
+                    alarms_dir,
                 ].as_slice().into();
             disk
                 .list_dir(&path)
                 .map_err(ScheduleSystemError::DiskError)
         }
 
-        fn get_alarm_file_names(disk: &mut Disk, output_dir_name: &str) -> ScheduleSystemResult<Vec<String>> {
+        fn get_alarm_file_names(disk: &mut Disk, alarms_dir: &str, output_dir_name: &str) -> ScheduleSystemResult<Vec<String>> {
             let path: DirectoryPath =
                 [
                     SYSTEM_DIR,
-                    ALARMS_DIR,
+                    alarms_dir,
                     output_dir_name
                 ].as_slice().into();
             disk
@@ -468,12 +2343,12 @@ impl ScheduleSystem {
                 .map_err(ScheduleSystemError::DiskError)
         }
 
-        fn read_alarm_file(disk: &mut Disk, output_dir_name: &str, alarm_file_name: &str) -> ScheduleSystemResult<String> {
+        fn read_alarm_file(disk: &mut Disk, alarms_dir: &str, output_dir_name: &str, alarm_file_name: &str) -> ScheduleSystemResult<String> {
             let file_path: FilePath = (
                 [
                     SYSTEM_DIR,
-                    ALARMS_DIR,
-                    &output_dir_name
+                    alarms_dir,
+                    output_dir_name
                 ].as_slice(),
                 alarm_file_name
             ).into();
@@ -487,22 +2362,20 @@ impl ScheduleSystem {
         }
 
 
+        let alarms_dir: String = alarms_dir_name(self.active_profile());
+
         let mut disk = self
             .disk
             .lock()
             .map_err(|_| ScheduleSystemError::MutexLockError)?;
-        let mut clock = self
-            .clock
-            .write()
-            .map_err(|_| ScheduleSystemError::MutexLockError)?;
 
-        let output_dir_names: Vec<String> = get_output_dir_names(&mut disk)?;
+        let output_dir_names: Vec<String> = get_output_dir_names(&mut disk, &alarms_dir)?;
 
         for output_dir_name in output_dir_names {
-            let alarm_file_names: Vec<String> = get_alarm_file_names(&mut disk, &output_dir_name)?;
+            let alarm_file_names: Vec<String> = get_alarm_file_names(&mut disk, &alarms_dir, &output_dir_name)?;
 
             for alarm_file_name in alarm_file_names {
-                let alarm_str: String = read_alarm_file(&mut disk, &output_dir_name, &alarm_file_name)?;
+                let alarm_str: String = read_alarm_file(&mut disk, &alarms_dir, &output_dir_name, &alarm_file_name)?;
 
                 let alarm_with_id: AlarmWithIdDTO =
                     match serde_json::from_str(&alarm_str) {
@@ -519,7 +2392,10 @@ impl ScheduleSystem {
         Ok(())
     }
 
+    /* alarm files belong to active profile, callers hold clock lock while they change them */
     fn write_alarm_to_disk(&self, alarm_id: AlarmId, alarm: Alarm) -> ScheduleSystemResult<()> {
+        let alarms_dir: String = alarms_dir_name(self.active_profile());
+
         let mut disk = self
             .disk
             .lock()
@@ -528,12 +2404,12 @@ impl ScheduleSystem {
         let alarm_with_id: AlarmWithIdDTO = (alarm_id, alarm).into();
 
         let output_index: u8 = alarm_with_id.id.output_index;
-        let identifier: &str = alarm_with_id.id.identifier.as_str().into();
+        let identifier: &str = alarm_with_id.id.identifier.as_str();
 
         let file_path: FilePath = (
             [
                 SYSTEM_DIR,
-                ALARMS_DIR,
+                alarms_dir.as_str(),
                 output_index.to_string().as_str()
             ].as_slice(),
             identifier
@@ -548,6 +2424,8 @@ impl ScheduleSystem {
     }
 
     fn remove_alarm_from_disk_by_id(&self, AlarmId { output_index, identifier }: &AlarmId) -> ScheduleSystemResult<()> {
+        let alarms_dir: String = alarms_dir_name(self.active_profile());
+
         let mut disk = self
             .disk
             .lock()
@@ -557,7 +2435,7 @@ impl ScheduleSystem {
             (
                 [
                     SYSTEM_DIR,
-                    ALARMS_DIR,
+                    alarms_dir.as_str(),
                     output_index.to_string().as_str()
                 ].as_slice(),
                 identifier.as_str()
@@ -570,6 +2448,8 @@ impl ScheduleSystem {
     }
 
     fn remove_alarm_from_disk_by_output_index(&self, output_index: u8) -> ScheduleSystemResult<()> {
+        let alarms_dir: String = alarms_dir_name(self.active_profile());
+
         let mut disk = self
             .disk
             .lock()
@@ -578,7 +2458,7 @@ impl ScheduleSystem {
         let path: DirectoryPath =
             [
                 SYSTEM_DIR,
-                ALARMS_DIR,
+                alarms_dir.as_str(),
                 output_index.to_string().as_str()
             ].as_slice().into();
 
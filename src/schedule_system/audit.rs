@@ -0,0 +1,186 @@
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+
+/* Previous hash of the first entry. */
+pub const AUDIT_GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuditAction {
+    Login,
+    Logout,
+    SessionsRevoked,
+    PasswordChanged,
+    AccessPointPasswordChanged,
+    PasswordPolicyChanged,
+    SetupCompleted,
+    UserAdded,
+    UserUpdated,
+    UserRemoved,
+    ApiKeyCreated,
+    ApiKeyRemoved,
+    ApiKeyUsed,
+    TlsCertificateChanged,
+    PasswordsReset,
+    FactoryReset,
+    WebUiChanged,
+    UsersRestored,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/**
+ * What happened, reported by caller. Secrets should never be part of details.
+ */
+#[derive(Clone, Debug)]
+pub struct AuditRecord {
+    pub actor: String,
+    pub action: AuditAction,
+    pub source_ip: Option<IpAddr>,
+    pub outcome: AuditOutcome,
+    pub details: String,
+}
+
+/**
+ * Stored record. Hash covers all fields and hash of previous entry,
+ * so changed, inserted or removed entry breaks the chain of all following entries.
+ */
+#[derive(Clone, Debug)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub timestamp_millis: i64,
+    pub record: AuditRecord,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    pub fn new(sequence: u64, timestamp_millis: i64, record: AuditRecord, previous_hash: String) -> Self {
+        let mut entry: AuditEntry = Self {
+            sequence,
+            timestamp_millis,
+            record,
+            previous_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        entry
+    }
+
+    pub fn compute_hash(&self) -> String {
+        let fields: [String; 8] = [
+            self.sequence.to_string(),
+            self.timestamp_millis.to_string(),
+            self.record.actor.clone(),
+            format!("{:?}", self.record.action),
+            self.record.source_ip.map(|ip| ip.to_string()).unwrap_or_default(),
+            format!("{:?}", self.record.outcome),
+            self.record.details.clone(),
+            self.previous_hash.clone(),
+        ];
+
+        /* length prefix keeps field boundaries unambiguous */
+        let mut hasher: Sha256 = Sha256::new();
+        for field in fields {
+            hasher.update(format!("{}:{field};", field.len()).as_bytes());
+        }
+
+        hex::encode(hasher.finalize())
+    }
+}
+
+/**
+ * Sequence and hash which next entry continues from.
+ * Day (YYYYMMDD) of last written file is kept, so entries are never written into older file when clock is set back.
+ */
+#[derive(Clone, Debug)]
+pub struct AuditChain {
+    pub next_sequence: u64,
+    pub last_hash: String,
+    pub last_day: String,
+}
+
+impl Default for AuditChain {
+    fn default() -> Self {
+        Self {
+            next_sequence: 0,
+            last_hash: AUDIT_GENESIS_HASH.to_string(),
+            last_day: String::new(),
+        }
+    }
+}
+
+impl AuditChain {
+    pub fn continue_from(head: &AuditChainHead, day: &str) -> Self {
+        Self {
+            next_sequence: head.sequence + 1,
+            last_hash: head.hash.clone(),
+            last_day: day.to_string(),
+        }
+    }
+}
+
+/**
+ * Sequence and hash of newest entry. It is stored in NVS, outside of SD card, because chain alone can be
+ * recomputed by anyone who removes or rewrites newest entries. Stored head reveals such change.
+ */
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditChainHead {
+    pub sequence: u64,
+    pub hash: String,
+}
+
+impl AuditChainHead {
+    pub fn of(entry: &AuditEntry) -> Self {
+        Self {
+            sequence: entry.sequence,
+            hash: entry.hash.clone(),
+        }
+    }
+}
+
+pub struct AuditFilter {
+    pub from_timestamp_millis: Option<i64>,
+    pub to_timestamp_millis: Option<i64>,
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub outcome: Option<AuditOutcome>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.from_timestamp_millis.map_or(true, |from| entry.timestamp_millis >= from) &&
+        self.to_timestamp_millis.map_or(true, |to| entry.timestamp_millis <= to) &&
+        self.actor.as_ref().map_or(true, |actor| &entry.record.actor == actor) &&
+        self.action.map_or(true, |action| entry.record.action == action) &&
+        self.outcome.map_or(true, |outcome| entry.record.outcome == outcome)
+    }
+}
+
+/**
+ * Entries sorted from newest to oldest.
+ */
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub has_more: bool,
+}
+
+/**
+ * Result of chain check. Oldest retained entry is trusted as anchor, because older files are rotated out.
+ */
+pub struct AuditVerification {
+    pub entries_count: u64,
+    pub first_sequence: Option<u64>,
+    pub last_sequence: Option<u64>,
+    /* Sequence of first entry which doesn't match its hash or previous entry. */
+    pub broken_at_sequence: Option<u64>,
+    pub unreadable_lines_count: u64,
+    /* Whether newest entry is the stored chain head. True when no head is stored yet. */
+    pub head_matches: bool,
+}
@@ -0,0 +1,9 @@
+use std::time::Instant;
+
+/**
+ * Text which is shown on display instead of time until it expires.
+ */
+pub struct DisplayMessage {
+    pub text: String,
+    pub until: Instant,
+}
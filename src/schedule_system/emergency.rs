@@ -0,0 +1,39 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EmergencyKind {
+    Lockdown,
+    Evacuation,
+}
+
+impl EmergencyKind {
+    /**
+     * Emergency can be replaced only by emergency with the same or higher priority.
+     */
+    pub fn priority(&self) -> u8 {
+        match self {
+            EmergencyKind::Lockdown => 1,
+            EmergencyKind::Evacuation => 2,
+        }
+    }
+
+    pub fn banner(&self) -> &'static str {
+        match self {
+            EmergencyKind::Lockdown => "LOCKDOWN",
+            EmergencyKind::Evacuation => "EVACUATION",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum EmergencySignal {
+    /* Outputs are kept high until emergency is cancelled. */
+    Continuous,
+    /* Outputs are switched on and off until emergency is cancelled. */
+    Intermittent { on_millis: u64, off_millis: u64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct Emergency {
+    pub kind: EmergencyKind,
+    pub output_indices: Vec<u8>,
+    pub signal: EmergencySignal,
+}
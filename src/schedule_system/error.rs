@@ -1,9 +1,12 @@
-use esp_idf_svc::sys::EspError;
+use interface::EspError;
 use interface::{ClockError, PathParseError};
 use std::fmt::{Debug, Display, Formatter};
-use display_interface::DisplayError;
+use display::display::DisplayError;
 use embedded_sdmmc::Error as DiskError;
 use embedded_sdmmc::sdcard::Error as SDCardError;
+use crate::security::error::SecurityError;
+use http_server::api_error::ApiError;
+use http_server::tls::TlsError;
 
 #[derive(Debug)]
 pub enum ScheduleSystemError {
@@ -16,10 +19,88 @@ pub enum ScheduleSystemError {
     PathParseError(PathParseError),
     SerdeError(serde_json::error::Error),
     MutexLockError,
+    OutputIndexOutOfBounds(u8),
+    ProfileOutOfBounds(u8),
+    InvalidInputConfig(String),
+    InvalidEmergency(String),
+    InvalidSettings(String),
+    InvalidSetup(String),
+    InvalidWebUiFile(String),
+    WebUiFileNotFound(String),
+    SetupAlreadyCompleted,
+    SecurityError(SecurityError),
+    TlsError(TlsError),
+    EmergencyPriorityTooLow,
+    EmergencyActive,
+    ThreadSpawnError(std::io::Error),
+    EventLogWriterAlreadyStarted,
 }
 
 impl Display for ScheduleSystemError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Schedule System Error: {:?}", self)
+        match self {
+            ScheduleSystemError::EspError(error) => write!(f, "{error}"),
+            ScheduleSystemError::I2cSharedBusError => f.write_str("Can't use shared I2C bus."),
+            ScheduleSystemError::AlarmIdParseError(message) => write!(f, "Invalid alarm id: {message}"),
+            ScheduleSystemError::ClockError(ClockError::AlarmNotFound) => f.write_str("Alarm not found."),
+            ScheduleSystemError::ClockError(ClockError::InvalidTimestamp(timestamp)) => write!(f, "Invalid timestamp {timestamp}."),
+            ScheduleSystemError::ClockError(error) => write!(f, "Clock error: {error:?}"),
+            ScheduleSystemError::DisplayError(error) => write!(f, "Display error: {error:?}"),
+            ScheduleSystemError::DiskError(error) => write!(f, "Disk error: {error:?}"),
+            ScheduleSystemError::PathParseError(error) => write!(f, "Invalid path: {error:?}"),
+            ScheduleSystemError::SerdeError(error) => write!(f, "Invalid data: {error}"),
+            ScheduleSystemError::MutexLockError => f.write_str("Could not lock mutex."),
+            ScheduleSystemError::OutputIndexOutOfBounds(output_index) => write!(f, "Output index {output_index} is out of bounds."),
+            ScheduleSystemError::ProfileOutOfBounds(profile) => write!(f, "Profile {profile} is out of bounds."),
+            ScheduleSystemError::InvalidInputConfig(message)
+            | ScheduleSystemError::InvalidEmergency(message)
+            | ScheduleSystemError::InvalidSettings(message)
+            | ScheduleSystemError::InvalidSetup(message)
+            | ScheduleSystemError::InvalidWebUiFile(message) => f.write_str(message),
+            ScheduleSystemError::WebUiFileNotFound(path) => write!(f, "Web interface file '{path}' not found."),
+            ScheduleSystemError::SetupAlreadyCompleted => f.write_str("Setup is already completed."),
+            ScheduleSystemError::SecurityError(error) => write!(f, "{error}"),
+            ScheduleSystemError::TlsError(error) => write!(f, "{error}"),
+            ScheduleSystemError::EmergencyPriorityTooLow => f.write_str("Emergency with higher priority is already active."),
+            ScheduleSystemError::EmergencyActive => f.write_str("Output can't be rung while emergency is active."),
+            ScheduleSystemError::ThreadSpawnError(error) => write!(f, "Can't spawn thread: {error}"),
+            ScheduleSystemError::EventLogWriterAlreadyStarted => f.write_str("Event log writer is already started."),
+        }
+    }
+}
+
+/**
+ * Status and code of API response. Errors of hardware and storage are server side, the rest are caused by request.
+ */
+impl From<ScheduleSystemError> for ApiError {
+    fn from(error: ScheduleSystemError) -> Self {
+        match error {
+            ScheduleSystemError::ClockError(ClockError::AlarmNotFound) => ApiError::not_found("alarm_not_found", error.to_string()),
+            ScheduleSystemError::ClockError(ClockError::InvalidTimestamp(_)) => ApiError::bad_request("invalid_timestamp", error.to_string()),
+            ScheduleSystemError::AlarmIdParseError(_) => ApiError::bad_request("invalid_alarm_id", error.to_string()),
+            ScheduleSystemError::PathParseError(_) => ApiError::bad_request("invalid_path", error.to_string()),
+            ScheduleSystemError::SerdeError(_) => ApiError::bad_request("invalid_data", error.to_string()),
+            ScheduleSystemError::OutputIndexOutOfBounds(_) => ApiError::bad_request("output_index_out_of_bounds", error.to_string()),
+            ScheduleSystemError::ProfileOutOfBounds(_) => ApiError::bad_request("profile_out_of_bounds", error.to_string()),
+            ScheduleSystemError::InvalidInputConfig(_) => ApiError::bad_request("invalid_input_config", error.to_string()),
+            ScheduleSystemError::InvalidEmergency(_) => ApiError::bad_request("invalid_emergency", error.to_string()),
+            ScheduleSystemError::InvalidSettings(_) => ApiError::bad_request("invalid_settings", error.to_string()),
+            ScheduleSystemError::InvalidSetup(_) => ApiError::bad_request("invalid_setup", error.to_string()),
+            ScheduleSystemError::InvalidWebUiFile(_) => ApiError::bad_request("invalid_web_ui_file", error.to_string()),
+            ScheduleSystemError::WebUiFileNotFound(_) => ApiError::not_found("web_ui_file_not_found", error.to_string()),
+            ScheduleSystemError::SetupAlreadyCompleted => ApiError::conflict("setup_already_completed", error.to_string()),
+            ScheduleSystemError::EmergencyPriorityTooLow => ApiError::conflict("emergency_priority_too_low", error.to_string()),
+            ScheduleSystemError::EmergencyActive => ApiError::conflict("emergency_active", error.to_string()),
+            ScheduleSystemError::DiskError(_) => ApiError::service_unavailable("disk_unavailable", error.to_string()),
+            ScheduleSystemError::SecurityError(error) => error.into(),
+            ScheduleSystemError::TlsError(error) => error.into(),
+            ScheduleSystemError::ClockError(_) => ApiError::internal_server_error("clock_error", error.to_string()),
+            ScheduleSystemError::EspError(_)
+            | ScheduleSystemError::I2cSharedBusError
+            | ScheduleSystemError::DisplayError(_)
+            | ScheduleSystemError::MutexLockError
+            | ScheduleSystemError::ThreadSpawnError(_)
+            | ScheduleSystemError::EventLogWriterAlreadyStarted => ApiError::internal_server_error("internal_error", error.to_string()),
+        }
     }
 }
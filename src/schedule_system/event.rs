@@ -0,0 +1,61 @@
+use clock::system_time;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventKind {
+    AlarmFired,
+    AlarmSkipped,
+    RingFailed,
+    ManualRing,
+    EmergencyStarted,
+    EmergencyStopped,
+    SilentModeChanged,
+    ProfileSwitched,
+    TimeChanged,
+    SettingsChanged,
+    AlarmsChanged,
+    InputsChanged,
+    Login,
+    LoginFailed,
+    LoginBlocked,
+}
+
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub timestamp_millis: i64,
+    pub kind: EventKind,
+    pub details: String,
+}
+
+impl Event {
+    pub fn now(kind: EventKind, details: String) -> Self {
+        Self {
+            timestamp_millis: system_time::now().as_millis() as i64,
+            kind,
+            details,
+        }
+    }
+}
+
+pub struct EventFilter {
+    pub from_timestamp_millis: Option<i64>,
+    pub to_timestamp_millis: Option<i64>,
+    pub kind: Option<EventKind>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &Event) -> bool {
+        self.from_timestamp_millis.map_or(true, |from| event.timestamp_millis >= from) &&
+        self.to_timestamp_millis.map_or(true, |to| event.timestamp_millis <= to) &&
+        self.kind.map_or(true, |kind| event.kind == kind)
+    }
+}
+
+/**
+ * Events sorted from newest to oldest.
+ */
+pub struct EventPage {
+    pub events: Vec<Event>,
+    pub has_more: bool,
+}
@@ -0,0 +1,21 @@
+use crate::schedule_system::emergency::Emergency;
+use crate::schedule_system::ring_pattern::RingPattern;
+use interface::gpio::{Level, Pull};
+
+#[derive(Clone, Debug)]
+pub enum InputAction {
+    RingOutput { output_index: u8, pattern: RingPattern },
+    StartEmergency { emergency: Emergency },
+    StopEmergency,
+    ToggleSilentMode,
+    SwitchProfile { profile: u8 },
+}
+
+#[derive(Clone, Debug)]
+pub struct InputConfig {
+    pub gpio: i32,
+    pub pull: Pull,
+    pub active_level: Level,
+    pub debounce_millis: u64,
+    pub action: InputAction,
+}
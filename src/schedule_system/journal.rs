@@ -0,0 +1,134 @@
+use crate::constant::SYSTEM_DIR;
+use disk::disk::Disk;
+use interface::disk::path::directory_path::DirectoryPath;
+use interface::disk::path::file_path::FilePath;
+use interface::disk::{DiskResult, ReadDisk, WriteDisk};
+use std::sync::Mutex;
+
+/**
+ * Append-only log of text lines stored in a directory inside SYSTEM_DIR.
+ * Every day has its own files named YYYYMMDD.NNN. NNN is incremented when file exceeds maximum size.
+ * Oldest files are deleted when files count exceeds the limit.
+ */
+pub struct Journal {
+    directory: &'static str,
+    max_file_bytes: u32,
+    max_files: usize,
+    /* File which lines are appended to, so directory isn't listed on every line. */
+    current_file: Mutex<Option<CurrentFile>>,
+}
+
+struct CurrentFile {
+    name: String,
+    length: u32,
+}
+
+impl Journal {
+    pub const fn new(directory: &'static str, max_file_bytes: u32, max_files: usize) -> Self {
+        Self { directory, max_file_bytes, max_files, current_file: Mutex::new(None) }
+    }
+
+    pub fn init(&self, disk: &mut Disk) -> DiskResult<()> {
+        disk.make_dir(&self.directory_path())
+    }
+
+    /**
+     * Day should be formatted as YYYYMMDD.
+     */
+    pub fn append(&self, disk: &mut Disk, day: &str, line: &str) -> DiskResult<()> {
+        /* cached file is taken below, so state left by panicked append is never used */
+        let mut current_file = self.current_file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        /* directory is listed only on start and when day changes */
+        let last_file: Option<CurrentFile> =
+            match current_file.take() {
+                Some(current_file) if Journal::file_day(&current_file.name) == day => Some(current_file),
+                _ => self.last_file_of_day(disk, day)?,
+            };
+
+        let data: String = format!("{line}\n");
+
+        let (file_name, length, is_new_file): (String, u32, bool) =
+            match last_file {
+                None => (format!("{day}.000"), 0, true),
+                Some(last_file) if last_file.length as usize + data.len() > self.max_file_bytes as usize => {
+                    (Journal::next_file_name(day, &last_file.name), 0, true)
+                }
+                Some(last_file) => (last_file.name, last_file.length, false),
+            };
+
+        /* on failure file is listed again on next line, because length of partially written file is unknown */
+        disk.append_to_file(&self.file_path(&file_name), data.as_bytes())?;
+
+        if is_new_file {
+            let file_names: Vec<String> = self.file_names(disk)?;
+            self.remove_oldest_files(disk, file_names)?;
+        }
+
+        *current_file = Some(CurrentFile { name: file_name, length: length + data.len() as u32 });
+
+        Ok(())
+    }
+
+    /**
+     * Names of journal files sorted from oldest to newest.
+     */
+    pub fn file_names(&self, disk: &mut Disk) -> DiskResult<Vec<String>> {
+        let mut file_names: Vec<String> = disk.list_files(&self.directory_path())?;
+        file_names.sort();
+
+        Ok(file_names)
+    }
+
+    pub fn read_file(&self, disk: &mut Disk, file_name: &str) -> DiskResult<Vec<u8>> {
+        disk.read_from_file(&self.file_path(file_name))
+    }
+
+    /**
+     * Day (YYYYMMDD) which file belongs to.
+     */
+    pub fn file_day(file_name: &str) -> &str {
+        file_name
+            .split_once('.')
+            .map_or(file_name, |(day, _)| day)
+    }
+
+    fn last_file_of_day(&self, disk: &mut Disk, day: &str) -> DiskResult<Option<CurrentFile>> {
+        let Some(name) = self.file_names(disk)?.into_iter().rev().find(|file_name| file_name.starts_with(day)) else {
+            return Ok(None);
+        };
+
+        let length: u32 = disk.file_length(&self.file_path(&name))?;
+
+        Ok(Some(CurrentFile { name, length }))
+    }
+
+    fn remove_oldest_files(&self, disk: &mut Disk, file_names: Vec<String>) -> DiskResult<()> {
+        let removable_count: usize = file_names.len().saturating_sub(self.max_files);
+
+        for file_name in file_names.iter().take(removable_count) {
+            disk.delete_file(&self.file_path(file_name))?;
+        }
+
+        Ok(())
+    }
+
+    fn next_file_name(day: &str, last_file_name: &str) -> String {
+        let index: u16 = last_file_name
+            .split_once('.')
+            .and_then(|(_, index)| index.parse().ok())
+            .unwrap_or(0);
+
+        format!("{day}.{:03}", index + 1)
+    }
+
+    fn directory_path(&self) -> DirectoryPath {
+        [SYSTEM_DIR, self.directory].as_slice().into()
+    }
+
+    fn file_path(&self, file_name: &str) -> FilePath {
+        ([SYSTEM_DIR, self.directory].as_slice(), file_name).into()
+    }
+}
@@ -0,0 +1,9 @@
+#[derive(Clone, Debug)]
+pub struct RingPattern {
+    /* Length of each impulse in milliseconds. */
+    pub impulse_length_millis: u64,
+    /* Pause between impulses in milliseconds. */
+    pub pause_length_millis: u64,
+    /* Count of impulses. */
+    pub repeat: u8,
+}
@@ -0,0 +1,160 @@
+use crate::constant::{ACCESS_POINT_SSID, ALARM_MATCH_CHECK_INTERVAL_MS, CORS_METHODS, DEVICE_INFO_DISPLAY_SECONDS, DISPLAY_REFRESH_INTERVAL_MS, MAX_REQUEST_BODY_BYTES, RESET_BUTTON_FACTORY_HOLD_SECONDS, RESET_BUTTON_PASSWORDS_HOLD_SECONDS, SETTINGS_SCHEMA_VERSION};
+use crate::security::session::SessionStoreKind;
+
+/* Maximum SSID length defined by 802.11. */
+const ACCESS_POINT_SSID_MAX_LENGTH: usize = 32;
+
+/* Reset button has to be released within this time, otherwise stuck button could reset device. */
+const RESET_BUTTON_MAX_HOLD_SECONDS: u64 = 60;
+
+/* Body is kept in memory while it is parsed, so limit can't be raised much. */
+const MAX_REQUEST_BODY_BYTES_RANGE: std::ops::RangeInclusive<u32> = 1024..=64 * 1024;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settings {
+    pub schema_version: u32,
+    pub access_point_ssid: String,
+    pub alarm_match_check_interval_ms: u64,
+    pub display_refresh_interval_ms: u64,
+    pub device_info_display_seconds: u64,
+    pub reset_button_passwords_hold_seconds: u64,
+    pub reset_button_factory_hold_seconds: u64,
+    /* origins like "http://192.168.71.2:8080" or "*" */
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allowed_methods: Vec<String>,
+    pub cors_allow_credentials: bool,
+    pub max_request_body_bytes: u32,
+    /* applied after restart, because server is started on boot */
+    pub https_enabled: bool,
+    pub https_redirect_enabled: bool,
+    /* clients have to log in again when it's changed */
+    pub session_store: SessionStoreKind,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            access_point_ssid: ACCESS_POINT_SSID.to_string(),
+            alarm_match_check_interval_ms: ALARM_MATCH_CHECK_INTERVAL_MS,
+            display_refresh_interval_ms: DISPLAY_REFRESH_INTERVAL_MS,
+            device_info_display_seconds: DEVICE_INFO_DISPLAY_SECONDS,
+            reset_button_passwords_hold_seconds: RESET_BUTTON_PASSWORDS_HOLD_SECONDS,
+            reset_button_factory_hold_seconds: RESET_BUTTON_FACTORY_HOLD_SECONDS,
+            cors_allowed_origins: vec![],
+            cors_allowed_methods: CORS_METHODS.iter().map(|method| method.to_string()).collect(),
+            cors_allow_credentials: false,
+            max_request_body_bytes: MAX_REQUEST_BODY_BYTES,
+            https_enabled: true,
+            https_redirect_enabled: true,
+            session_store: SessionStoreKind::default(),
+        }
+    }
+}
+
+impl Settings {
+    /**
+     * Returns description of the first invalid setting.
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        if self.access_point_ssid.is_empty() || self.access_point_ssid.len() > ACCESS_POINT_SSID_MAX_LENGTH {
+            return Err(format!("Access point SSID should be 1-{ACCESS_POINT_SSID_MAX_LENGTH} bytes long."));
+        }
+
+        /* alarms are matched by seconds, so interval should be shorter than one second */
+        if !(50..=900).contains(&self.alarm_match_check_interval_ms) {
+            return Err("Alarm match check interval should be 50-900ms.".to_string());
+        }
+
+        if !(50..=5000).contains(&self.display_refresh_interval_ms) {
+            return Err("Display refresh interval should be 50-5000ms.".to_string());
+        }
+
+        if !(1..=300).contains(&self.device_info_display_seconds) {
+            return Err("Device info display time should be 1-300s.".to_string());
+        }
+
+        if !(1..=RESET_BUTTON_MAX_HOLD_SECONDS).contains(&self.reset_button_passwords_hold_seconds) {
+            return Err(format!("Password reset hold time should be 1-{RESET_BUTTON_MAX_HOLD_SECONDS}s."));
+        }
+
+        if self.reset_button_factory_hold_seconds > RESET_BUTTON_MAX_HOLD_SECONDS {
+            return Err(format!("Factory reset hold time should be at most {RESET_BUTTON_MAX_HOLD_SECONDS}s."));
+        }
+
+        if self.reset_button_factory_hold_seconds <= self.reset_button_passwords_hold_seconds {
+            return Err("Factory reset hold time should be longer than password reset hold time.".to_string());
+        }
+
+        if let Some(origin) = self.cors_allowed_origins.iter().find(|origin| !Settings::is_valid_cors_origin(origin)) {
+            return Err(format!("CORS origin '{origin}' should be '*' or scheme and host without path, e.g. 'http://192.168.71.2:8080'."));
+        }
+
+        if let Some(method) = self.cors_allowed_methods.iter().find(|method| !CORS_METHODS.contains(&method.as_str())) {
+            return Err(format!("CORS method '{method}' should be one of {}.", CORS_METHODS.join(", ")));
+        }
+
+        if !MAX_REQUEST_BODY_BYTES_RANGE.contains(&self.max_request_body_bytes) {
+            return Err(format!(
+                "Maximum request body size should be {}-{} bytes.",
+                MAX_REQUEST_BODY_BYTES_RANGE.start(),
+                MAX_REQUEST_BODY_BYTES_RANGE.end(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn is_valid_cors_origin(origin: &str) -> bool {
+        if origin == "*" {
+            return true;
+        }
+
+        let host: Option<&str> = origin
+            .strip_prefix("http://")
+            .or_else(|| origin.strip_prefix("https://"));
+
+        host.is_some_and(|host| !host.is_empty() && !host.contains(['/', '?', '#', ' ']))
+    }
+}
+
+/**
+ * Partial update of settings. Fields which are None stay unchanged.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct SettingsPatch {
+    pub access_point_ssid: Option<String>,
+    pub alarm_match_check_interval_ms: Option<u64>,
+    pub display_refresh_interval_ms: Option<u64>,
+    pub device_info_display_seconds: Option<u64>,
+    pub reset_button_passwords_hold_seconds: Option<u64>,
+    pub reset_button_factory_hold_seconds: Option<u64>,
+    pub cors_allowed_origins: Option<Vec<String>>,
+    pub cors_allowed_methods: Option<Vec<String>>,
+    pub cors_allow_credentials: Option<bool>,
+    pub max_request_body_bytes: Option<u32>,
+    pub https_enabled: Option<bool>,
+    pub https_redirect_enabled: Option<bool>,
+    pub session_store: Option<SessionStoreKind>,
+}
+
+impl SettingsPatch {
+    pub fn apply_to(self, settings: &Settings) -> Settings {
+        Settings {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            access_point_ssid: self.access_point_ssid.unwrap_or_else(|| settings.access_point_ssid.clone()),
+            alarm_match_check_interval_ms: self.alarm_match_check_interval_ms.unwrap_or(settings.alarm_match_check_interval_ms),
+            display_refresh_interval_ms: self.display_refresh_interval_ms.unwrap_or(settings.display_refresh_interval_ms),
+            device_info_display_seconds: self.device_info_display_seconds.unwrap_or(settings.device_info_display_seconds),
+            reset_button_passwords_hold_seconds: self.reset_button_passwords_hold_seconds.unwrap_or(settings.reset_button_passwords_hold_seconds),
+            reset_button_factory_hold_seconds: self.reset_button_factory_hold_seconds.unwrap_or(settings.reset_button_factory_hold_seconds),
+            cors_allowed_origins: self.cors_allowed_origins.unwrap_or_else(|| settings.cors_allowed_origins.clone()),
+            cors_allowed_methods: self.cors_allowed_methods.unwrap_or_else(|| settings.cors_allowed_methods.clone()),
+            cors_allow_credentials: self.cors_allow_credentials.unwrap_or(settings.cors_allow_credentials),
+            max_request_body_bytes: self.max_request_body_bytes.unwrap_or(settings.max_request_body_bytes),
+            https_enabled: self.https_enabled.unwrap_or(settings.https_enabled),
+            https_redirect_enabled: self.https_redirect_enabled.unwrap_or(settings.https_redirect_enabled),
+            session_store: self.session_store.unwrap_or(settings.session_store),
+        }
+    }
+}
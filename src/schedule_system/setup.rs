@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+
+/**
+ * First-run configuration. Device name is used as access point SSID.
+ */
+pub struct Setup {
+    pub username: String,
+    pub user_password: String,
+    pub access_point_password: String,
+    pub device_name: String,
+    pub datetime: DateTime<Utc>,
+}
@@ -13,7 +13,7 @@ impl ToAlarmsWithId for HashMap<AlarmId, Alarm> {
         self
             .into_iter()
             .fold(Vec::with_capacity(alarms_count), |mut accumulator, (alarm_id, alarm)| {
-                let alarm_with_id_dto: AlarmWithIdDTO = (alarm_id.into(), alarm.into()).into();
+                let alarm_with_id_dto: AlarmWithIdDTO = (alarm_id, alarm).into();
 
                 accumulator.push(alarm_with_id_dto);
                 accumulator
@@ -0,0 +1,97 @@
+use crate::constant::WEB_UI_WRITE_BUFFER_BYTES;
+use crate::schedule_system::error::ScheduleSystemError;
+use crate::schedule_system::ScheduleSystem;
+use interface::disk::path::file_path::FilePath;
+
+/**
+ * File of web interface. Path is relative to web interface directory, e.g. "gz/app.js".
+ */
+pub struct WebUiFile {
+    pub path: String,
+    pub size: u32,
+}
+
+/**
+ * Segments of path relative to web interface directory. Leading and trailing slashes are ignored.
+ * Segments can't be "." or "..", so files outside of web interface directory can't be reached.
+ */
+pub fn parse_web_ui_path(path: &str) -> Result<Vec<&str>, ScheduleSystemError> {
+    let segments: Vec<&str> = path
+        .trim_matches('/')
+        .split('/')
+        .collect();
+
+    if segments.iter().any(|segment| segment.is_empty() || *segment == "." || *segment == "..") {
+        return Err(ScheduleSystemError::InvalidWebUiFile(format!("Invalid path '{path}'.")));
+    }
+
+    Ok(segments)
+}
+
+/**
+ * Writes uploaded files into staging slot of web interface. Data is buffered, so disk is written in large pieces.
+ */
+pub struct WebUiWriter<'a> {
+    schedule_system: &'a ScheduleSystem,
+    file: Option<FilePath>,
+    buffer: Vec<u8>,
+}
+
+impl<'a> WebUiWriter<'a> {
+    pub fn new(schedule_system: &'a ScheduleSystem) -> Self {
+        Self { schedule_system, file: None, buffer: Vec::with_capacity(WEB_UI_WRITE_BUFFER_BYTES) }
+    }
+
+    pub fn make_dir(&mut self, path: &str) -> Result<(), ScheduleSystemError> {
+        self.schedule_system.make_web_ui_staging_dir(path)
+    }
+
+    /**
+     * Previous file is closed, so files are written one after another.
+     */
+    pub fn create_file(&mut self, path: &str) -> Result<(), ScheduleSystemError> {
+        self.close_file()?;
+
+        self.file = Some(self.schedule_system.create_web_ui_staging_file(path)?);
+
+        Ok(())
+    }
+
+    pub fn write(&mut self, mut data: &[u8]) -> Result<(), ScheduleSystemError> {
+        if self.file.is_none() {
+            return Err(ScheduleSystemError::InvalidWebUiFile("Data doesn't belong to any file.".to_string()));
+        }
+
+        while !data.is_empty() {
+            let length: usize = data.len().min(WEB_UI_WRITE_BUFFER_BYTES - self.buffer.len());
+            self.buffer.extend_from_slice(&data[..length]);
+            data = &data[length..];
+
+            if self.buffer.len() == WEB_UI_WRITE_BUFFER_BYTES {
+                self.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn close_file(&mut self) -> Result<(), ScheduleSystemError> {
+        self.flush()?;
+        self.file = None;
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ScheduleSystemError> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+
+        if !self.buffer.is_empty() {
+            self.schedule_system.append_to_file(file, &self.buffer)?;
+            self.buffer.clear();
+        }
+
+        Ok(())
+    }
+}
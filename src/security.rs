@@ -1,62 +1,317 @@
+pub mod api_key;
 pub mod error;
-
-use std::sync::{Arc, RwLock};
+pub mod login_guard;
+pub mod password;
+pub mod password_policy;
+pub mod session;
+pub mod user;
+
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock, RwLockReadGuard};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs, EspNvsPartition, NvsDefault};
-use esp_idf_svc::sys::EspError;
-use rand::distributions::Alphanumeric;
-use rand::{thread_rng, Rng};
+use interface::EspError;
+use clock::system_time;
+#[cfg(target_os = "espidf")]
 use synchronized::synchronized;
+#[cfg(not(target_os = "espidf"))]
+use std::collections::BTreeMap;
+use crate::constant::{API_KEYS_MAX_COUNT, API_KEY_LAST_USED_PERSIST_SECONDS, USERS_MAX_COUNT};
+use crate::model::auth::api_key::StoredApiKeyDTO;
+use crate::schedule_system::audit::{AuditAction, AuditChainHead, AuditOutcome, AuditRecord};
+use crate::model::auth::password_policy::PasswordPolicyDTO;
+use crate::model::auth::user::StoredUserDTO;
+use crate::security::api_key::{validate_api_key_name, ApiKey};
 use crate::security::error::SecurityError;
-
+use crate::security::login_guard::{LoginGuard, LoginLockout};
+use crate::security::password::{constant_time_eq, hash_password, is_password_hash, simulate_password_verification, verify_password};
+use crate::security::password_policy::PasswordPolicy;
+use crate::security::session::memory_session_store::MemorySessionStore;
+use crate::security::session::signed_token_store::SignedTokenStore;
+use crate::security::session::{Session, SessionStore, SessionStoreKind};
+use crate::security::user::{validate_username, Permission, Role, User, ADMIN_USERNAME};
+
+#[cfg(target_os = "espidf")]
 const NVS_NAMESPACE: &str = "secure";
-const ACCESS_TOKEN_LENGTH: usize = 256;
 
 const WIFI_PASSWORD_KEY: &str = "wifi_password";
 const WIFI_DEFAULT_PASSWORD: &str = "scheduler-rs"; /* should be minimum 8 chars */
 
-const USER_PASSWORD_KEY: &str = "api_password";
+/* Users are stored as JSON array of StoredUserDTO. */
+const USERS_KEY: &str = "users";
+/* Single password of older firmware. It is migrated into admin account. */
+const LEGACY_USER_PASSWORD_KEY: &str = "api_password";
 const USER_DEFAULT_PASSWORD: &str = "scheduler-rs";
 
+/* API keys are stored as JSON array of StoredApiKeyDTO. */
+const API_KEYS_KEY: &str = "api_keys";
+
+/* Stored as JSON of PasswordPolicyDTO. Default policy is used when it's missing. */
+const PASSWORD_POLICY_KEY: &str = "pw_policy";
+
+/* Head of audit chain is stored as "<sequence>:<hash>". */
+const AUDIT_CHAIN_HEAD_KEY: &str = "audit_head";
+
+/* Actor of audit entries recorded by security context itself. */
+const AUDIT_ACTOR: &str = "system";
+
 
-static mut SECURITY_CONTEXT: Option<SecurityContext> = None;
+/* NVS of host build, it is kept in memory until process exits. */
+#[cfg(not(target_os = "espidf"))]
+static MOCK_NVS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+static SECURITY_CONTEXT: OnceLock<SecurityContext> = OnceLock::new();
+/* Creation of context can fail, so it is guarded by mutex instead of being created inside OnceLock. */
+static SECURITY_CONTEXT_INIT: Mutex<()> = Mutex::new(());
 
 pub type SecurityResult<T> = Result<T, SecurityError>;
 
+type AuditListener = Box<dyn Fn(AuditRecord) + Send + Sync>;
+
 pub struct SecurityContext {
-    access_token: Arc<RwLock<String>>,
+    /* Store is selected by settings. Access tokens of previous store are dropped when it's changed. */
+    sessions: RwLock<Box<dyn SessionStore>>,
+    session_store_kind: Mutex<SessionStoreKind>,
+    /* Cached copy of users stored in NVS. */
+    users: RwLock<Vec<User>>,
+    login_guard: LoginGuard,
+    /* True until default passwords of access point and all users are changed. */
+    setup_required: AtomicBool,
+    password_policy: RwLock<PasswordPolicy>,
+    /* Cached copy of API keys stored in NVS. Last used time may be newer than stored one. */
+    api_keys: RwLock<Vec<ApiKey>>,
+    /* Receives security events which aren't visible to callers, e.g. use of API key. */
+    audit_listener: RwLock<Option<AuditListener>>,
+    /* Events which happened before listener was set, e.g. while context was created. */
+    pending_audit_records: Mutex<Vec<AuditRecord>>,
 }
 
 impl SecurityContext {
     pub fn get() -> Result<&'static SecurityContext, EspError> {
-        let access_token: String = Self::generate_access_token();
+        if let Some(security_context) = SECURITY_CONTEXT.get() {
+            return Ok(security_context);
+        }
 
-        let this: Self = Self {
-            access_token: Arc::new(RwLock::new(access_token)),
-        };
+        let _init_guard = SECURITY_CONTEXT_INIT
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-        unsafe {
-            Ok(SECURITY_CONTEXT.get_or_insert(this))
+        /* other thread could create context while this one was waiting */
+        if let Some(security_context) = SECURITY_CONTEXT.get() {
+            return Ok(security_context);
+        }
+
+        let security_context: SecurityContext = SecurityContext::new()?;
+
+        Ok(SECURITY_CONTEXT.get_or_init(|| security_context))
+    }
+
+    fn new() -> Result<Self, EspError> {
+        let sessions: Box<dyn SessionStore> = SecurityContext::new_session_store(SessionStoreKind::default())?;
+
+        let mut pending_audit_records: Vec<AuditRecord> = vec![];
+
+        /* device would be locked for everyone, so admin with default password is restored and setup is required again */
+        let users: Vec<User> =
+            match SecurityContext::read_users()? {
+                Some(users) => users,
+                None => {
+                    log::warn!("Restoring '{ADMIN_USERNAME}' account with default password.");
+                    let users: Vec<User> = vec![SecurityContext::default_admin()];
+                    SecurityContext::write_users(&users)?;
+
+                    pending_audit_records.push(AuditRecord {
+                        actor: AUDIT_ACTOR.to_string(),
+                        action: AuditAction::UsersRestored,
+                        source_ip: None,
+                        outcome: AuditOutcome::Success,
+                        details: format!("Stored users are corrupted. '{ADMIN_USERNAME}' account restored with default password."),
+                    });
+
+                    users
+                }
+            };
+
+        let setup_required: bool = SecurityContext::has_default_password(&users)?;
+        let password_policy: PasswordPolicy = SecurityContext::read_password_policy()?;
+        let api_keys: Vec<ApiKey> = SecurityContext::read_api_keys()?;
+
+        Ok(Self {
+            sessions: RwLock::new(sessions),
+            session_store_kind: Mutex::new(SessionStoreKind::default()),
+            users: RwLock::new(users),
+            login_guard: LoginGuard::new(),
+            setup_required: AtomicBool::new(setup_required),
+            password_policy: RwLock::new(password_policy),
+            api_keys: RwLock::new(api_keys),
+            audit_listener: RwLock::new(None),
+            pending_audit_records: Mutex::new(pending_audit_records),
+        })
+    }
+
+    fn new_session_store(kind: SessionStoreKind) -> Result<Box<dyn SessionStore>, EspError> {
+        match kind {
+            SessionStoreKind::Memory => Ok(Box::new(MemorySessionStore::new())),
+            SessionStoreKind::SignedToken => Ok(Box::new(SignedTokenStore::new()?)),
         }
     }
 
     /**
-     * Check if provided credentials are correct before giving an access token.
-     * Right using only password, because this system only should have one user, but keeping username parameter for future improvements (e.g. JWT token generation)
+     * Replace store of access tokens. All clients have to log in again, because tokens of previous store aren't valid.
      */
-    pub fn get_access_token(&self, _username: &str, password: &str) -> SecurityResult<String> {
-        let actual_password: String = SecurityContext::nvs_read_str(USER_PASSWORD_KEY)
-            .map_err(SecurityError::EspError)?
-            .unwrap_or(String::from(USER_DEFAULT_PASSWORD));
+    pub fn set_session_store(&self, kind: SessionStoreKind) -> SecurityResult<()> {
+        let mut session_store_kind = self.session_store_kind
+            .lock()
+            .map_err(|_| SecurityError::WriteLockError)?;
 
-        if password != actual_password {
-            return Err(SecurityError::WrongCredentials);
+        if *session_store_kind == kind {
+            return Ok(());
         }
 
-        let access_token = self.access_token
+        let sessions: Box<dyn SessionStore> = SecurityContext::new_session_store(kind)
+            .map_err(SecurityError::EspError)?;
+
+        *self.sessions
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)? = sessions;
+        *session_store_kind = kind;
+
+        log::info!("Session store changed to {kind:?}.");
+
+        Ok(())
+    }
+
+    fn sessions(&self) -> SecurityResult<RwLockReadGuard<'_, Box<dyn SessionStore>>> {
+        self.sessions
             .read()
-            .map_err(|_| SecurityError::ReadLockError)?;
+            .map_err(|_| SecurityError::ReadLockError)
+    }
+
+    /**
+     * Check if provided credentials are correct before issuing a new access token.
+     * Credentials aren't checked while client is locked out after too many failed attempts.
+     */
+    pub fn get_access_token(&self, username: &str, password: &str, client_ip: Option<IpAddr>) -> SecurityResult<String> {
+        if let Some(lockout) = self.login_guard.lockout(client_ip)? {
+            return Err(SecurityError::LoginLocked(lockout));
+        }
+
+        let password_hash: String =
+            match self.get_user(username) {
+                Ok(user) if verify_password(password, &user.password_hash) => user.password_hash,
+                Ok(_) => {
+                    self.login_guard.record_failure(client_ip)?;
+                    return Err(SecurityError::WrongCredentials);
+                }
+                Err(_) => {
+                    /* unknown user takes the same time as wrong password, so response time doesn't reveal existing usernames */
+                    simulate_password_verification(password);
+                    self.login_guard.record_failure(client_ip)?;
+                    return Err(SecurityError::WrongCredentials);
+                }
+            };
+
+        self.login_guard.record_success(client_ip)?;
+
+        if !is_password_hash(&password_hash) {
+            log::info!("Migrating plaintext password of user '{username}' to hash.");
+
+            /* old password is kept even if it doesn't satisfy policy */
+            self.modify_users(|users| {
+                if let Some(user) = users.iter_mut().find(|user| user.username == username) {
+                    SecurityContext::set_password(user, password);
+                }
+
+                Ok(())
+            })?;
+        }
+
+        self.sessions()?.issue(username)
+    }
+
+    pub fn login_lockout(&self, client_ip: Option<IpAddr>) -> SecurityResult<Option<LoginLockout>> {
+        self.login_guard.lockout(client_ip)
+    }
+
+    /**
+     * Access token can be either token of session or API key.
+     */
+    pub fn authenticate(&self, access_token: &str, client_ip: Option<IpAddr>) -> SecurityResult<Session> {
+        match ApiKey::parse(access_token) {
+            Some((id, secret)) => self.authenticate_api_key(id, secret, client_ip),
+            None => self.sessions()?.validate(access_token),
+        }
+    }
+
+    /**
+     * Check permission of authenticated session. Role is checked on every request,
+     * so role changes and removed users take effect immediately. API key needs permission in its scopes too.
+     */
+    pub fn authorize(&self, session: &Session, permission: Permission) -> SecurityResult<()> {
+        let user: User = self
+            .get_user(&session.username)
+            .map_err(|_| SecurityError::InvalidAccessToken)?;
+
+        if !user.role.has_permission(permission) || !session.has_scope(permission) {
+            return Err(SecurityError::PermissionDenied);
+        }
+
+        Ok(())
+    }
+
+    pub fn revoke_access_token(&self, access_token: &str) -> SecurityResult<()> {
+        self.sessions()?.revoke(access_token)
+    }
+
+    pub fn revoke_all_access_tokens(&self) -> SecurityResult<()> {
+        self.sessions()?.revoke_all()
+    }
+
+    pub fn revoke_user_access_tokens(&self, username: &str) -> SecurityResult<()> {
+        self.sessions()?.revoke_user(username)
+    }
+
+    /**
+     * Current password is required, so stolen access token isn't enough to take over account.
+     * Other sessions of user are revoked. Returns new access token for client which changed password.
+     */
+    pub fn change_user_password(&self, username: &str, current_password: &str, new_password: &str, client_ip: Option<IpAddr>) -> SecurityResult<String> {
+        self.verify_current_password(username, current_password, client_ip)?;
+        self.set_user_password(username, new_password)?;
+
+        self.sessions()?.issue(username)
+    }
+
+    /**
+     * Password of logged in user is required to change access point password.
+     */
+    pub fn change_access_point_password(&self, username: &str, current_password: &str, new_password: &str, client_ip: Option<IpAddr>) -> SecurityResult<()> {
+        self.verify_current_password(username, current_password, client_ip)?;
+
+        self.get_password_policy()?
+            .check_access_point_password(new_password)
+            .map_err(SecurityError::WeakPassword)?;
 
-        Ok(access_token.clone())
+        self.set_access_point_password(new_password).map_err(SecurityError::EspError)
+    }
+
+    /**
+     * Failed attempts are counted together with failed logins, so current password can't be guessed with stolen token.
+     */
+    fn verify_current_password(&self, username: &str, password: &str, client_ip: Option<IpAddr>) -> SecurityResult<()> {
+        if let Some(lockout) = self.login_guard.lockout(client_ip)? {
+            return Err(SecurityError::LoginLocked(lockout));
+        }
+
+        let user: User = self.get_user(username)?;
+
+        if !verify_password(password, &user.password_hash) {
+            self.login_guard.record_failure(client_ip)?;
+            return Err(SecurityError::WrongCredentials);
+        }
+
+        self.login_guard.record_success(client_ip)
     }
 
     pub fn get_access_point_password(&self) -> Result<String, EspError> {
@@ -67,44 +322,641 @@ impl SecurityContext {
     }
 
     pub fn set_access_point_password(&self, new_password: &str) -> Result<(), EspError> {
-        SecurityContext::nvs_write_str(WIFI_PASSWORD_KEY, new_password)
+        SecurityContext::nvs_write_str(WIFI_PASSWORD_KEY, new_password)?;
+        self.refresh_setup_state()
     }
 
     pub fn reset_access_point_password(&self) -> Result<(), EspError> {
-        SecurityContext::nvs_write_str(WIFI_PASSWORD_KEY, WIFI_DEFAULT_PASSWORD)
+        self.set_access_point_password(WIFI_DEFAULT_PASSWORD)
     }
 
-    pub fn set_api_password(&self, new_password: &str) -> Result<(), EspError> {
-        SecurityContext::nvs_write_str(USER_PASSWORD_KEY, new_password)
+    /**
+     * Access point password is stored as plaintext, because Wi-Fi driver and reset button need the original value.
+     */
+    pub fn is_valid_wifi_password(&self, password: &str) -> Result<bool, EspError> {
+        let actual_password: String = self.get_access_point_password()?;
+
+        Ok(constant_time_eq(password, &actual_password))
     }
+}
 
-    pub fn reset_api_password(&self) -> Result<(), EspError> {
-        SecurityContext::nvs_write_str(USER_PASSWORD_KEY, USER_DEFAULT_PASSWORD)
+/* first-run setup */
+impl SecurityContext {
+    /**
+     * API is locked until default passwords are changed, except of login and setup.
+     */
+    pub fn is_setup_required(&self) -> bool {
+        self.setup_required.load(Ordering::SeqCst)
     }
 
-    pub fn is_valid_wifi_password(&self, password: &str) -> Result<bool, EspError> {
-        let actual_password: String = self.get_access_point_password()?;
+    /**
+     * Set password of user and access point together. Access point password is restored when user password can't be set.
+     */
+    pub fn complete_setup(&self, username: &str, user_password: &str, access_point_password: &str) -> SecurityResult<()> {
+        if constant_time_eq(user_password, USER_DEFAULT_PASSWORD) ||
+           constant_time_eq(access_point_password, WIFI_DEFAULT_PASSWORD) {
+            return Err(SecurityError::InvalidUser("Default passwords can't be used.".to_string()));
+        }
+
+        let password_policy: PasswordPolicy = self.get_password_policy()?;
+
+        password_policy
+            .check_user_password(user_password, username)
+            .map_err(SecurityError::WeakPassword)?;
+
+        password_policy
+            .check_access_point_password(access_point_password)
+            .map_err(SecurityError::WeakPassword)?;
+
+        let previous_access_point_password: String = self
+            .get_access_point_password()
+            .map_err(SecurityError::EspError)?;
+
+        self.set_access_point_password(access_point_password)
+            .map_err(SecurityError::EspError)?;
+
+        if let Err(error) = self.set_user_password(username, user_password) {
+            let _ = self.set_access_point_password(&previous_access_point_password);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    fn refresh_setup_state(&self) -> Result<(), EspError> {
+        let has_default_password: bool =
+            match self.users.read() {
+                Ok(users) => SecurityContext::has_default_password(&users)?,
+                Err(_) => true,
+            };
+
+        self.setup_required.store(has_default_password, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    fn has_default_password(users: &[User]) -> Result<bool, EspError> {
+        let access_point_password: String = SecurityContext::nvs_read_str(WIFI_PASSWORD_KEY)?
+            .unwrap_or(String::from(WIFI_DEFAULT_PASSWORD));
+
+        if constant_time_eq(&access_point_password, WIFI_DEFAULT_PASSWORD) {
+            return Ok(true);
+        }
+
+        Ok(users.iter().any(|user| user.has_default_password))
+    }
+}
+
+/* users */
+impl SecurityContext {
+    pub fn get_users(&self) -> SecurityResult<Vec<User>> {
+        let users: Vec<User> = self.users
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?
+            .clone();
+
+        Ok(users)
+    }
+
+    pub fn get_user(&self, username: &str) -> SecurityResult<User> {
+        self.users
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?
+            .iter()
+            .find(|user| user.username == username)
+            .cloned()
+            .ok_or(SecurityError::UserNotFound)
+    }
+
+    pub fn add_user(&self, username: &str, password: &str, role: Role) -> SecurityResult<()> {
+        validate_username(username).map_err(SecurityError::InvalidUser)?;
+
+        self.get_password_policy()?
+            .check_user_password(password, username)
+            .map_err(SecurityError::WeakPassword)?;
+
+        self.modify_users(|users| {
+            if users.iter().any(|user| user.username == username) {
+                return Err(SecurityError::UserAlreadyExists);
+            }
+
+            if users.len() >= USERS_MAX_COUNT {
+                return Err(SecurityError::InvalidUser(format!("Maximum {USERS_MAX_COUNT} users are allowed.")));
+            }
+
+            users.push(SecurityContext::new_user(username, role, password));
+
+            Ok(())
+        })
+    }
+
+    /**
+     * Last admin can't be demoted. All sessions of user are revoked when password is changed.
+     */
+    pub fn update_user(&self, username: &str, password: Option<&str>, role: Option<Role>) -> SecurityResult<()> {
+        if let Some(password) = password {
+            self.get_password_policy()?
+                .check_user_password(password, username)
+                .map_err(SecurityError::WeakPassword)?;
+        }
+
+        self.modify_users(|users| {
+            let user: &mut User = users
+                .iter_mut()
+                .find(|user| user.username == username)
+                .ok_or(SecurityError::UserNotFound)?;
+
+            if let Some(password) = password {
+                SecurityContext::set_password(user, password);
+            }
+
+            if let Some(role) = role {
+                user.role = role;
+            }
+
+            SecurityContext::validate_admin_exists(users)
+        })?;
+
+        if password.is_some() {
+            self.sessions()?.revoke_user(username)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_user_password(&self, username: &str, new_password: &str) -> SecurityResult<()> {
+        self.update_user(username, Some(new_password), None)
+    }
+
+    /**
+     * Last admin can't be removed. API keys of user are removed too.
+     */
+    pub fn delete_user(&self, username: &str) -> SecurityResult<()> {
+        self.modify_users(|users| {
+            let users_count: usize = users.len();
+            users.retain(|user| user.username != username);
+
+            if users.len() == users_count {
+                return Err(SecurityError::UserNotFound);
+            }
+
+            SecurityContext::validate_admin_exists(users)
+        })?;
+
+        self.modify_api_keys(|api_keys| {
+            api_keys.retain(|api_key| api_key.owner != username);
+            Ok(())
+        })
+    }
+
+    /**
+     * Restore admin account with default password. Used by reset button.
+     */
+    pub fn reset_admin_password(&self) -> SecurityResult<()> {
+        self.modify_users(|users| {
+            match users.iter_mut().find(|user| user.username == ADMIN_USERNAME) {
+                Some(user) => {
+                    user.role = Role::Admin;
+                    SecurityContext::set_password(user, USER_DEFAULT_PASSWORD);
+                }
+                None => users.push(SecurityContext::default_admin()),
+            }
+
+            Ok(())
+        })
+    }
+
+    /**
+     * Keep only admin account with default password and remove API keys, sessions and password policy.
+     */
+    pub fn factory_reset(&self) -> SecurityResult<()> {
+        self.modify_users(|users| {
+            *users = vec![SecurityContext::default_admin()];
+            Ok(())
+        })?;
+
+        self.revoke_all_api_keys()?;
+        self.revoke_all_access_tokens()?;
+        self.reset_password_policy()
+    }
+
+    /**
+     * Apply change to copy of users. Users are stored and cached only when change succeeds.
+     */
+    fn modify_users<F>(&self, modify: F) -> SecurityResult<()>
+    where F: FnOnce(&mut Vec<User>) -> SecurityResult<()> {
+        let mut users = self.users
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        let mut modified_users: Vec<User> = users.clone();
+        modify(&mut modified_users)?;
+
+        SecurityContext::write_users(&modified_users).map_err(SecurityError::EspError)?;
+
+        let has_default_password: bool = SecurityContext::has_default_password(&modified_users)
+            .map_err(SecurityError::EspError)?;
+        self.setup_required.store(has_default_password, Ordering::SeqCst);
 
-        Ok(password == actual_password)
+        *users = modified_users;
+
+        Ok(())
+    }
+
+    fn validate_admin_exists(users: &[User]) -> SecurityResult<()> {
+        if users.iter().any(|user| user.role == Role::Admin) {
+            Ok(())
+        } else {
+            Err(SecurityError::InvalidUser("At least one admin is required.".to_string()))
+        }
+    }
+
+    fn default_admin() -> User {
+        SecurityContext::new_user(ADMIN_USERNAME, Role::Admin, USER_DEFAULT_PASSWORD)
     }
 
-    pub fn is_valid_access_token_token(&self, access_token: &str) -> SecurityResult<bool> {
-        let actual_access_token = self.access_token
+    fn new_user(username: &str, role: Role, password: &str) -> User {
+        let mut user: User = User {
+            username: username.to_string(),
+            role,
+            password_hash: String::new(),
+            has_default_password: false,
+        };
+        SecurityContext::set_password(&mut user, password);
+
+        user
+    }
+
+    /**
+     * Default password is recognized while it's set, so hashes don't have to be derived to find it.
+     */
+    fn set_password(user: &mut User, password: &str) {
+        user.password_hash = hash_password(password);
+        user.has_default_password = constant_time_eq(password, USER_DEFAULT_PASSWORD);
+    }
+
+    /**
+     * On first boot after update, single password of older firmware becomes password of admin account.
+     * Returns None when stored users are corrupted or there is no admin among them.
+     */
+    fn read_users() -> Result<Option<Vec<User>>, EspError> {
+        if let Some(users) = SecurityContext::nvs_read_str(USERS_KEY)? {
+            let mut users: Vec<User> =
+                match serde_json::from_str::<Vec<StoredUserDTO>>(&users) {
+                    Ok(users) => users.into_iter().map(Into::into).collect(),
+                    Err(error) => {
+                        log::error!("Can't parse users: {error}");
+                        return Ok(None);
+                    }
+                };
+
+            if SecurityContext::validate_admin_exists(&users).is_err() {
+                log::error!("Stored users have no admin.");
+                return Ok(None);
+            }
+
+            /* derives hash of every user, so it's done only on boot */
+            for user in users.iter_mut() {
+                user.has_default_password = verify_password(USER_DEFAULT_PASSWORD, &user.password_hash);
+            }
+
+            return Ok(Some(users));
+        }
+
+        log::info!("Migrating API password to '{ADMIN_USERNAME}' account.");
+
+        let admin: User =
+            match SecurityContext::nvs_read_str(LEGACY_USER_PASSWORD_KEY)? {
+                Some(password_hash) if is_password_hash(&password_hash) => User {
+                    username: ADMIN_USERNAME.to_string(),
+                    role: Role::Admin,
+                    has_default_password: verify_password(USER_DEFAULT_PASSWORD, &password_hash),
+                    password_hash,
+                },
+                Some(password) => SecurityContext::new_user(ADMIN_USERNAME, Role::Admin, &password),
+                None => SecurityContext::default_admin(),
+            };
+
+        let users: Vec<User> = vec![admin];
+
+        SecurityContext::write_users(&users)?;
+        SecurityContext::nvs_remove(LEGACY_USER_PASSWORD_KEY)?;
+
+        Ok(Some(users))
+    }
+
+    fn write_users(users: &[User]) -> Result<(), EspError> {
+        let users: Vec<StoredUserDTO> = users.iter().cloned().map(Into::into).collect();
+        let users_str: String = serde_json::to_string(&users).unwrap_or_default();
+
+        SecurityContext::nvs_write_str(USERS_KEY, &users_str)
+    }
+}
+
+/* API keys */
+impl SecurityContext {
+    pub fn get_api_keys(&self) -> SecurityResult<Vec<ApiKey>> {
+        let api_keys: Vec<ApiKey> = self.api_keys
             .read()
-            .map_err(|_| SecurityError::ReadLockError)?;
+            .map_err(|_| SecurityError::ReadLockError)?
+            .clone();
+
+        Ok(api_keys)
+    }
+
+    /**
+     * Returns created key and its plaintext value. Plaintext value can't be retrieved later.
+     */
+    pub fn create_api_key(&self, owner: &str, name: &str, scopes: Vec<Permission>, expires_at_seconds: Option<u64>) -> SecurityResult<(ApiKey, String)> {
+        validate_api_key_name(name).map_err(SecurityError::InvalidApiKey)?;
+
+        if scopes.is_empty() {
+            return Err(SecurityError::InvalidApiKey("At least one scope is required.".to_string()));
+        }
+
+        let now_seconds: u64 = SecurityContext::now_seconds();
 
-        Ok(access_token == *actual_access_token)
+        if expires_at_seconds.is_some_and(|expires_at_seconds| expires_at_seconds <= now_seconds) {
+            return Err(SecurityError::InvalidApiKey("Expiration time should be in future.".to_string()));
+        }
+
+        let (api_key, key): (ApiKey, String) = ApiKey::generate(name.to_string(), owner.to_string(), scopes, now_seconds, expires_at_seconds);
+
+        self.modify_api_keys(|api_keys| {
+            if api_keys.len() >= API_KEYS_MAX_COUNT {
+                return Err(SecurityError::InvalidApiKey(format!("Maximum {API_KEYS_MAX_COUNT} API keys are allowed.")));
+            }
+
+            api_keys.push(api_key.clone());
+
+            Ok(())
+        })?;
+
+        Ok((api_key, key))
     }
 
-    fn generate_access_token() -> String {
-        thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(ACCESS_TOKEN_LENGTH)
-            .map(char::from)
-            .collect()
+    pub fn delete_api_key(&self, id: &str) -> SecurityResult<()> {
+        self.modify_api_keys(|api_keys| {
+            let api_keys_count: usize = api_keys.len();
+            api_keys.retain(|api_key| api_key.id != id);
+
+            if api_keys.len() == api_keys_count {
+                return Err(SecurityError::ApiKeyNotFound);
+            }
+
+            Ok(())
+        })
     }
 
+    /**
+     * Remove all API keys. Used by reset button, so keys don't outlive reset passwords.
+     */
+    pub fn revoke_all_api_keys(&self) -> SecurityResult<()> {
+        self.modify_api_keys(|api_keys| {
+            api_keys.clear();
+            Ok(())
+        })
+    }
+
+    /**
+     * Use of key is audited once per persist interval of last used time and address,
+     * so frequent requests don't flood audit log, but every client using the key is recorded.
+     */
+    fn authenticate_api_key(&self, id: &str, secret: &str, client_ip: Option<IpAddr>) -> SecurityResult<Session> {
+        let (session, is_audit_needed): (Session, bool) = self.use_api_key(id, secret, client_ip)?;
+
+        if is_audit_needed {
+            self.audit(AuditRecord {
+                actor: session.username.clone(),
+                action: AuditAction::ApiKeyUsed,
+                source_ip: client_ip,
+                outcome: AuditOutcome::Success,
+                details: format!("API key '{id}'."),
+            });
+        }
+
+        Ok(session)
+    }
+
+    /**
+     * Last used time is always updated in cache, but stored only when it moves to next persist interval.
+     * Returns session of key owner and whether this is the first use in current interval or from new address.
+     */
+    fn use_api_key(&self, id: &str, secret: &str, client_ip: Option<IpAddr>) -> SecurityResult<(Session, bool)> {
+        let now_seconds: u64 = SecurityContext::now_seconds();
+
+        let mut api_keys = self.api_keys
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        /* every key is checked, so response time doesn't tell whether id exists */
+        let api_key_index: Option<usize> = api_keys
+            .iter()
+            .enumerate()
+            .fold(None, |found_index, (index, api_key)| {
+                let is_match: bool = constant_time_eq(&api_key.id, id) & api_key.matches_secret(secret);
+                if is_match { Some(index) } else { found_index }
+            });
+
+        let api_key: &mut ApiKey = api_key_index
+            .and_then(|api_key_index| api_keys.get_mut(api_key_index))
+            .ok_or(SecurityError::InvalidAccessToken)?;
+
+        if api_key.is_expired(now_seconds) {
+            return Err(SecurityError::AccessTokenExpired);
+        }
+
+        let session: Session = Session {
+            username: api_key.owner.clone(),
+            scopes: Some(api_key.scopes.clone()),
+        };
+
+        let is_persist_needed: bool = api_key.last_used_at_seconds
+            .map_or(true, |last_used_at_seconds| last_used_at_seconds / API_KEY_LAST_USED_PERSIST_SECONDS != now_seconds / API_KEY_LAST_USED_PERSIST_SECONDS);
+
+        if is_persist_needed {
+            api_key.audited_ips.clear();
+        }
+
+        let is_new_address: bool = client_ip.is_some_and(|client_ip| !api_key.audited_ips.contains(&client_ip));
+
+        if let Some(client_ip) = client_ip.filter(|_| is_new_address) {
+            api_key.audited_ips.push(client_ip);
+        }
+
+        api_key.last_used_at_seconds = Some(now_seconds);
+
+        if is_persist_needed {
+            /* failed write doesn't reject valid key */
+            if let Err(error) = SecurityContext::write_api_keys(&api_keys) {
+                log::warn!("Can't store last used time of API key: {error}");
+            }
+        }
+
+        Ok((session, is_persist_needed || is_new_address))
+    }
+
+    /**
+     * Apply change to copy of API keys. API keys are stored and cached only when change succeeds.
+     */
+    fn modify_api_keys<F>(&self, modify: F) -> SecurityResult<()>
+    where F: FnOnce(&mut Vec<ApiKey>) -> SecurityResult<()> {
+        let mut api_keys = self.api_keys
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        let mut modified_api_keys: Vec<ApiKey> = api_keys.clone();
+        modify(&mut modified_api_keys)?;
 
+        SecurityContext::write_api_keys(&modified_api_keys).map_err(SecurityError::EspError)?;
+        *api_keys = modified_api_keys;
+
+        Ok(())
+    }
+
+    fn read_api_keys() -> Result<Vec<ApiKey>, EspError> {
+        let Some(api_keys) = SecurityContext::nvs_read_str(API_KEYS_KEY)? else {
+            return Ok(vec![]);
+        };
+
+        match serde_json::from_str::<Vec<StoredApiKeyDTO>>(&api_keys) {
+            Ok(api_keys) => Ok(api_keys.into_iter().map(Into::into).collect()),
+            Err(error) => {
+                log::error!("Can't parse API keys: {error}");
+                Ok(vec![])
+            }
+        }
+    }
+
+    fn write_api_keys(api_keys: &[ApiKey]) -> Result<(), EspError> {
+        let api_keys: Vec<StoredApiKeyDTO> = api_keys.iter().cloned().map(Into::into).collect();
+        let api_keys_str: String = serde_json::to_string(&api_keys).unwrap_or_default();
+
+        SecurityContext::nvs_write_str(API_KEYS_KEY, &api_keys_str)
+    }
+
+    fn now_seconds() -> u64 {
+        system_time::now().as_secs()
+    }
+}
+
+/* audit */
+impl SecurityContext {
+    pub fn set_audit_listener<L>(&self, audit_listener: L)
+    where L: Fn(AuditRecord) + Send + Sync + 'static {
+        let pending_audit_records: Vec<AuditRecord> = self.pending_audit_records
+            .lock()
+            .map(|mut pending_audit_records| std::mem::take(&mut *pending_audit_records))
+            .unwrap_or_default();
+
+        pending_audit_records.into_iter().for_each(&audit_listener);
+
+        if let Ok(mut current_audit_listener) = self.audit_listener.write() {
+            *current_audit_listener = Some(Box::new(audit_listener));
+        }
+    }
+
+    pub fn get_audit_chain_head(&self) -> SecurityResult<Option<AuditChainHead>> {
+        let audit_chain_head: Option<AuditChainHead> = SecurityContext::nvs_read_str(AUDIT_CHAIN_HEAD_KEY)
+            .map_err(SecurityError::EspError)?
+            .and_then(|audit_chain_head| {
+                let (sequence, hash): (&str, &str) = audit_chain_head.split_once(':')?;
+
+                Some(AuditChainHead {
+                    sequence: sequence.parse().ok()?,
+                    hash: hash.to_string(),
+                })
+            });
+
+        Ok(audit_chain_head)
+    }
+
+    pub fn set_audit_chain_head(&self, audit_chain_head: &AuditChainHead) -> SecurityResult<()> {
+        let audit_chain_head_str: String = format!("{}:{}", audit_chain_head.sequence, audit_chain_head.hash);
+
+        SecurityContext::nvs_write_str(AUDIT_CHAIN_HEAD_KEY, &audit_chain_head_str)
+            .map_err(SecurityError::EspError)
+    }
+
+    fn audit(&self, record: AuditRecord) {
+        if let Ok(audit_listener) = self.audit_listener.read() {
+            if let Some(audit_listener) = audit_listener.as_ref() {
+                audit_listener(record);
+            }
+        }
+    }
+}
+
+/* password policy */
+impl SecurityContext {
+    pub fn get_password_policy(&self) -> SecurityResult<PasswordPolicy> {
+        let password_policy: PasswordPolicy = self.password_policy
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?
+            .clone();
+
+        Ok(password_policy)
+    }
+
+    pub fn set_password_policy(&self, password_policy: PasswordPolicy) -> SecurityResult<()> {
+        password_policy
+            .validate()
+            .map_err(SecurityError::InvalidPasswordPolicy)?;
+
+        let mut current_password_policy = self.password_policy
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        let password_policy_str: String = serde_json::to_string(&PasswordPolicyDTO::from(password_policy.clone()))
+            .unwrap_or_default();
+
+        SecurityContext::nvs_write_str(PASSWORD_POLICY_KEY, &password_policy_str)
+            .map_err(SecurityError::EspError)?;
+
+        *current_password_policy = password_policy;
+
+        Ok(())
+    }
+
+    fn reset_password_policy(&self) -> SecurityResult<()> {
+        let mut password_policy = self.password_policy
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        SecurityContext::nvs_remove(PASSWORD_POLICY_KEY).map_err(SecurityError::EspError)?;
+        *password_policy = PasswordPolicy::default();
+
+        Ok(())
+    }
+
+    fn read_password_policy() -> Result<PasswordPolicy, EspError> {
+        let Some(password_policy) = SecurityContext::nvs_read_str(PASSWORD_POLICY_KEY)? else {
+            return Ok(PasswordPolicy::default());
+        };
+
+        match serde_json::from_str::<PasswordPolicyDTO>(&password_policy) {
+            Ok(password_policy) => {
+                let password_policy: PasswordPolicy = password_policy.into();
+
+                /* policy stored by older firmware could allow shorter passwords */
+                if let Err(error) = password_policy.validate() {
+                    log::error!("Invalid password policy, using default one: {error}");
+                    return Ok(PasswordPolicy::default());
+                }
+
+                Ok(password_policy)
+            }
+            Err(error) => {
+                log::error!("Can't parse password policy, using default one: {error}");
+                Ok(PasswordPolicy::default())
+            }
+        }
+    }
+}
+
+/* nvs */
+#[cfg(target_os = "espidf")]
+impl SecurityContext {
     fn nvs_read_str(key: &str) -> Result<Option<String>, EspError> {
         synchronized!{
             log::info!("Reading from NVS by key '{key}'.");
@@ -113,7 +965,6 @@ impl SecurityContext {
             log::info!("NVS initialized.");
 
             if let Some(password_length) = esp_nvs.str_len(key)? {
-                log::info!("Length of value is {password_length} for key '{key}'.");
                 let mut password_buffer: Vec<u8> = vec![0; password_length];
 
                 let password: Option<&str> = esp_nvs.get_str(key, password_buffer.as_mut_slice())?;
@@ -128,7 +979,7 @@ impl SecurityContext {
 
     fn nvs_write_str(key: &str, value: &str) -> Result<(), EspError> {
         synchronized! {
-            log::info!("Writing string '{value}' to NVS by key '{key}'.");
+            log::info!("Writing string to NVS by key '{key}'.");
             let esp_nvs_partition: EspNvsPartition<NvsDefault> = EspDefaultNvsPartition::take()?;
             let mut esp_nvs: EspNvs<NvsDefault> = EspNvs::new(esp_nvs_partition, NVS_NAMESPACE, true)?;
             log::info!("NVS initialized.");
@@ -136,4 +987,37 @@ impl SecurityContext {
             esp_nvs.set_str(key, value)
         }
     }
+
+    fn nvs_remove(key: &str) -> Result<(), EspError> {
+        synchronized! {
+            log::info!("Removing NVS value by key '{key}'.");
+            let esp_nvs_partition: EspNvsPartition<NvsDefault> = EspDefaultNvsPartition::take()?;
+            let mut esp_nvs: EspNvs<NvsDefault> = EspNvs::new(esp_nvs_partition, NVS_NAMESPACE, true)?;
+
+            esp_nvs.remove(key).map(|_| ())
+        }
+    }
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl SecurityContext {
+    fn nvs_read_str(key: &str) -> Result<Option<String>, EspError> {
+        let nvs = MOCK_NVS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        Ok(nvs.get(key).cloned())
+    }
+
+    fn nvs_write_str(key: &str, value: &str) -> Result<(), EspError> {
+        let mut nvs = MOCK_NVS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        nvs.insert(key.to_string(), value.to_string());
+
+        Ok(())
+    }
+
+    fn nvs_remove(key: &str) -> Result<(), EspError> {
+        let mut nvs = MOCK_NVS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        nvs.remove(key);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,90 @@
+use crate::security::password::constant_time_eq;
+use crate::security::user::Permission;
+use rand::{thread_rng, RngCore};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+
+/* Access tokens of sessions never contain '_', so prefix tells API key from access token. */
+pub const API_KEY_PREFIX: &str = "key_";
+const API_KEY_ID_LENGTH: usize = 4;
+const API_KEY_SECRET_LENGTH: usize = 32;
+const API_KEY_NAME_MAX_LENGTH: usize = 32;
+
+/**
+ * Long-lived credential of automation client. Key acts on behalf of user who created it,
+ * so it has permissions which are both in its scopes and in role of that user.
+ * Format of key is key_<id hex>_<secret hex>. Only SHA-256 of secret is stored.
+ * Secret is random, so fast hash is enough and key can be checked on every request.
+ */
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<Permission>,
+    pub secret_hash: String,
+    pub created_at_seconds: u64,
+    pub expires_at_seconds: Option<u64>,
+    pub last_used_at_seconds: Option<u64>,
+    /* Addresses whose use is already audited in current persist interval. Kept only in memory. */
+    pub audited_ips: Vec<IpAddr>,
+}
+
+impl ApiKey {
+    /**
+     * Returns API key and its plaintext value, which is shown to client only once.
+     */
+    pub fn generate(name: String, owner: String, scopes: Vec<Permission>, created_at_seconds: u64, expires_at_seconds: Option<u64>) -> (Self, String) {
+        let mut id: [u8; API_KEY_ID_LENGTH] = [0; API_KEY_ID_LENGTH];
+        let mut secret: [u8; API_KEY_SECRET_LENGTH] = [0; API_KEY_SECRET_LENGTH];
+        thread_rng().fill_bytes(&mut id);
+        thread_rng().fill_bytes(&mut secret);
+
+        let id: String = hex::encode(id);
+        let secret: String = hex::encode(secret);
+        let key: String = format!("{API_KEY_PREFIX}{id}_{secret}");
+
+        let api_key: ApiKey = ApiKey {
+            id,
+            name,
+            owner,
+            scopes,
+            secret_hash: ApiKey::hash_secret(&secret),
+            created_at_seconds,
+            expires_at_seconds,
+            last_used_at_seconds: None,
+            audited_ips: vec![],
+        };
+
+        (api_key, key)
+    }
+
+    /**
+     * Split key into id and secret. Returns None when value isn't an API key.
+     */
+    pub fn parse(key: &str) -> Option<(&str, &str)> {
+        key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|key| key.split_once('_'))
+    }
+
+    pub fn matches_secret(&self, secret: &str) -> bool {
+        constant_time_eq(&ApiKey::hash_secret(secret), &self.secret_hash)
+    }
+
+    pub fn is_expired(&self, now_seconds: u64) -> bool {
+        self.expires_at_seconds.is_some_and(|expires_at_seconds| now_seconds > expires_at_seconds)
+    }
+
+    fn hash_secret(secret: &str) -> String {
+        hex::encode(Sha256::digest(secret.as_bytes()))
+    }
+}
+
+pub fn validate_api_key_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.len() > API_KEY_NAME_MAX_LENGTH {
+        return Err(format!("API key name should be 1-{API_KEY_NAME_MAX_LENGTH} characters long."));
+    }
+
+    Ok(())
+}
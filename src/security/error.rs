@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
-use esp_idf_svc::sys::EspError;
+use interface::EspError;
+use http_server::api_error::ApiError;
+use crate::security::login_guard::LoginLockout;
 
 #[derive(Debug)]
 pub enum SecurityError {
@@ -7,6 +9,17 @@ pub enum SecurityError {
     ReadLockError,
     WriteLockError,
     WrongCredentials,
+    InvalidAccessToken,
+    AccessTokenExpired,
+    PermissionDenied,
+    UserNotFound,
+    UserAlreadyExists,
+    ApiKeyNotFound,
+    InvalidApiKey(String),
+    InvalidUser(String),
+    WeakPassword(String),
+    InvalidPasswordPolicy(String),
+    LoginLocked(LoginLockout),
 }
 
 impl Display for SecurityError {
@@ -16,6 +29,42 @@ impl Display for SecurityError {
             SecurityError::ReadLockError => f.write_str("Could not read lock."),
             SecurityError::WriteLockError => f.write_str("Could not write lock."),
             SecurityError::WrongCredentials => f.write_str("Wrong credentials."),
+            SecurityError::InvalidAccessToken => f.write_str("Invalid access token."),
+            SecurityError::AccessTokenExpired => f.write_str("Access token expired."),
+            SecurityError::PermissionDenied => f.write_str("Permission denied."),
+            SecurityError::UserNotFound => f.write_str("User not found."),
+            SecurityError::UserAlreadyExists => f.write_str("User already exists."),
+            SecurityError::ApiKeyNotFound => f.write_str("API key not found."),
+            SecurityError::InvalidApiKey(message) => f.write_str(message),
+            SecurityError::InvalidUser(message) => f.write_str(message),
+            SecurityError::WeakPassword(message) => f.write_str(message),
+            SecurityError::InvalidPasswordPolicy(message) => f.write_str(message),
+            SecurityError::LoginLocked(lockout) => write!(f, "Too many failed logins. Try again in {}s.", lockout.remaining().as_secs() + 1),
+        }
+    }
+}
+
+/**
+ * Failed authentication is 401, missing permission is 403. Lockout is sent with Retry-After header by auth controller.
+ */
+impl From<SecurityError> for ApiError {
+    fn from(error: SecurityError) -> Self {
+        match error {
+            SecurityError::WrongCredentials => ApiError::unauthorized("wrong_credentials", error.to_string()),
+            SecurityError::InvalidAccessToken => ApiError::unauthorized("invalid_access_token", error.to_string()),
+            SecurityError::AccessTokenExpired => ApiError::unauthorized("access_token_expired", error.to_string()),
+            SecurityError::PermissionDenied => ApiError::forbidden("permission_denied", error.to_string()),
+            SecurityError::UserNotFound => ApiError::not_found("user_not_found", error.to_string()),
+            SecurityError::ApiKeyNotFound => ApiError::not_found("api_key_not_found", error.to_string()),
+            SecurityError::UserAlreadyExists => ApiError::conflict("user_already_exists", error.to_string()),
+            SecurityError::InvalidApiKey(_) => ApiError::bad_request("invalid_api_key", error.to_string()),
+            SecurityError::InvalidUser(_) => ApiError::bad_request("invalid_user", error.to_string()),
+            SecurityError::WeakPassword(_) => ApiError::bad_request("weak_password", error.to_string()),
+            SecurityError::InvalidPasswordPolicy(_) => ApiError::bad_request("invalid_password_policy", error.to_string()),
+            SecurityError::LoginLocked(_) => ApiError::new(429, "login_locked", error.to_string()),
+            SecurityError::EspError(_)
+            | SecurityError::ReadLockError
+            | SecurityError::WriteLockError => ApiError::internal_server_error("internal_error", error.to_string()),
         }
     }
 }
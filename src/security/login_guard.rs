@@ -0,0 +1,171 @@
+use crate::constant::{LOGIN_BACKOFF_BASE_SECONDS, LOGIN_CLIENT_FREE_ATTEMPTS, LOGIN_FAILURES_RESET_SECONDS, LOGIN_GLOBAL_FREE_ATTEMPTS, LOGIN_LOCKOUT_MAX_SECONDS, LOGIN_TRACKED_CLIENTS_MAX};
+use crate::security::error::SecurityError;
+use crate::security::SecurityResult;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct FailedAttempts {
+    count: u32,
+    last_failure_at: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl FailedAttempts {
+    fn new(now: Instant) -> Self {
+        Self {
+            count: 0,
+            last_failure_at: now,
+            locked_until: None,
+        }
+    }
+
+    fn lockout_remaining(&self, now: Instant) -> Option<Duration> {
+        self.locked_until
+            .and_then(|locked_until| locked_until.checked_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /**
+     * Every failure after free attempts doubles lockout time.
+     */
+    fn record_failure(&mut self, now: Instant, free_attempts: u32) {
+        if now.duration_since(self.last_failure_at) > Duration::from_secs(LOGIN_FAILURES_RESET_SECONDS) {
+            self.count = 0;
+        }
+
+        self.count += 1;
+        self.last_failure_at = now;
+
+        if self.count > free_attempts {
+            let exponent: u32 = (self.count - free_attempts - 1).min(16);
+            let lockout_seconds: u64 = (LOGIN_BACKOFF_BASE_SECONDS << exponent).min(LOGIN_LOCKOUT_MAX_SECONDS);
+
+            self.locked_until = Some(now + Duration::from_secs(lockout_seconds));
+        }
+    }
+
+    fn is_forgotten(&self, now: Instant) -> bool {
+        self.lockout_remaining(now).is_none() &&
+        now.duration_since(self.last_failure_at) > Duration::from_secs(LOGIN_FAILURES_RESET_SECONDS)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum LoginLockout {
+    Client(Duration),
+    /* Too many failures from all clients together. */
+    Global(Duration),
+}
+
+impl LoginLockout {
+    pub fn remaining(&self) -> Duration {
+        match self {
+            LoginLockout::Client(remaining) | LoginLockout::Global(remaining) => *remaining,
+        }
+    }
+}
+
+/**
+ * Counts failed logins per client and for all clients together.
+ * Clients with unknown address count only to global counter, so they can't lock out each other.
+ */
+pub struct LoginGuard {
+    clients: Mutex<HashMap<IpAddr, FailedAttempts>>,
+    global: Mutex<FailedAttempts>,
+}
+
+impl Default for LoginGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoginGuard {
+    pub fn new() -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            global: Mutex::new(FailedAttempts::new(Instant::now())),
+        }
+    }
+
+    pub fn lockout(&self, client_ip: Option<IpAddr>) -> SecurityResult<Option<LoginLockout>> {
+        let now: Instant = Instant::now();
+
+        let global_remaining: Option<Duration> = self.global
+            .lock()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .lockout_remaining(now);
+
+        if let Some(remaining) = global_remaining {
+            return Ok(Some(LoginLockout::Global(remaining)));
+        }
+
+        let Some(client_ip) = client_ip else {
+            return Ok(None);
+        };
+
+        let client_remaining: Option<Duration> = self.clients
+            .lock()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .get(&client_ip)
+            .and_then(|failed_attempts| failed_attempts.lockout_remaining(now));
+
+        Ok(client_remaining.map(LoginLockout::Client))
+    }
+
+    pub fn record_failure(&self, client_ip: Option<IpAddr>) -> SecurityResult<()> {
+        let now: Instant = Instant::now();
+
+        self.global
+            .lock()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .record_failure(now, LOGIN_GLOBAL_FREE_ATTEMPTS);
+
+        let Some(client_ip) = client_ip else {
+            return Ok(());
+        };
+
+        let mut clients = self.clients
+            .lock()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        clients.retain(|_, failed_attempts| !failed_attempts.is_forgotten(now));
+
+        /* attacker can't grow map by changing address, least recently failed client is forgotten */
+        if !clients.contains_key(&client_ip) && clients.len() >= LOGIN_TRACKED_CLIENTS_MAX {
+            let oldest_client_ip: Option<IpAddr> = clients
+                .iter()
+                .min_by_key(|(_, failed_attempts)| failed_attempts.last_failure_at)
+                .map(|(client_ip, _)| *client_ip);
+
+            if let Some(oldest_client_ip) = oldest_client_ip {
+                clients.remove(&oldest_client_ip);
+            }
+        }
+
+        clients
+            .entry(client_ip)
+            .or_insert_with(|| FailedAttempts::new(now))
+            .record_failure(now, LOGIN_CLIENT_FREE_ATTEMPTS);
+
+        Ok(())
+    }
+
+    /**
+     * Global counter isn't reset, so attacker can't unlock it with own valid account.
+     */
+    pub fn record_success(&self, client_ip: Option<IpAddr>) -> SecurityResult<()> {
+        let Some(client_ip) = client_ip else {
+            return Ok(());
+        };
+
+        self.clients
+            .lock()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .remove(&client_ip);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use pbkdf2::pbkdf2_hmac;
+use rand::{thread_rng, RngCore};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+/* Format of stored hash: pbkdf2-sha256$<iterations>$<salt hex>$<hash hex> */
+const HASH_ALGORITHM: &str = "pbkdf2-sha256";
+/* Balance between brute-force cost and login time on ESP32. */
+const HASH_ITERATIONS: u32 = 10_000;
+const SALT_LENGTH: usize = 16;
+const HASH_LENGTH: usize = 32;
+
+pub fn hash_password(password: &str) -> String {
+    let mut salt: [u8; SALT_LENGTH] = [0; SALT_LENGTH];
+    thread_rng().fill_bytes(&mut salt);
+
+    let hash: [u8; HASH_LENGTH] = derive_hash(password, &salt, HASH_ITERATIONS);
+
+    format!("{HASH_ALGORITHM}${HASH_ITERATIONS}${}${}", hex::encode(salt), hex::encode(hash))
+}
+
+/**
+ * Stored value which isn't a hash is treated as legacy plaintext password.
+ */
+pub fn verify_password(password: &str, stored_password: &str) -> bool {
+    let Some((iterations, salt, hash)) = parse_hash(stored_password) else {
+        return constant_time_eq(password, stored_password);
+    };
+
+    let actual_hash: [u8; HASH_LENGTH] = derive_hash(password, &salt, iterations);
+
+    actual_hash.ct_eq(hash.as_slice()).into()
+}
+
+/**
+ * Derive hash with default parameters and discard it. Used when there is no stored hash to compare with.
+ */
+pub fn simulate_password_verification(password: &str) {
+    let _ = derive_hash(password, &[0; SALT_LENGTH], HASH_ITERATIONS);
+}
+
+pub fn is_password_hash(stored_password: &str) -> bool {
+    parse_hash(stored_password).is_some()
+}
+
+/**
+ * Compare strings in time which doesn't depend on matching prefix length.
+ */
+pub fn constant_time_eq(left: &str, right: &str) -> bool {
+    left.as_bytes().ct_eq(right.as_bytes()).into()
+}
+
+fn derive_hash(password: &str, salt: &[u8], iterations: u32) -> [u8; HASH_LENGTH] {
+    let mut hash: [u8; HASH_LENGTH] = [0; HASH_LENGTH];
+    pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut hash);
+
+    hash
+}
+
+fn parse_hash(stored_password: &str) -> Option<(u32, Vec<u8>, Vec<u8>)> {
+    let mut parts = stored_password.split('$');
+
+    if parts.next()? != HASH_ALGORITHM {
+        return None;
+    }
+
+    let iterations: u32 = parts.next()?.parse().ok()?;
+    let salt: Vec<u8> = hex::decode(parts.next()?).ok()?;
+    let hash: Vec<u8> = hex::decode(parts.next()?).ok()?;
+
+    if parts.next().is_some() || iterations == 0 || hash.len() != HASH_LENGTH {
+        return None;
+    }
+
+    Some((iterations, salt, hash))
+}
@@ -0,0 +1,90 @@
+use crate::constant::{PASSWORD_MAX_LENGTH, PASSWORD_MIN_LENGTH};
+
+/* Limits of WPA2/WPA3 passphrase. */
+const ACCESS_POINT_PASSWORD_MIN_LENGTH: usize = 8;
+const ACCESS_POINT_PASSWORD_MAX_LENGTH: usize = 63;
+
+/**
+ * Rules for new passwords of users and access point. Existing passwords aren't checked when policy changes.
+ */
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_letter: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /* Password can't contain username, case insensitive. */
+    pub reject_username: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: PASSWORD_MIN_LENGTH,
+            require_letter: true,
+            require_digit: true,
+            require_symbol: false,
+            reject_username: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        /* default is the lowest allowed minimum, so policy can only be made stricter */
+        if !(PASSWORD_MIN_LENGTH..=PASSWORD_MAX_LENGTH).contains(&self.min_length) {
+            return Err(format!("Minimum password length should be {PASSWORD_MIN_LENGTH}-{PASSWORD_MAX_LENGTH}."));
+        }
+
+        Ok(())
+    }
+
+    pub fn check_user_password(&self, password: &str, username: &str) -> Result<(), String> {
+        self.check(password)?;
+
+        if self.reject_username && password.to_lowercase().contains(&username.to_lowercase()) {
+            return Err("Password can't contain username.".to_string());
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Besides policy, Wi-Fi driver accepts only 8-63 printable ASCII characters.
+     */
+    pub fn check_access_point_password(&self, password: &str) -> Result<(), String> {
+        let length: usize = password.chars().count();
+
+        if !(ACCESS_POINT_PASSWORD_MIN_LENGTH..=ACCESS_POINT_PASSWORD_MAX_LENGTH).contains(&length) {
+            return Err(format!("Access point password should be {ACCESS_POINT_PASSWORD_MIN_LENGTH}-{ACCESS_POINT_PASSWORD_MAX_LENGTH} characters long."));
+        }
+
+        if !password.chars().all(|char| char.is_ascii_graphic() || char == ' ') {
+            return Err("Access point password can contain only printable ASCII characters.".to_string());
+        }
+
+        self.check(password)
+    }
+
+    fn check(&self, password: &str) -> Result<(), String> {
+        let length: usize = password.chars().count();
+
+        if length < self.min_length || length > PASSWORD_MAX_LENGTH {
+            return Err(format!("Password should be {}-{PASSWORD_MAX_LENGTH} characters long.", self.min_length));
+        }
+
+        if self.require_letter && !password.chars().any(char::is_alphabetic) {
+            return Err("Password should contain a letter.".to_string());
+        }
+
+        if self.require_digit && !password.chars().any(|char| char.is_ascii_digit()) {
+            return Err("Password should contain a digit.".to_string());
+        }
+
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            return Err("Password should contain a symbol.".to_string());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+pub mod memory_session_store;
+pub mod signed_token_store;
+
+use crate::security::user::Permission;
+use crate::security::SecurityResult;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SessionStoreKind {
+    /* Random tokens tracked in RAM. Supports idle timeout, lost on reboot. */
+    #[default]
+    Memory,
+    /* HMAC-signed tokens with embedded claims. Survive reboot, no idle timeout. */
+    SignedToken,
+}
+
+/**
+ * Authenticated user of access token or API key.
+ */
+#[derive(Clone, Debug)]
+pub struct Session {
+    pub username: String,
+    /* Scopes of API key. None for users logged in with password, they have all permissions of their role. */
+    pub scopes: Option<Vec<Permission>>,
+}
+
+impl Session {
+    pub fn has_scope(&self, permission: Permission) -> bool {
+        self.scopes
+            .as_ref()
+            .map_or(true, |scopes| scopes.contains(&Permission::Admin) || scopes.contains(&permission))
+    }
+}
+
+/**
+ * Issues and validates access tokens. Implemented by server side sessions and by stateless signed tokens.
+ */
+pub trait SessionStore: Send + Sync {
+    fn issue(&self, username: &str) -> SecurityResult<String>;
+
+    fn validate(&self, access_token: &str) -> SecurityResult<Session>;
+
+    fn revoke(&self, access_token: &str) -> SecurityResult<()>;
+
+    /**
+     * Revoke all access tokens of user, e.g. after password change.
+     */
+    fn revoke_user(&self, username: &str) -> SecurityResult<()>;
+
+    fn revoke_all(&self) -> SecurityResult<()>;
+}
@@ -0,0 +1,136 @@
+use crate::constant::{SESSIONS_MAX_COUNT, SESSION_IDLE_TIMEOUT_SECONDS, SESSION_MAX_LIFETIME_SECONDS};
+use crate::security::error::SecurityError;
+use crate::security::session::{Session, SessionStore};
+use crate::security::SecurityResult;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+const ACCESS_TOKEN_LENGTH: usize = 64;
+
+struct StoredSession {
+    username: String,
+    issued_at: Instant,
+    last_used_at: Instant,
+}
+
+impl StoredSession {
+    fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.issued_at) > Duration::from_secs(SESSION_MAX_LIFETIME_SECONDS) ||
+        now.duration_since(self.last_used_at) > Duration::from_secs(SESSION_IDLE_TIMEOUT_SECONDS)
+    }
+}
+
+/**
+ * Random access tokens mapped to sessions in RAM. All sessions are lost on reboot.
+ */
+pub struct MemorySessionStore {
+    sessions: RwLock<HashMap<String, StoredSession>>,
+}
+
+impl Default for MemorySessionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self {
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn generate_access_token() -> String {
+        thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(ACCESS_TOKEN_LENGTH)
+            .map(char::from)
+            .collect()
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn issue(&self, username: &str) -> SecurityResult<String> {
+        let mut sessions = self.sessions
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        let now: Instant = Instant::now();
+        sessions.retain(|_, session| !session.is_expired(now));
+
+        if sessions.len() >= SESSIONS_MAX_COUNT {
+            let oldest_access_token: Option<String> = sessions
+                .iter()
+                .min_by_key(|(_, session)| session.last_used_at)
+                .map(|(access_token, _)| access_token.clone());
+
+            if let Some(oldest_access_token) = oldest_access_token {
+                sessions.remove(&oldest_access_token);
+            }
+        }
+
+        let access_token: String = MemorySessionStore::generate_access_token();
+
+        sessions.insert(access_token.clone(), StoredSession {
+            username: username.to_string(),
+            issued_at: now,
+            last_used_at: now,
+        });
+
+        Ok(access_token)
+    }
+
+    fn validate(&self, access_token: &str) -> SecurityResult<Session> {
+        let mut sessions = self.sessions
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        let now: Instant = Instant::now();
+
+        let session: &mut StoredSession = sessions
+            .get_mut(access_token)
+            .ok_or(SecurityError::InvalidAccessToken)?;
+
+        if session.is_expired(now) {
+            sessions.remove(access_token);
+            return Err(SecurityError::AccessTokenExpired);
+        }
+
+        session.last_used_at = now;
+
+        Ok(Session {
+            username: session.username.clone(),
+            scopes: None,
+        })
+    }
+
+    fn revoke(&self, access_token: &str) -> SecurityResult<()> {
+        self.sessions
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .remove(access_token);
+
+        Ok(())
+    }
+
+    fn revoke_user(&self, username: &str) -> SecurityResult<()> {
+        self.sessions
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .retain(|_, session| session.username != username);
+
+        Ok(())
+    }
+
+    fn revoke_all(&self) -> SecurityResult<()> {
+        self.sessions
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .clear();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use crate::constant::SESSION_MAX_LIFETIME_SECONDS;
+use crate::security::error::SecurityError;
+use crate::security::session::{Session, SessionStore};
+use crate::security::{SecurityContext, SecurityResult};
+use interface::EspError;
+use clock::system_time;
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, RngCore};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SIGNING_KEY_NVS_KEY: &str = "token_key";
+const SIGNING_KEY_LENGTH: usize = 32;
+/* JSON map of username -> time in seconds. Tokens of user issued before or in the same second are revoked. */
+const NOT_BEFORE_NVS_KEY: &str = "token_nbf";
+
+/**
+ * Stateless tokens: hex(claims).hex(HMAC-SHA256(claims)). Claims are "issued_at:expires_at:username" in seconds.
+ * Signing key is stored in NVS, so tokens survive reboot. Revoking all tokens rotates the key.
+ * Single revoked tokens are kept in RAM until they expire. Idle timeout isn't supported, because tokens aren't tracked.
+ * Tokens of single user are revoked by persisted time, until which tokens of this user aren't accepted.
+ */
+pub struct SignedTokenStore {
+    signing_key: RwLock<Vec<u8>>,
+    /* lowercase hex of signature -> expiration time in seconds */
+    revoked_tokens: RwLock<HashMap<String, u64>>,
+    not_before: RwLock<HashMap<String, u64>>,
+}
+
+impl SignedTokenStore {
+    pub fn new() -> Result<Self, EspError> {
+        let signing_key: Option<Vec<u8>> = SecurityContext::nvs_read_str(SIGNING_KEY_NVS_KEY)?
+            .and_then(|signing_key| hex::decode(signing_key).ok());
+
+        /* key is generated on first boot or when stored one is corrupted */
+        let signing_key: Vec<u8> =
+            match signing_key {
+                Some(signing_key) => signing_key,
+                None => SignedTokenStore::rotate_signing_key()?,
+            };
+
+        let not_before: HashMap<String, u64> = SecurityContext::nvs_read_str(NOT_BEFORE_NVS_KEY)?
+            .and_then(|not_before| serde_json::from_str(&not_before).ok())
+            .unwrap_or_default();
+
+        Ok(Self {
+            signing_key: RwLock::new(signing_key),
+            revoked_tokens: RwLock::new(HashMap::new()),
+            not_before: RwLock::new(not_before),
+        })
+    }
+
+    fn rotate_signing_key() -> Result<Vec<u8>, EspError> {
+        let mut signing_key: Vec<u8> = vec![0; SIGNING_KEY_LENGTH];
+        thread_rng().fill_bytes(&mut signing_key);
+
+        SecurityContext::nvs_write_str(SIGNING_KEY_NVS_KEY, &hex::encode(&signing_key))?;
+
+        Ok(signing_key)
+    }
+
+    fn mac(&self) -> SecurityResult<HmacSha256> {
+        let signing_key = self.signing_key
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?;
+
+        HmacSha256::new_from_slice(&signing_key).map_err(|_| SecurityError::InvalidAccessToken)
+    }
+
+    fn now_seconds() -> u64 {
+        system_time::now().as_secs()
+    }
+}
+
+impl SessionStore for SignedTokenStore {
+    fn issue(&self, username: &str) -> SecurityResult<String> {
+        /* token of user revoked in this second would be rejected, so it is issued in the next one */
+        let issued_at: u64 = self.not_before
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?
+            .get(username)
+            .map_or(0, |not_before| not_before + 1)
+            .max(SignedTokenStore::now_seconds());
+        let expires_at: u64 = issued_at + SESSION_MAX_LIFETIME_SECONDS;
+        let claims: String = format!("{issued_at}:{expires_at}:{username}");
+
+        let mut mac: HmacSha256 = self.mac()?;
+        mac.update(claims.as_bytes());
+        let signature: Vec<u8> = mac.finalize().into_bytes().to_vec();
+
+        Ok(format!("{}.{}", hex::encode(claims), hex::encode(signature)))
+    }
+
+    fn validate(&self, access_token: &str) -> SecurityResult<Session> {
+        let (claims, signature) = access_token
+            .split_once('.')
+            .ok_or(SecurityError::InvalidAccessToken)?;
+
+        let claims: Vec<u8> = hex::decode(claims).map_err(|_| SecurityError::InvalidAccessToken)?;
+        let signature_bytes: Vec<u8> = hex::decode(signature).map_err(|_| SecurityError::InvalidAccessToken)?;
+
+        /* verify_slice compares in constant time */
+        let mut mac: HmacSha256 = self.mac()?;
+        mac.update(&claims);
+        mac.verify_slice(&signature_bytes).map_err(|_| SecurityError::InvalidAccessToken)?;
+
+        let claims: String = String::from_utf8(claims).map_err(|_| SecurityError::InvalidAccessToken)?;
+        let mut claims_parts = claims.splitn(3, ':');
+
+        let issued_at: u64 = claims_parts.next().and_then(|part| part.parse().ok()).ok_or(SecurityError::InvalidAccessToken)?;
+        let expires_at: u64 = claims_parts.next().and_then(|part| part.parse().ok()).ok_or(SecurityError::InvalidAccessToken)?;
+        let username: &str = claims_parts.next().ok_or(SecurityError::InvalidAccessToken)?;
+
+        if SignedTokenStore::now_seconds() > expires_at {
+            return Err(SecurityError::AccessTokenExpired);
+        }
+
+        let is_revoked: bool = self.revoked_tokens
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?
+            .contains_key(&hex::encode(&signature_bytes));
+
+        if is_revoked {
+            return Err(SecurityError::InvalidAccessToken);
+        }
+
+        let is_revoked_for_user: bool = self.not_before
+            .read()
+            .map_err(|_| SecurityError::ReadLockError)?
+            .get(username)
+            .is_some_and(|not_before| issued_at <= *not_before);
+
+        if is_revoked_for_user {
+            return Err(SecurityError::InvalidAccessToken);
+        }
+
+        Ok(Session {
+            username: username.to_string(),
+            scopes: None,
+        })
+    }
+
+    fn revoke(&self, access_token: &str) -> SecurityResult<()> {
+        /* hex is case-insensitive, so signature is normalized and other spelling of revoked token is rejected too */
+        let Some(signature) = access_token
+            .split_once('.')
+            .and_then(|(_, signature)| hex::decode(signature).ok())
+            .map(hex::encode) else {
+            return Ok(());
+        };
+
+        let now: u64 = SignedTokenStore::now_seconds();
+
+        let mut revoked_tokens = self.revoked_tokens
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        revoked_tokens.retain(|_, expires_at| *expires_at >= now);
+        revoked_tokens.insert(signature, now + SESSION_MAX_LIFETIME_SECONDS);
+
+        Ok(())
+    }
+
+    fn revoke_user(&self, username: &str) -> SecurityResult<()> {
+        let now: u64 = SignedTokenStore::now_seconds();
+
+        let mut not_before = self.not_before
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?;
+
+        /* entries older than token lifetime don't reject anything */
+        not_before.retain(|_, not_before| *not_before + SESSION_MAX_LIFETIME_SECONDS >= now);
+        not_before.insert(username.to_string(), now);
+
+        let not_before_str: String = serde_json::to_string(&*not_before).unwrap_or_default();
+
+        SecurityContext::nvs_write_str(NOT_BEFORE_NVS_KEY, &not_before_str)
+            .map_err(SecurityError::EspError)
+    }
+
+    fn revoke_all(&self) -> SecurityResult<()> {
+        let signing_key: Vec<u8> = SignedTokenStore::rotate_signing_key()
+            .map_err(SecurityError::EspError)?;
+
+        *self.signing_key
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)? = signing_key;
+
+        self.revoked_tokens
+            .write()
+            .map_err(|_| SecurityError::WriteLockError)?
+            .clear();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+/* Username of account created from single password of older firmware. */
+pub const ADMIN_USERNAME: &str = "admin";
+const USERNAME_MAX_LENGTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Permission {
+    Read,
+    WriteAlarms,
+    Ring,
+    Admin,
+}
+
+/**
+ * Admin manages users, passwords and device settings.
+ * Operator edits alarms and rings outputs manually. Viewer can only read.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Admin,
+    Operator,
+    Viewer,
+}
+
+impl Role {
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Operator => matches!(permission, Permission::Read | Permission::WriteAlarms | Permission::Ring),
+            Role::Viewer => matches!(permission, Permission::Read),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct User {
+    pub username: String,
+    pub role: Role,
+    /* Salted hash, see security::password. */
+    pub password_hash: String,
+    /* Not stored. Known when password is set, derived from hash when users are loaded. */
+    pub has_default_password: bool,
+}
+
+pub fn validate_username(username: &str) -> Result<(), String> {
+    if username.is_empty() || username.len() > USERNAME_MAX_LENGTH {
+        return Err(format!("Username should be 1-{USERNAME_MAX_LENGTH} characters long."));
+    }
+
+    if !username.chars().all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '_') {
+        return Err("Username can contain only latin letters, digits, '-' and '_'.".to_string());
+    }
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use std::sync::{Mutex, RwLock};
+#[cfg(target_os = "espidf")]
 use esp_idf_svc::hal::gpio::{AnyOutputPin, Output, PinDriver};
-use esp_idf_svc::sys::EspError;
+#[cfg(not(target_os = "espidf"))]
+use interface::gpio::Level;
+use interface::EspError;
 use access_point::access_point::AccessPoint;
 use clock::clock::Clock;
 use disk::disk::Disk;
@@ -42,13 +45,17 @@ impl IntoBoxedRwLock for Display<'_> {}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 /* Mutex Output PinDriver */
+#[cfg(target_os = "espidf")]
 pub type MutexOutputPin<'a> = Mutex<PinDriver<'a, AnyOutputPin, Output>>;
+#[cfg(not(target_os = "espidf"))]
+pub type MutexOutputPin<'a> = Mutex<MockOutputPin>;
 
 pub trait IntoMutexOutputPin<'a>
 where Self: Sized {
     fn try_into_mutex_output_pin(self) -> Result<MutexOutputPin<'a>, EspError>;
 }
 
+#[cfg(target_os = "espidf")]
 impl<'a> IntoMutexOutputPin<'a> for AnyOutputPin {
     fn try_into_mutex_output_pin(self) -> Result<MutexOutputPin<'a>, EspError> {
         Ok(
@@ -56,3 +63,53 @@ impl<'a> IntoMutexOutputPin<'a> for AnyOutputPin {
         )
     }
 }
+
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+/* Mock Output Pin */
+/**
+ * Output pin of host build. Level is kept, so tests can check which outputs are ringing.
+ */
+#[cfg(not(target_os = "espidf"))]
+pub struct MockOutputPin {
+    level: Level,
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl MockOutputPin {
+    pub fn new() -> Self {
+        Self { level: Level::Low }
+    }
+
+    pub fn set_level(&mut self, level: Level) -> Result<(), EspError> {
+        self.level = level;
+
+        Ok(())
+    }
+
+    pub fn set_high(&mut self) -> Result<(), EspError> {
+        self.set_level(Level::High)
+    }
+
+    pub fn set_low(&mut self) -> Result<(), EspError> {
+        self.set_level(Level::Low)
+    }
+
+    pub fn is_set_high(&self) -> bool {
+        self.level == Level::High
+    }
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl Default for MockOutputPin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_os = "espidf"))]
+impl<'a> IntoMutexOutputPin<'a> for MockOutputPin {
+    fn try_into_mutex_output_pin(self) -> Result<MutexOutputPin<'a>, EspError> {
+        Ok(Mutex::new(self))
+    }
+}
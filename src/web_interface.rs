@@ -1,79 +1,204 @@
+use crate::constant::{WEB_UI_ACTIVE_SLOT_FILE, WEB_UI_GZIP_DIR, WEB_UI_INDEX_FILE, WEB_UI_SLOT_DIRS};
+use crate::schedule_system::error::ScheduleSystemError;
 use crate::schedule_system::ScheduleSystem;
+use chrono::NaiveDate;
+use embedded_sdmmc::{DirEntry, Timestamp};
+use esp_idf_svc::http::server::{EspHttpConnection, Request};
 use esp_idf_svc::http::Method;
+use esp_idf_svc::io::{EspIOError, Write};
 use esp_idf_svc::sys::EspError;
-use http_server::http_request::{IntoResponse, RequestError};
+use http_server::api_error::ApiError;
+use http_server::cors::get_cors_policy;
+use http_server::http_request::{IntoResponse, RequestError, RequestResult};
 use http_server::http_server::HttpServer;
+use http_server::static_file::{accepts_gzip, ByteRange, StaticFile};
+use interface::disk::path::file_path::FilePath;
 use std::sync::Arc;
-use esp_idf_svc::http::server::Response;
-use esp_idf_svc::io::{EspIOError, Write};
-use mime::Mime;
-use mime_guess::MimeGuess;
-use crate::constant::{SYSTEM_DIR, WEB_UI_DIR};
 
+/**
+ * Static files of web interface are public, because login page is one of them. They are served from active slot
+ * of uploaded web interface, see web_ui_controller.
+ * Files are revalidated with ETag and Last-Modified, precompressed copies are sent to clients accepting gzip
+ * and byte ranges are supported, so large assets can be resumed.
+ */
 pub fn serve(http_server: &mut HttpServer, schedule_system: Arc<ScheduleSystem>) -> Result<(), EspError> {
-    let ui_files_location: String = format!("/{SYSTEM_DIR}/{WEB_UI_DIR}");
+    http_server.add_handler("/*?", Method::Get, move |request| {
+        serve_file(request, &schedule_system)
+    })
+}
 
-    let schedule_system_clone: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
+fn serve_file(request: Request<&mut EspHttpConnection>, schedule_system: &ScheduleSystem) -> RequestResult<(), EspIOError> {
+    let uri_path: String = request.uri().split(['?', '#']).next().unwrap_or_default().to_string();
 
-    http_server.add_handler("/*?", Method::Get, move |request| {
-        let filepath: &str =
-            match request.uri().rsplit_once(".") {
-                None => "/",
-                Some((_, extension)) => {
-                    if extension.contains("/") {
-                        "/"
-                    } else {
-                        request.uri()
-                    }
-                }
-            };
-
-        let filepath: String = match filepath {
-            "/" => String::from("/index.htm"),
-            rest => String::from(rest),
+    let segments: Vec<&str> = uri_path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    /* files outside of web interface directory must not be reachable */
+    if segments.iter().any(|segment| *segment == "." || *segment == "..") {
+        return request.error(&ApiError::bad_request("invalid_path", "Path can't contain '.' or '..' segments."));
+    }
+
+    /* before first activation files are served from WEB_UI_DIR, which holds slots too, so staging must not leak.
+       FAT names are case-insensitive and trailing dots are ignored. */
+    let is_reserved: bool = segments.first().is_some_and(|segment| {
+        let segment: &str = segment.trim_end_matches('.');
+
+        WEB_UI_SLOT_DIRS
+            .iter()
+            .chain([&WEB_UI_ACTIVE_SLOT_FILE])
+            .any(|reserved| segment.eq_ignore_ascii_case(reserved))
+    });
+
+    if is_reserved {
+        return request.error(&ApiError::not_found("file_not_found", format!("File {uri_path} not found.")));
+    }
+
+    /* paths without extension are routes of web interface, which are handled by index page */
+    let (directories, filename): (&[&str], &str) =
+        match segments.split_last() {
+            Some((filename, directories)) if filename.contains('.') => (directories, *filename),
+            _ => (&[], WEB_UI_INDEX_FILE),
         };
 
-        let Ok(path) = format!("{ui_files_location}{filepath}").as_str().try_into() else {
-            return request.bad_request(&format!("Can't build file path |{ui_files_location}{filepath}|!"))
+    let mut directories_path: Vec<&str> = schedule_system.web_ui_dir();
+    directories_path.extend_from_slice(directories);
+
+    let plain_path: FilePath = (directories_path.as_slice(), filename).into();
+
+    directories_path.push(WEB_UI_GZIP_DIR);
+    let gzip_path: FilePath = (directories_path.as_slice(), filename).into();
+
+    let gzip_file: Option<DirEntry> =
+        if accepts_gzip(request.header("Accept-Encoding")) {
+            find_file(schedule_system, &gzip_path, &uri_path)?
+        } else {
+            None
         };
-        println!("{:?}", path);
 
-        let Some(guess) = mime_guess::from_path(filepath).first() else {
-            return request.bad_request(&format!("Can't build file |{ui_files_location}"))
+    let (path, dir_entry, gzip): (FilePath, DirEntry, bool) =
+        match gzip_file {
+            Some(dir_entry) => (gzip_path, dir_entry, true),
+            None => match find_file(schedule_system, &plain_path, &uri_path)? {
+                Some(dir_entry) => (plain_path, dir_entry, false),
+                None => return request.error(&ApiError::not_found("file_not_found", format!("File {uri_path} not found."))),
+            },
         };
 
-        let mime_type: String = format!("{}; charset=utf-8", guess);
-        println!("{:?}", mime_type);
+    let file: StaticFile = StaticFile {
+        length: dir_entry.size as u64,
+        modified_seconds: timestamp_to_seconds(&dir_entry.mtime),
+        gzip,
+    };
 
-        let headers = &[
-            ("Content-Type", mime_type.as_str()),
+    let etag: String = file.etag();
+    let last_modified: Option<String> = file.last_modified();
+    let content_type: String = content_type(filename);
+    let cors_headers: Vec<(&'static str, String)> = get_cors_policy().response_headers(request.header("Origin"));
 
-            ("Access-Control-Allow-Origin", "*"),
-            ("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE, PATCH"),
-            ("Access-Control-Allow-Headers", "Access-Control-Allow-Headers, Origin,Accept, X-Requested-With, Content-Type, Access-Token, Access-Control-Request-Method, Access-Control-Request-Headers"),
-        ];
+    let mut headers: Vec<(&str, &str)> = vec![
+        ("ETag", etag.as_str()),
+        /* files can be replaced by upload at any time, so cached copy is always revalidated */
+        ("Cache-Control", "no-cache"),
+        ("Vary", "Accept-Encoding"),
+    ];
+    headers.extend(cors_headers.iter().map(|(name, value)| (*name, value.as_str())));
 
-        let mut response = request
-            .into_response(200, Some("Sending chunks"), headers)
+    if let Some(last_modified) = &last_modified {
+        headers.push(("Last-Modified", last_modified.as_str()));
+    }
+
+    if file.is_not_modified(request.header("If-None-Match"), request.header("If-Modified-Since")) {
+        request
+            .into_response(304, Some("Not Modified"), &headers)
             .map_err(RequestError::Connection)?;
 
+        return Ok(());
+    }
 
-        let _ = schedule_system_clone.read_from_file_bytes(
-            &path,
-            64 * 1024,
-            |buffer, bytes_read| {
-                match response.write(&buffer[0..bytes_read]) {
-                    Ok(written_bytes) => {
-                        println!("{written_bytes} bytes written");
-                        Ok(())
-                    }
-                    Err(error) => {
-                        eprintln!("{error}");
-                        Err(())
-                    }
-                }
-            });
-
-        response.flush().map_err(RequestError::Connection)
-    })
+    headers.push(("Content-Type", content_type.as_str()));
+    headers.push(("Accept-Ranges", "bytes"));
+
+    if gzip {
+        headers.push(("Content-Encoding", "gzip"));
+    }
+
+    let (status, message, start, end): (u16, &str, u64, u64) =
+        match file.range(request.header("Range"), request.header("If-Range")) {
+            ByteRange::Full => (200, "OK", 0, file.length.saturating_sub(1)),
+            ByteRange::Partial { start, end } => (206, "Partial Content", start, end),
+            ByteRange::Unsatisfiable => {
+                let content_range: String = format!("bytes */{}", file.length);
+                headers.push(("Content-Range", content_range.as_str()));
+
+                request
+                    .into_response(416, Some("Range Not Satisfiable"), &headers)
+                    .map_err(RequestError::Connection)?;
+
+                return Ok(());
+            }
+        };
+
+    let content_range: String = format!("bytes {start}-{end}/{}", file.length);
+
+    if status == 206 {
+        headers.push(("Content-Range", content_range.as_str()));
+    }
+
+    let mut response = request
+        .into_response(status, Some(message), &headers)
+        .map_err(RequestError::Connection)?;
+
+    /* empty file has no bytes to read */
+    let length: u32 = if file.length == 0 { 0 } else { (end - start + 1) as u32 };
+
+    /* headers are already sent, so failed read can only be logged and response is cut short */
+    let read_result = schedule_system.read_from_file_range(&path, start as u32, length, |buffer| {
+        response
+            .write_all(buffer)
+            .map_err(|error| log::warn!("Can't send file {uri_path}: {error}"))
+    });
+
+    if let Err(error) = read_result {
+        log::error!("Can't read file {uri_path}: {error:?}");
+    }
+
+    response.flush().map_err(RequestError::Connection)
+}
+
+/* missing file or directory is not an error, any other disk failure is */
+fn find_file(schedule_system: &ScheduleSystem, path: &FilePath, uri_path: &str) -> RequestResult<Option<DirEntry>, EspIOError> {
+    match schedule_system.get_file_info(path) {
+        Ok(dir_entry) if !dir_entry.attributes.is_directory() => Ok(Some(dir_entry)),
+        Ok(_) | Err(ScheduleSystemError::DiskError(embedded_sdmmc::Error::NotFound)) => Ok(None),
+        Err(error) => Err(RequestError::General(format!("Can't read file {uri_path}: {error:?}"))),
+    }
+}
+
+/* FAT stores local time without zone, device clock is kept in UTC. Invalid date means time is unknown. */
+fn timestamp_to_seconds(timestamp: &Timestamp) -> Option<u64> {
+    NaiveDate::from_ymd_opt(
+        1970 + timestamp.year_since_1970 as i32,
+        timestamp.zero_indexed_month as u32 + 1,
+        timestamp.zero_indexed_day as u32 + 1,
+    )
+        .and_then(|date| date.and_hms_opt(timestamp.hours as u32, timestamp.minutes as u32, timestamp.seconds as u32))
+        .map(|datetime| datetime.and_utc().timestamp().max(0) as u64)
+}
+
+/* charset is meaningful for text formats only, unknown files are sent as binary */
+fn content_type(filename: &str) -> String {
+    let Some(guess) = mime_guess::from_path(filename).first() else {
+        return String::from("application/octet-stream");
+    };
+
+    let is_text: bool = guess.type_() == mime::TEXT
+        || matches!(guess.subtype().as_str(), "javascript" | "json" | "xml" | "svg+xml");
+
+    if is_text {
+        format!("{guess}; charset=utf-8")
+    } else {
+        guess.to_string()
+    }
 }
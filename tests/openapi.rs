@@ -0,0 +1,35 @@
+use automatic_bell_system::rest_interface::RestApi;
+use automatic_bell_system::schedule_system::ScheduleSystem;
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use http_server::http_request::method_name;
+use http_server::mock_connection::{MockConnection, MockConnections, MockRouter};
+use http_server::openapi::undocumented_routes;
+use http_server::router::{served_routes, Api, Route};
+use serde_json::Value;
+use std::sync::Arc;
+
+#[test]
+fn every_route_of_rest_api_has_schema() {
+    assert_eq!(undocumented_routes(&RestApi::routes::<MockConnections>()), Vec::<String>::new());
+}
+
+#[test]
+fn specification_is_served_with_every_route() {
+    let router: MockRouter<RestApi> = MockRouter::new(Arc::new(ScheduleSystem::new_mock().unwrap()));
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/openapi.json");
+
+    router.handle(Request::wrap(&mut connection)).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let specification: Value = connection.response_json().unwrap();
+
+    let routes: Vec<Route<MockConnections, RestApi>> = RestApi::routes::<MockConnections>();
+
+    /* routes inherited by newer API version are served, so they are documented too */
+    for (path, route) in served_routes(&routes) {
+        let method: String = method_name(route.method()).to_lowercase();
+
+        assert!(specification["paths"][&path][&method].is_object(), "{method} {path} is missing in specification");
+    }
+}
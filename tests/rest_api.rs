@@ -0,0 +1,219 @@
+use automatic_bell_system::rest_interface::RestApi;
+use automatic_bell_system::schedule_system::ScheduleSystem;
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use http_server::api_error::ApiError;
+use http_server::http_request::RequestResult;
+use http_server::mock_connection::{MockConnection, MockRouter};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/* default credentials of admin, they are kept by every test, because users are stored in NVS shared by process */
+const ADMIN_USERNAME: &str = "admin";
+const ADMIN_PASSWORD: &str = "scheduler-rs";
+
+/**
+ * Router of REST interface with schedule system on in-memory devices.
+ */
+fn rest_api() -> MockRouter<RestApi> {
+    let schedule_system: ScheduleSystem = ScheduleSystem::new_mock().unwrap();
+
+    MockRouter::new(Arc::new(schedule_system))
+}
+
+fn send(router: &MockRouter<RestApi>, connection: &mut MockConnection) -> RequestResult<(), embedded_svc::io::ErrorKind> {
+    router.handle(Request::wrap(connection))
+}
+
+fn login(router: &MockRouter<RestApi>) -> String {
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/api/v1/login")
+        .with_json(&json!({ "username": ADMIN_USERNAME, "password": ADMIN_PASSWORD }));
+
+    send(router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    connection.response_text()
+}
+
+fn authorized(method: Method, uri: &str, access_token: &str) -> MockConnection {
+    MockConnection::new(method, uri).with_header("Authorization", &format!("Bearer {access_token}"))
+}
+
+/**
+ * Errors of handlers are written by ErrorResponder middleware of server, so router returns them.
+ */
+fn send_failing(router: &MockRouter<RestApi>, connection: &mut MockConnection) -> ApiError {
+    send(router, connection).unwrap_err().to_api_error()
+}
+
+fn alarm_at(hour: u8, minute: u8) -> Value {
+    json!({
+        "year": { "tag": "Ignore" },
+        "month": { "tag": "Ignore" },
+        "month_day": { "tag": "Ignore" },
+        "week_day": { "tag": "Match", "segments": ["Monday", "Friday"] },
+        "hour": { "tag": "Match", "segments": [hour] },
+        "minute": { "tag": "Match", "segments": [minute] },
+        "second": { "tag": "Match", "segments": [0] },
+        "impulse_length_millis": 3000,
+    })
+}
+
+#[test]
+fn request_without_access_token_is_rejected_with_401() {
+    let router: MockRouter<RestApi> = rest_api();
+    let mut connection: MockConnection = MockConnection::new(Method::Get, "/api/v1/clock");
+
+    send(&router, &mut connection).unwrap();
+
+    assert_eq!(connection.status(), Some(401));
+}
+
+#[test]
+fn wrong_password_is_rejected() {
+    let router: MockRouter<RestApi> = rest_api();
+    let mut connection: MockConnection = MockConnection::new(Method::Post, "/api/v1/login")
+        .with_json(&json!({ "username": "nobody", "password": "wrong-password" }))
+        .with_client_ip("192.168.71.20".parse().unwrap());
+
+    send(&router, &mut connection).unwrap();
+
+    assert_eq!(connection.status(), Some(401));
+}
+
+#[test]
+fn clock_is_set_and_read() {
+    let router: MockRouter<RestApi> = rest_api();
+    let access_token: String = login(&router);
+    let timestamp_millis: i64 = 1_767_261_600_000;
+
+    let mut connection: MockConnection = authorized(Method::Put, "/api/v1/clock", &access_token)
+        .with_json(&json!({ "timestamp_millis": timestamp_millis }));
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Get, "/api/v1/clock", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let clock: Value = connection.response_json().unwrap();
+    let read_timestamp_millis: i64 = clock["timestamp_millis"].as_i64().unwrap();
+
+    /* clock keeps running between requests */
+    assert!((timestamp_millis..timestamp_millis + 5_000).contains(&read_timestamp_millis), "{read_timestamp_millis}");
+}
+
+#[test]
+fn alarm_is_added_listed_and_removed() {
+    let router: MockRouter<RestApi> = rest_api();
+    let access_token: String = login(&router);
+
+    let mut connection: MockConnection = authorized(Method::Post, "/api/v1/outputs/1/alarms", &access_token)
+        .with_json(&alarm_at(8, 30));
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Get, "/api/v1/outputs/1/alarms", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let alarms: Vec<Value> = connection.response_json().unwrap();
+    assert_eq!(alarms.len(), 1);
+    assert_eq!(alarms[0]["alarm"]["hour"], json!({ "tag": "Match", "segments": [8] }));
+
+    let identifier: &str = alarms[0]["id"]["identifier"].as_str().unwrap();
+
+    let mut connection: MockConnection = authorized(Method::Delete, &format!("/api/v1/outputs/1/alarms/{identifier}"), &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Get, &format!("/api/v1/outputs/1/alarms/{identifier}"), &access_token);
+    let error: ApiError = send_failing(&router, &mut connection);
+    assert_eq!((error.status, error.code), (404, "alarm_not_found"));
+}
+
+#[test]
+fn alarm_of_missing_output_is_rejected() {
+    let router: MockRouter<RestApi> = rest_api();
+    let access_token: String = login(&router);
+
+    let mut connection: MockConnection = authorized(Method::Post, "/api/v1/outputs/7/alarms", &access_token)
+        .with_json(&alarm_at(8, 30));
+    let error: ApiError = send_failing(&router, &mut connection);
+
+    assert_eq!((error.status, error.code), (400, "output_index_out_of_bounds"));
+}
+
+#[test]
+fn settings_are_updated() {
+    let router: MockRouter<RestApi> = rest_api();
+    let access_token: String = login(&router);
+
+    let mut connection: MockConnection = authorized(Method::Patch, "/api/v1/settings", &access_token)
+        .with_json(&json!({ "access_point_ssid": "Bell of school 7", "display_refresh_interval_ms": 500 }));
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Get, "/api/v1/settings", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let settings: Value = connection.response_json().unwrap();
+    assert_eq!(settings["access_point_ssid"], "Bell of school 7");
+    assert_eq!(settings["display_refresh_interval_ms"], 500);
+}
+
+#[test]
+fn web_ui_is_uploaded_to_staging_and_activated() {
+    let router: MockRouter<RestApi> = rest_api();
+    let access_token: String = login(&router);
+    let index: &[u8] = b"<!DOCTYPE html><title>Bells</title>";
+
+    let mut connection: MockConnection = authorized(Method::Put, "/api/v1/web-ui/staging/files?path=index.htm", &access_token)
+        .with_header("Content-Type", "application/octet-stream")
+        .with_body(index);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Post, "/api/v1/web-ui/staging/activate", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Get, "/api/v1/web-ui/files", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let files: Value = connection.response_json().unwrap();
+    assert_eq!(files, json!([{ "path": "INDEX.HTM", "size": index.len() }]));
+}
+
+#[test]
+fn staging_changes_are_audited() {
+    let router: MockRouter<RestApi> = rest_api();
+    let access_token: String = login(&router);
+
+    let mut connection: MockConnection = authorized(Method::Put, "/api/v1/web-ui/staging/files?path=app.js", &access_token)
+        .with_header("Content-Type", "application/octet-stream")
+        .with_body(b"console.log(1);");
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Delete, "/api/v1/web-ui/staging", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let mut connection: MockConnection = authorized(Method::Get, "/api/v1/audit?action=WebUiChanged", &access_token);
+    send(&router, &mut connection).unwrap();
+    assert_eq!(connection.status(), Some(200));
+
+    let page: Value = connection.response_json().unwrap();
+    let details: Vec<&str> = page["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["details"].as_str().unwrap())
+        .collect();
+
+    assert!(details.contains(&"File 'app.js' (15 bytes) uploaded to staging."), "{details:?}");
+    assert!(details.contains(&"Staging cleared."), "{details:?}");
+}
@@ -0,0 +1,54 @@
+use automatic_bell_system::schedule_system::alarm_id::AlarmId;
+use automatic_bell_system::schedule_system::ScheduleSystem;
+use clock::alarm::{Alarm, AlarmMatcher};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+
+const ALARMS_COUNT: usize = 50;
+
+fn alarm() -> Alarm {
+    Alarm {
+        year: AlarmMatcher::Ignore,
+        month: AlarmMatcher::Ignore,
+        month_day: AlarmMatcher::Ignore,
+        week_day: AlarmMatcher::Ignore,
+        hour: AlarmMatcher::Match(HashSet::from([8])),
+        minute: AlarmMatcher::Match(HashSet::from([30])),
+        second: AlarmMatcher::Match(HashSet::from([0])),
+        impulse_length_millis: 3000,
+    }
+}
+
+fn alarm_ids(schedule_system: &ScheduleSystem) -> HashSet<AlarmId> {
+    schedule_system.get_alarms().unwrap().into_keys().collect()
+}
+
+#[test]
+fn alarms_added_while_profile_is_switched_are_stored_in_profile_they_were_added_to() {
+    let schedule_system: Arc<ScheduleSystem> = Arc::new(ScheduleSystem::new_mock().unwrap());
+
+    let switching_system: Arc<ScheduleSystem> = Arc::clone(&schedule_system);
+    let switching = thread::spawn(move || {
+        for _ in 0..ALARMS_COUNT {
+            switching_system.switch_profile(1).unwrap();
+            switching_system.switch_profile(0).unwrap();
+        }
+    });
+
+    for _ in 0..ALARMS_COUNT {
+        schedule_system.add_alarm(0, alarm()).unwrap();
+    }
+
+    switching.join().unwrap();
+
+    /* alarms of clock are the same as alarms read from disk of active profile */
+    let profile_alarms: HashSet<AlarmId> = alarm_ids(&schedule_system);
+    schedule_system.switch_profile(0).unwrap();
+    assert_eq!(alarm_ids(&schedule_system), profile_alarms);
+
+    schedule_system.switch_profile(1).unwrap();
+    let other_profile_alarms: HashSet<AlarmId> = alarm_ids(&schedule_system);
+
+    assert_eq!(profile_alarms.len() + other_profile_alarms.len(), ALARMS_COUNT);
+}
@@ -0,0 +1,49 @@
+use automatic_bell_system::rest_interface::SetupGuard;
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Method;
+use embedded_svc::io::ErrorKind;
+use http_server::http_request::{IntoResponse, RequestResult};
+use http_server::middleware::{Middleware, Next};
+use http_server::mock_connection::MockConnection;
+use std::sync::Arc;
+
+fn handler(request: Request<&mut MockConnection>) -> RequestResult<(), ErrorKind> {
+    request.ok(&"handled")
+}
+
+/* passwords are never changed in this test, so default ones keep setup required */
+fn send(method: Method, uri: &str) -> MockConnection {
+    let middlewares: Vec<Arc<dyn Middleware<MockConnection>>> = vec![Arc::new(SetupGuard)];
+    let mut connection: MockConnection = MockConnection::new(method, uri);
+
+    Next::new(&middlewares, &handler).run(Request::wrap(&mut connection)).unwrap();
+
+    connection
+}
+
+#[test]
+fn only_login_and_setup_are_allowed_before_setup() {
+    let allowed: [(Method, &str); 5] = [
+        (Method::Post, "/api/v1/login"),
+        (Method::Get, "/api/v1/setup"),
+        (Method::Post, "/api/v1/setup"),
+        (Method::Get, "/api/v1/password-policy"),
+        (Method::Get, "/index.htm"),
+    ];
+
+    for (method, uri) in allowed {
+        assert_eq!(send(method, uri).status(), Some(200), "{uri}");
+    }
+
+    let rejected: [(Method, &str); 5] = [
+        (Method::Put, "/api/v1/password-policy"),
+        (Method::Post, "/api/v1/logout"),
+        (Method::Get, "/api/v1/access-token-validity"),
+        (Method::Get, "/api/v1/openapi.json"),
+        (Method::Get, "/api/v1/clock"),
+    ];
+
+    for (method, uri) in rejected {
+        assert_eq!(send(method, uri).status(), Some(403), "{uri}");
+    }
+}
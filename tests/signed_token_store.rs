@@ -0,0 +1,19 @@
+use automatic_bell_system::security::error::SecurityError;
+use automatic_bell_system::security::session::signed_token_store::SignedTokenStore;
+use automatic_bell_system::security::session::SessionStore;
+
+#[test]
+fn token_issued_in_second_of_user_revocation_is_rejected() {
+    let store: SignedTokenStore = SignedTokenStore::new().unwrap();
+
+    let access_token: String = store.issue("operator").unwrap();
+    assert_eq!(store.validate(&access_token).unwrap().username, "operator");
+
+    /* revoked and checked again within the same second */
+    store.revoke_user("operator").unwrap();
+    assert!(matches!(store.validate(&access_token), Err(SecurityError::InvalidAccessToken)));
+
+    /* replacement token, e.g. after password change, is accepted immediately */
+    let new_access_token: String = store.issue("operator").unwrap();
+    assert_eq!(store.validate(&new_access_token).unwrap().username, "operator");
+}